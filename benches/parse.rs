@@ -0,0 +1,43 @@
+//! Compares the time taken to parse a source with the crate's own parser
+//! and with the pest grammar, run with `cargo bench`.
+
+use new_pkl::{
+    pest::{parse, Rule},
+    Pkl,
+};
+use std::{fs, time::Instant};
+
+fn main() {
+    let src = fs::read_to_string("main.pkl").unwrap().repeat(1000);
+    let time = Instant::now();
+
+    let pkl = Pkl::new();
+    if let Err(e) = pkl.generate_ast(&src) {
+        panic!("failed to parse main.pkl: {}", e.msg());
+    }
+
+    println!(
+        "{}ms to generate the ast of {} chars",
+        time.elapsed().as_millis(),
+        src.len()
+    );
+
+    let src = fs::read_to_string("a.pkl").unwrap();
+    let time = Instant::now();
+
+    let mut result = parse(&src).unwrap();
+    let file = result.next().unwrap().into_inner();
+
+    // the pairs are lazily built, walk them so that they are all visited
+    let comments = file
+        .flatten()
+        .filter(|pair| pair.as_rule() == Rule::COMMENT)
+        .count();
+
+    println!(
+        "{}ms to parse {} chars with pest, {} comment(s) found",
+        time.elapsed().as_millis(),
+        src.len(),
+        comments
+    );
+}
@@ -1,3 +1,4 @@
+use crate::lexer::LexingError;
 use logos::Span;
 
 /// Represents a parsing error in the PKL format.
@@ -7,18 +8,96 @@ use logos::Span;
 /// * `String` - A message describing the error.
 /// * `Span` - The span in the source where the error occurred.
 /// * `Option<String>` - The name of the file in which the error occurs.
+///
+/// `UnknownMember` carries the same data as `WithContext`,
+/// it tells apart a member that does not exist at all from other errors.
+///
+/// `TypeMismatch` carries the same data as well, it is reported when a value
+/// does not correspond to the type it is declared or used with.
+///
+/// `Syntax` and `Lexing` carry the same data too, they are reported when the source
+/// cannot be parsed and when it contains an invalid token, such as an out of range literal.
+///
+/// `Unsupported` is reported when valid Pkl uses a construct
+/// that is not supported yet, rather than because of a mistake in the source.
+///
+/// `Imported` wraps an error that occurred in an imported, amended or extended module,
+/// along with the source of that module, so that the span of the wrapped error can be
+/// paired with the right source. Its own span is the one of the import statement.
 pub enum PklError {
     WithContext(String, Span, Option<String>),
     WithoutContext(String, Option<String>),
+    UnknownMember(String, Span, Option<String>),
+    TypeMismatch(String, Span, Option<String>),
+    Syntax(String, Span, Option<String>),
+    Lexing(String, Span, Option<String>),
+    Unsupported {
+        feature: String,
+        span: Span,
+        file_name: Option<String>,
+    },
+    Imported {
+        error: Box<PklError>,
+        source: String,
+        import_span: Span,
+        file_name: Option<String>,
+    },
 }
 
 impl PklError {
     pub fn new(msg: String, span: Span) -> Self {
         Self::WithContext(msg, span, None)
     }
+    pub fn unsupported(feature: impl Into<String>, span: Span) -> Self {
+        Self::Unsupported {
+            feature: feature.into(),
+            span,
+            file_name: None,
+        }
+    }
+    pub fn type_mismatch(msg: String, span: Span) -> Self {
+        Self::TypeMismatch(msg, span, None)
+    }
+    /// Turns an error raised while parsing into a syntax error,
+    /// errors of a more specific kind being kept as they are.
+    pub(crate) fn into_syntax(self) -> Self {
+        match self {
+            PklError::WithContext(msg, span, file_name) => PklError::Syntax(msg, span, file_name),
+            error => error,
+        }
+    }
+    /// Builds the error of a source named `name`, such as the path of an imported file,
+    /// that could not be read, reported on `span` when it is read by a statement.
+    pub fn unreadable(
+        name: impl std::fmt::Display,
+        error: std::io::Error,
+        span: Option<Span>,
+    ) -> Self {
+        let msg = format!("Error reading {}: {}", name, error);
+        match span {
+            Some(span) => Self::WithContext(msg, span, None),
+            None => Self::WithoutContext(msg, None),
+        }
+    }
+    /// Wraps an error that occurred in the module named `file_name`,
+    /// imported by a statement located at `import_span`.
+    pub fn imported_from(self, file_name: String, source: String, import_span: Span) -> Self {
+        Self::Imported {
+            error: Box::new(self.with_file_name(file_name)),
+            source,
+            import_span,
+            file_name: None,
+        }
+    }
     pub fn with_file_name(mut self, name: String) -> Self {
         match &mut self {
-            PklError::WithContext(_, _, n) => *n = Some(name),
+            PklError::WithContext(_, _, n)
+            | PklError::UnknownMember(_, _, n)
+            | PklError::TypeMismatch(_, _, n)
+            | PklError::Syntax(_, _, n)
+            | PklError::Lexing(_, _, n)
+            | PklError::Unsupported { file_name: n, .. }
+            | PklError::Imported { file_name: n, .. } => *n = Some(name),
             PklError::WithoutContext(_, n) => *n = Some(name),
         };
         self
@@ -26,22 +105,88 @@ impl PklError {
 
     pub fn msg(&self) -> &str {
         match self {
-            PklError::WithContext(m, _, _) => m,
+            PklError::WithContext(m, _, _)
+            | PklError::UnknownMember(m, _, _)
+            | PklError::TypeMismatch(m, _, _)
+            | PklError::Syntax(m, _, _)
+            | PklError::Lexing(m, _, _)
+            | PklError::Unsupported { feature: m, .. } => m,
             PklError::WithoutContext(m, _) => m,
+            PklError::Imported { error, .. } => error.msg(),
         }
     }
     pub fn file_name(&self) -> &Option<String> {
         match self {
-            PklError::WithContext(_, _, n) => n,
+            PklError::WithContext(_, _, n)
+            | PklError::UnknownMember(_, _, n)
+            | PklError::TypeMismatch(_, _, n)
+            | PklError::Syntax(_, _, n)
+            | PklError::Lexing(_, _, n)
+            | PklError::Unsupported { file_name: n, .. }
+            | PklError::Imported { file_name: n, .. } => n,
             PklError::WithoutContext(_, n) => n,
         }
     }
     pub fn span(&self) -> Option<Span> {
         match self {
-            PklError::WithContext(_, span, _) => Some(span.to_owned()),
+            PklError::WithContext(_, span, _)
+            | PklError::UnknownMember(_, span, _)
+            | PklError::TypeMismatch(_, span, _)
+            | PklError::Syntax(_, span, _)
+            | PklError::Lexing(_, span, _)
+            | PklError::Unsupported { span, .. }
+            | PklError::Imported {
+                import_span: span, ..
+            } => Some(span.to_owned()),
             PklError::WithoutContext(_, _) => None,
         }
     }
+
+    /// Returns a stable code identifying the kind of the error,
+    /// such as `pkl_fast::type_mismatch`, to branch on without matching messages.
+    ///
+    /// The code of an imported error is the one of the error that occurred in the imported module.
+    /// Errors without context, such as those of the typed getters, have no code.
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            PklError::WithContext(_, _, _) => Some("pkl_fast::error"),
+            PklError::UnknownMember(_, _, _) => Some("pkl_fast::unknown_member"),
+            PklError::TypeMismatch(_, _, _) => Some("pkl_fast::type_mismatch"),
+            PklError::Syntax(_, _, _) => Some("pkl_fast::syntax"),
+            PklError::Lexing(_, _, _) => Some("pkl_fast::lexing"),
+            PklError::Unsupported { .. } => Some("pkl_fast::unsupported"),
+            PklError::Imported { error, .. } => error.code(),
+            PklError::WithoutContext(_, _) => None,
+        }
+    }
+
+    /// Returns the names of the modules the error went through,
+    /// from the module it occurred in up to the outermost importing one.
+    pub fn import_chain(&self) -> Vec<&str> {
+        let mut chain = match self {
+            PklError::Imported { error, .. } => error.import_chain(),
+            _ => Vec::new(),
+        };
+
+        if let Some(name) = self.file_name() {
+            chain.push(name);
+        }
+        chain
+    }
+}
+
+/// A non-fatal diagnostic emitted while building a table,
+/// such as a lint that is not respected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PklWarning {
+    pub message: String,
+    pub span: Span,
+}
+
+impl PklWarning {
+    pub fn new(message: String, span: Span) -> Self {
+        Self { message, span }
+    }
 }
 
 /// A result type for PKL parsing operations.
@@ -55,8 +200,171 @@ impl From<(String, Span)> for PklError {
         Self::WithContext(value.0, value.1, None)
     }
 }
+impl From<(LexingError, Span)> for PklError {
+    fn from(value: (LexingError, Span)) -> Self {
+        Self::Lexing(value.0.to_string(), value.1, None)
+    }
+}
 impl From<(String, Span, String)> for PklError {
     fn from(value: (String, Span, String)) -> Self {
         Self::WithContext(value.0, value.1, Some(value.2))
     }
 }
+
+#[cfg(feature = "miette")]
+impl PklError {
+    /// Converts the error into a `miette` report,
+    /// its span being highlighted in `source` when rendered.
+    pub fn into_report(self, source: impl Into<String>) -> miette::Report {
+        use miette::{LabeledSpan, MietteDiagnostic, NamedSource};
+
+        // the error is rendered against the source of the module it occurred in
+        let chain = self.import_chain().join(" <- ");
+        let error = match self.into_origin() {
+            (error, Some(module_source)) => {
+                return error
+                    .into_report(module_source)
+                    .wrap_err(format!("Error in imported module {}", chain))
+            }
+            (error, None) => error,
+        };
+
+        let mut diagnostic = MietteDiagnostic::new(error.msg());
+        if let Some(span) = error.span() {
+            diagnostic = diagnostic.with_label(LabeledSpan::at(span, "here"));
+        }
+        if let Some(code) = error.code() {
+            diagnostic = diagnostic.with_code(code);
+        }
+        if let PklError::Unsupported { .. } = error {
+            diagnostic = diagnostic.with_help("this is valid Pkl that is not supported yet");
+        }
+
+        let file_name = error
+            .file_name()
+            .to_owned()
+            .unwrap_or_else(|| "<source>".to_owned());
+
+        miette::Report::new(diagnostic).with_source_code(NamedSource::new(file_name, source.into()))
+    }
+
+    /// Returns the error that occurred in the innermost imported module
+    /// along with the source of that module, or the error itself if it is not imported.
+    fn into_origin(self) -> (PklError, Option<String>) {
+        match self {
+            PklError::Imported { error, source, .. } => match error.into_origin() {
+                (error, None) => (error, Some(source)),
+                origin => origin,
+            },
+            error => (error, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PklError;
+    use crate::test_utils::{eval_err, parse_err};
+
+    #[test]
+    fn unsupported_constructs_are_told_apart_from_mistakes() {
+        for source in [
+            "class A {\n  x: Int(isPositive)\n}",
+            "x: Int(isPositive) = 1",
+            "local class A {}",
+        ] {
+            let error = parse_err(source);
+            assert!(
+                matches!(error, PklError::Unsupported { .. }),
+                "{source}: {}",
+                error.msg()
+            );
+        }
+
+        let error = parse_err("local local a = 1");
+        assert!(
+            !matches!(error, PklError::Unsupported { .. }),
+            "{}",
+            error.msg()
+        );
+    }
+
+    #[test]
+    fn errors_of_imported_modules_keep_their_source() {
+        let module = "a = 1\nb = a + \"x\"";
+        let mut pkl = crate::Pkl::new();
+        pkl.register_module("broken", module);
+
+        let importing = "import \"mem:broken\"";
+        let error = pkl.parse(importing).err().unwrap();
+        let PklError::Imported {
+            error: inner,
+            source,
+            import_span,
+            ..
+        } = &error
+        else {
+            panic!("expected an imported error, got {}", error.msg());
+        };
+
+        assert_eq!(source, module);
+        assert_eq!(&importing[import_span.to_owned()], importing);
+        assert_eq!(&module[inner.span().unwrap()], "a + \"x\"");
+        assert_eq!(error.import_chain(), ["mem:broken"]);
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn errors_of_imported_modules_render_against_their_source() {
+        let mut pkl = crate::Pkl::new();
+        pkl.register_module("broken", "a = 1\nb = a + \"x\"");
+
+        let error = pkl.parse("import \"mem:broken\"").err().unwrap();
+        let rendered = format!("{:?}", error.into_report("import \"mem:broken\""));
+        assert!(
+            rendered.contains("Error in imported module mem:broken"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("b = a + \"x\""), "{rendered}");
+    }
+
+    #[test]
+    fn errors_report_the_code_of_their_kind() {
+        let type_mismatches = [
+            parse_err("a: Int = \"x\""),
+            eval_err("1 + \"a\""),
+            eval_err("!1"),
+            eval_err("-\"a\""),
+            eval_err("true && 1"),
+            eval_err("\"ab\".padStart(\"3\", \"x\")"),
+            eval_err("List(1, \"a\").sum()"),
+            parse_err("when (1) {\n  a = 1\n}"),
+        ];
+        for error in type_mismatches {
+            assert_eq!(
+                error.code(),
+                Some("pkl_fast::type_mismatch"),
+                "{}",
+                error.msg()
+            );
+        }
+
+        for error in [
+            parse_err("a = (1"),
+            parse_err("a = 1 b = 2"),
+            eval_err("1 1"),
+        ] {
+            assert_eq!(error.code(), Some("pkl_fast::syntax"), "{}", error.msg());
+        }
+
+        for error in [
+            parse_err("a = 0x8000000000000000"),
+            parse_err("a = 1 -0x8000000000000000"),
+            parse_err("a = 0x_1"),
+        ] {
+            assert_eq!(error.code(), Some("pkl_fast::lexing"), "{}", error.msg());
+        }
+
+        assert_eq!(eval_err("1 ~/ 0").code(), Some("pkl_fast::error"));
+    }
+}
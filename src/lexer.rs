@@ -1,9 +1,10 @@
 use logos::Logos;
+use std::borrow::Cow;
 
 /* ANCHOR: tokens */
 /// All meaningful Pkl tokens.
 ///
-/// > NOTE: regexes for [`PklToken::Int`], [`PklToken::Float`], [`PklToken::MultiLineString`] and [`PklToken::String`]
+/// > NOTE: regexes for [`PklToken::Int`], [`PklToken::Float`] and [`PklToken::String`]
 /// > may not catch all possible values, especially for strings. If you find
 /// > errors, please report them so that we can improve the regex.
 ///
@@ -32,6 +33,10 @@ pub enum PklToken<'a> {
     OpenParen,
     #[token(")")]
     CloseParen,
+    #[token("[")]
+    OpenBracket,
+    #[token("]")]
+    CloseBracket,
     #[token(",")]
     Comma,
     #[token(":")]
@@ -43,6 +48,11 @@ pub enum PklToken<'a> {
     As,
     #[token(".")]
     Dot,
+    #[token("...")]
+    Spread,
+    /// The arrow separating the parameters of a lambda from its body.
+    #[token("->")]
+    Arrow,
     #[token("null")]
     Null,
 
@@ -54,6 +64,44 @@ pub enum PklToken<'a> {
     Union,
     #[token(">")]
     OperatorMoreThan,
+    #[token(">=")]
+    OperatorMoreOrEqual,
+    #[token("<")]
+    OperatorLessThan,
+    #[token("<=")]
+    OperatorLessOrEqual,
+    #[token("==")]
+    OperatorEqual,
+    #[token("!=")]
+    OperatorNotEqual,
+    #[token("!")]
+    OperatorNot,
+    /// The postfix non-null assertion, lexed as a single token
+    /// so that it is not mistaken for a double negation.
+    #[token("!!")]
+    NonNullAssertion,
+    #[token("&&")]
+    OperatorAnd,
+    #[token("||")]
+    OperatorOr,
+    #[token("??")]
+    OperatorNullCoalescing,
+    #[token("+")]
+    OperatorPlus,
+    /// The binary minus as well as the unary negation, such as `-offset`,
+    /// decimal literals being unsigned so that `-2.ms` is a negated duration.
+    #[token("-")]
+    OperatorMinus,
+    #[token("*")]
+    OperatorMul,
+    #[token("/")]
+    OperatorDiv,
+    #[token("~/")]
+    OperatorIntDiv,
+    #[token("%")]
+    OperatorModulo,
+    #[token("**")]
+    OperatorPow,
 
     #[token("new")]
     New,
@@ -82,7 +130,12 @@ pub enum PklToken<'a> {
     #[token("amends")]
     Amends,
 
-    #[regex(r"-?\d+(?:_?\d)*", |lex| {
+    #[token("when")]
+    When,
+    #[token("else")]
+    Else,
+
+    #[regex(r"\d+(?:_?\d)*", |lex| {
         let raw = lex.slice();
         // Remove underscores for parsing
         let clean_raw: String = raw.chars().filter(|&c| c != '_').collect();
@@ -90,73 +143,24 @@ pub enum PklToken<'a> {
     }, priority = 3)]
     Int(i64),
 
-    #[regex(r"-?0x[0-9a-fA-F]+(?:_?[0-9a-fA-F])*", |lex| {
-        let raw = lex.slice();
-        // Check for the optional minus sign
-        let (is_negative, hex_str) = if raw.starts_with('-') {
-            (true, &raw[3..]) // Skip "-0x"
-        } else {
-            (false, &raw[2..]) // Skip "0x"
-        };
-
-        // Remove underscores for parsing
-        let clean_hex: String = hex_str.chars().filter(|&c| c != '_').collect();
-        let value = i64::from_str_radix(&clean_hex, 16);
-
-        if is_negative {
-            value.map(|v| -v)
-        } else {
-            value
-        }
-    })]
+    #[regex(r"[+-]?0x_?[0-9a-fA-F]+(?:_?[0-9a-fA-F])*", |lex| parse_radix_int(lex.slice(), 16))]
     HexInt(i64),
 
-    #[regex(r"-?0b[01]+(?:_?[01])*", |lex| {
-        let raw = lex.slice();
-        // Check for the optional minus sign
-        let (is_negative, hex_str) = if raw.starts_with('-') {
-            (true, &raw[3..]) // Skip "-0b"
-        } else {
-            (false, &raw[2..]) // Skip "0b"
-        };
-
-        // Remove underscores for parsing
-        let clean_hex: String = hex_str.chars().filter(|&c| c != '_').collect();
-        let value = i64::from_str_radix(&clean_hex, 2);
-
-        if is_negative {
-            value.map(|v| -v)
-        } else {
-            value
-        }
-    })]
+    #[regex(r"[+-]?0b_?[01]+(?:_?[01])*", |lex| parse_radix_int(lex.slice(), 2))]
     BinaryInt(i64),
 
-    #[regex(r"-?0o[0-7]+(?:_?[0-7])*", |lex| {
-        let raw = lex.slice();
-        // Check for the optional minus sign
-        let (is_negative, hex_str) = if raw.starts_with('-') {
-            (true, &raw[3..]) // Skip "-0o"
-        } else {
-            (false, &raw[2..]) // Skip "0o"
-        };
-
-        // Remove underscores for parsing
-        let clean_hex: String = hex_str.chars().filter(|&c| c != '_').collect();
-        let value = i64::from_str_radix(&clean_hex, 8);
-
-        if is_negative {
-            value.map(|v| -v)
-        } else {
-            value
-        }
-    })]
+    #[regex(r"[+-]?0o_?[0-7]+(?:_?[0-7])*", |lex| parse_radix_int(lex.slice(), 8))]
     OctalInt(i64),
 
     #[token("NaN", |_| std::f64::NAN)]
     #[token("Infinity", |_| std::f64::INFINITY)]
-    #[token("-Infinity", |_| std::f64::NEG_INFINITY)]
-    #[regex(r"-?(?:0|[1-9]+(?:_?\d)*)?(?:\.\d+(?:_?\d)*)(?:[eE][+-]?\d+(?:_?\d)*)?", |lex| {
+    // the fractional part is optional only when an exponent is present, `1e3` is a float
+    #[regex(r"(?:0|[1-9]+(?:_?\d)*)?\.\d+(?:_?\d)*(?:[eE][+-]?\d+(?:_?\d)*)?", |lex| {
+        let raw = lex.slice();
+        let clean_raw: String = raw.chars().filter(|&c| c != '_').collect();
+        clean_raw.parse::<f64>()
+    }, priority = 2)]
+    #[regex(r"(?:0|[1-9]+(?:_?\d)*)[eE][+-]?\d+(?:_?\d)*", |lex| {
         let raw = lex.slice();
         let clean_raw: String = raw.chars().filter(|&c| c != '_').collect();
         clean_raw.parse::<f64>()
@@ -179,9 +183,9 @@ pub enum PklToken<'a> {
     #[regex(r#"`([^`\\]|\\[`\\bnfrt]|\\u\{[a-fA-F0-9]+})*`"#, |lex| {let raw=lex.slice();&raw[1..raw.len()-1]})]
     IllegalIdentifier(&'a str),
 
-    #[regex(r#"//[^\n\\]*"#, |lex| let raw=lex.slice();&raw[2..raw.len()-1])]
+    #[regex(r#"//[^\n\\]*"#, |lex| let raw=lex.slice();&raw[2..])]
     LineComment(&'a str),
-    #[regex(r#"///[^\n\\]*"#, |lex| let raw=lex.slice();&raw[3..raw.len()-1])]
+    #[regex(r#"///[^\n\\]*"#, |lex| let raw=lex.slice();&raw[3..])]
     DocComment(&'a str),
     #[regex(r#"/\*[^*]*\*+(?:[^/*][^*]*\*+)*/"#, |lex| let raw=lex.slice();&raw[2..raw.len()-2])]
     MultilineComment(&'a str),
@@ -189,21 +193,7 @@ pub enum PklToken<'a> {
     #[regex(r#""([^"\\]|\\["\\bnfrt]|\\u\{[a-fA-F0-9]+})*""#, |lex| let raw=lex.slice();&raw[1..raw.len()-1])]
     String(&'a str),
 
-    // does pkl support <"> character in the multiline strings ?
-    #[regex(r#""""([^"\\]|\\["\\bnfrt]|u[a-fA-F0-9]{4})*""""#, |lex| {
-        let raw=lex.slice();
-
-        if raw[3..=3] != *"\n" {
-            return Err(LexingError::ExpectedNewLineAfterMultilineStringStart)
-        }
-
-        // return err if raw[raw.len()-4..=raw.len()-4] != "\n"
-        if raw[raw.len()-4..=raw.len()-4] != *"\n" {
-            return Err(LexingError::ExpectedNewLineBeforeMultilineStringEnd)
-        }
-
-        Ok(&raw[4..raw.len()-4])
-    })]
+    #[token(r#"""""#, lex_multiline_string)]
     MultiLineString(&'a str),
 }
 /* ANCHOR_END: tokens */
@@ -215,13 +205,14 @@ pub enum LexingError {
 
     ExpectedNewLineBeforeMultilineStringEnd,
     ExpectedNewLineAfterMultilineStringStart,
+    UnterminatedMultilineString,
     #[default]
     Default,
 }
 
 use std::{
     fmt,
-    num::{ParseFloatError, ParseIntError},
+    num::{IntErrorKind, ParseFloatError, ParseIntError},
 };
 
 /// Error type returned by calling `lex.slice().parse()` to u8.
@@ -252,11 +243,119 @@ impl fmt::Display for LexingError {
                 f,
                 "Expected a newline after the start of the multiline string"
             ),
+            LexingError::UnterminatedMultilineString => {
+                write!(f, "Missing the closing delimiter of the multiline string")
+            }
             LexingError::Default => write!(f, "An unspecified lexing error occurred"),
         }
     }
 }
 
+/// Lexes the rest of a multiline string once its opening `"""` is matched,
+/// interpolated expressions such as `\(name)` being skipped as a whole.
+///
+/// Returns the content of the string, from the start of its first line
+/// to the indentation of its closing `"""` included.
+fn lex_multiline_string<'a>(
+    lex: &mut logos::Lexer<'a, PklToken<'a>>,
+) -> Result<&'a str, LexingError> {
+    let rest = lex.remainder();
+    if !rest.starts_with('\n') {
+        return Err(LexingError::ExpectedNewLineAfterMultilineStringStart);
+    }
+
+    let bytes = rest.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if bytes.get(i + 1) == Some(&b'(') => i = skip_interpolation(bytes, i + 2)?,
+            b'\\' => i += 2,
+            b'"' if bytes[i..].starts_with(b"\"\"\"") => {
+                // the opening newline always precedes the closing line
+                let last_line_start = rest[..i].rfind('\n').unwrap_or(0) + 1;
+                if !rest[last_line_start..i]
+                    .bytes()
+                    .all(|b| b == b' ' || b == b'\t')
+                {
+                    return Err(LexingError::ExpectedNewLineBeforeMultilineStringEnd);
+                }
+
+                lex.bump(i + 3);
+                return Ok(&rest[1..i]);
+            }
+            _ => i += 1,
+        }
+    }
+
+    Err(LexingError::UnterminatedMultilineString)
+}
+
+/// Returns the index right after the parenthesis closing the interpolation
+/// whose expression starts at `start`, skipping the strings the expression contains.
+fn skip_interpolation(bytes: &[u8], start: usize) -> Result<usize, LexingError> {
+    let mut depth = 1;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(i + 1);
+                }
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' && bytes[i] != b'\n' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+
+    Err(LexingError::UnterminatedMultilineString)
+}
+
+/// Parses a hexadecimal, binary or octal integer literal,
+/// with its optional sign and the underscores separating its digits.
+fn parse_radix_int(raw: &str, radix: u32) -> Result<i64, LexingError> {
+    let (is_negative, unsigned) = match raw.as_bytes()[0] {
+        b'-' => (true, &raw[1..]),
+        b'+' => (false, &raw[1..]),
+        _ => (false, raw),
+    };
+
+    // Skip the "0x", "0b" or "0o" prefix
+    let digits = &unsigned[2..];
+    if digits.starts_with('_') {
+        return Err(LexingError::InvalidInteger(format!(
+            "`{}` cannot have an underscore right after its radix prefix",
+            raw
+        )));
+    }
+
+    // Remove underscores for parsing
+    let clean_digits: String = digits.chars().filter(|&c| c != '_').collect();
+    let out_of_range = || LexingError::InvalidInteger(format!("`{}` is out of range", raw));
+
+    // the digits are parsed unsigned, so `-0x8000000000000000` is out of range as well
+    let value = i64::from_str_radix(&clean_digits, radix).map_err(|e| match e.kind() {
+        IntErrorKind::PosOverflow => out_of_range(),
+        _ => e.into(),
+    })?;
+
+    if is_negative {
+        value.checked_neg().ok_or_else(out_of_range)
+    } else {
+        Ok(value)
+    }
+}
+
 pub trait IsValidPkl {
     fn is_valid_pkl_id(self) -> bool;
 }
@@ -286,3 +385,128 @@ impl IsValidPkl for &str {
         false
     }
 }
+
+/// Decodes the escape sequences of a string literal or of an identifier enclosed in backticks,
+/// such as `\u{20}` or `\n`, into the characters they stand for.
+///
+/// Text without any escape sequence, regular identifiers included, is returned as is.
+pub fn decode_escapes(raw: &str) -> Cow<'_, str> {
+    if !raw.contains('\\') {
+        return Cow::Borrowed(raw);
+    }
+
+    let mut decoded = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            decoded.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('b') => decoded.push('\u{8}'),
+            Some('n') => decoded.push('\n'),
+            Some('f') => decoded.push('\u{c}'),
+            Some('r') => decoded.push('\r'),
+            Some('t') => decoded.push('\t'),
+            Some('u') => {
+                // the lexer only accepts `\u{...}` with hexadecimal digits
+                let digits = chars
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|c| *c != '}')
+                    .collect::<String>();
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    Some(c) => decoded.push(c),
+                    None => decoded.push(char::REPLACEMENT_CHARACTER),
+                }
+            }
+            // `\``, `\"` and `\\`
+            Some(c) => decoded.push(c),
+            None => decoded.push('\\'),
+        }
+    }
+
+    Cow::Owned(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LexingError, PklToken};
+    use crate::test_utils::{error_slice, parse_err};
+    use logos::Logos;
+
+    /// Lexes `source` into its tokens, whitespace included.
+    fn lex(source: &str) -> Vec<Result<PklToken<'_>, LexingError>> {
+        PklToken::lexer(source).collect()
+    }
+
+    #[test]
+    fn radix_literals_accept_a_sign() {
+        assert_eq!(lex("-0b1010"), [Ok(PklToken::BinaryInt(-10))]);
+        assert_eq!(lex("+0x1F"), [Ok(PklToken::HexInt(31))]);
+        assert_eq!(lex("0o777"), [Ok(PklToken::OctalInt(511))]);
+        assert_eq!(lex("0b1_0"), [Ok(PklToken::BinaryInt(2))]);
+    }
+
+    #[test]
+    fn radix_literals_reject_an_underscore_after_their_prefix() {
+        assert_eq!(
+            lex("0x_FF"),
+            [Err(LexingError::InvalidInteger(
+                "`0x_FF` cannot have an underscore right after its radix prefix".to_owned()
+            ))]
+        );
+    }
+
+    #[test]
+    fn floats_can_have_an_exponent_without_fractional_part() {
+        assert_eq!(lex("1e3"), [Ok(PklToken::Float(1000.0))]);
+        assert_eq!(lex("1.5e3"), [Ok(PklToken::Float(1500.0))]);
+        assert_eq!(lex("1e-3"), [Ok(PklToken::Float(0.001))]);
+        assert_eq!(lex("10E+2"), [Ok(PklToken::Float(1000.0))]);
+        assert_eq!(lex("1"), [Ok(PklToken::Int(1))]);
+    }
+
+    #[test]
+    fn double_bang_is_lexed_as_a_single_token() {
+        assert_eq!(
+            lex("!a!!"),
+            [
+                Ok(PklToken::OperatorNot),
+                Ok(PklToken::Identifier("a")),
+                Ok(PklToken::NonNullAssertion)
+            ]
+        );
+    }
+
+    #[test]
+    fn radix_literals_out_of_range_are_errors() {
+        for literal in [
+            "-0x8000000000000000",
+            "0x8000000000000000",
+            "-0b1000000000000000000000000000000000000000000000000000000000000000",
+            "-0o1000000000000000000000",
+        ] {
+            assert_eq!(
+                lex(literal),
+                [Err(LexingError::InvalidInteger(format!(
+                    "`{literal}` is out of range"
+                )))]
+            );
+        }
+        assert_eq!(
+            lex("-0x7FFFFFFFFFFFFFFF"),
+            [Ok(PklToken::HexInt(-i64::MAX))]
+        );
+
+        let source = "x = -0x8000000000000000";
+        let error = parse_err(source);
+        assert_eq!(error.code(), Some("pkl_fast::lexing"));
+        assert_eq!(error_slice(source, &error), "-0x8000000000000000");
+    }
+}
@@ -1,24 +1,68 @@
-use hashbrown::HashMap;
+use indexmap::IndexMap;
 use lexer::PklToken;
-use parser::{parse_pkl, statement::PklStatement};
+use parser::{expr::parse_expr, parse_pkl, statement::PklStatement};
+use std::{fmt, io::Read, ops::Range, path::Path, sync::Arc};
 use table::class::ClassSchema;
-use table::{ast_to_table, PklMember, PklTable};
+use table::{
+    ast_to_table, validate_ast, FunctionRegistry, Importer, PklTable, ScopedTable, TableSettings,
+};
 
 mod errors;
 mod lexer;
 mod parser;
 pub mod pest;
 mod table;
+#[cfg(test)]
+mod test_utils;
 mod utils;
 
 pub use errors::PklError;
 pub use errors::PklResult;
+pub use errors::PklWarning;
+pub use table::types::PklType;
+pub use table::value::json::NonFiniteFloatPolicy;
 pub use table::value::PklValue;
+pub use table::PklMember;
+
+/// Counts of the members of a [`Pkl`] instance, see [`Pkl::summary`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct PklSummary {
+    /// The number of values, local ones excluded.
+    pub values: usize,
+    /// The number of classes.
+    pub classes: usize,
+    /// The number of imported modules.
+    pub imports: usize,
+}
+
+/// The kind and name of a top-level statement, see [`Pkl::outline`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum StatementKind {
+    /// A property, local, const and fixed ones included.
+    Property(String),
+    /// A class declaration.
+    Class(String),
+    /// A typealias.
+    TypeAlias(String),
+    /// An import, with the uri it is imported from and the name it is imported under.
+    Import { uri: String, name: String },
+    /// The module clause, with the full name of the module.
+    Module(String),
+    /// An amends clause, with the uri of the amended module.
+    Amends(String),
+    /// An extends clause, with the uri of the extended module.
+    Extends(String),
+    /// A `when` statement.
+    When,
+}
 
 #[derive(Debug, PartialEq, Clone)]
 /// The `Pkl` struct represents the main interface for working with PKL data.
 pub struct Pkl {
     table: PklTable,
+    settings: TableSettings,
+    importer: Importer,
+    functions: FunctionRegistry,
 }
 
 impl Pkl {
@@ -26,11 +70,160 @@ impl Pkl {
     pub fn new() -> Self {
         Self {
             table: PklTable::default(),
+            settings: TableSettings::default(),
+            importer: Importer::default(),
+            functions: FunctionRegistry::default(),
         }
     }
 
+    /// Creates a new `Pkl` instance from a PKL source read from `reader`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader the PKL source is read from, such as a file or stdin.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the populated instance, or an error if reading or parsing fails.
+    pub fn from_reader<R: Read>(mut reader: R) -> PklResult<Pkl> {
+        let mut source = String::new();
+        reader
+            .read_to_string(&mut source)
+            .map_err(|e| PklError::unreadable("source", e, None))?;
+
+        let mut pkl = Pkl::new();
+        pkl.parse(&source)?;
+
+        Ok(pkl)
+    }
+
+    /// Enables or disables deep amending for the next parsed sources.
+    ///
+    /// When enabled, amending an object merges its nested objects
+    /// with the amending ones instead of replacing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether nested objects are merged when amended.
+    pub fn set_deep_amend(&mut self, enabled: bool) {
+        self.settings.deep_amend = enabled;
+    }
+
+    /// Enables or disables the camelCase lint of property names for the next parsed sources.
+    ///
+    /// When enabled, each property whose name is not camelCase,
+    /// such as `my_prop`, emits a warning retrievable with [`Pkl::warnings`].
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether property names are checked.
+    pub fn set_lint_property_names(&mut self, enabled: bool) {
+        self.settings.lint_property_names = enabled;
+    }
+
+    /// Enables or disables lenient class instances for the next parsed sources.
+    ///
+    /// When enabled, instantiating a class without one of its fields,
+    /// or with a field it does not declare, emits a warning retrievable with
+    /// [`Pkl::warnings`] instead of failing.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether missing and unknown fields are only warned about.
+    pub fn set_lenient_instances(&mut self, enabled: bool) {
+        self.settings.lenient_instances = enabled;
+    }
+
+    /// Enables or disables the rejection of duplicate imports for the next parsed sources.
+    ///
+    /// A module imported again without an alias, after being imported without one,
+    /// is ignored by default. When enabled, it is an error instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether importing a module twice is an error.
+    pub fn set_deny_duplicate_imports(&mut self, enabled: bool) {
+        self.settings.deny_duplicate_imports = enabled;
+    }
+
+    /// Sets the directory relative file imports, amends and extends are resolved against,
+    /// instead of the current directory, for the next parsed sources.
+    ///
+    /// The imported files resolve their own relative imports against their directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory relative file paths are resolved against.
+    ///
+    /// # Returns
+    ///
+    /// The instance, resolving relative file paths against `path`.
+    pub fn with_base_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.importer.set_base_dir(path.as_ref());
+        self
+    }
+
+    /// Returns the name of the module the parsed source amends, if any.
+    pub fn amends_target(&self) -> Option<&str> {
+        self.table.amended_module_name()
+    }
+
+    /// Returns the name of the module the parsed source extends, if any.
+    pub fn extends_target(&self) -> Option<&str> {
+        self.table.extended_module_name()
+    }
+
+    /// Returns `true` if the parsed source amends another module.
+    pub fn is_amending(&self) -> bool {
+        self.amends_target().is_some()
+    }
+
+    /// Returns `true` if the parsed source extends another module.
+    pub fn is_extending(&self) -> bool {
+        self.extends_target().is_some()
+    }
+
+    /// Returns the warnings emitted while parsing the sources.
+    pub fn warnings(&self) -> &[PklWarning] {
+        &self.table.warnings
+    }
+
+    /// Registers a virtual module that can then be imported, amended
+    /// or extended with the `mem:` scheme, such as `import "mem:base"`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the module, without the `mem:` scheme.
+    /// * `source` - The PKL source of the module, parsed when the module is imported.
+    pub fn register_module(&mut self, name: &str, source: &str) {
+        self.importer.register_module(name, source);
+    }
+
+    /// Registers a function implemented in Rust, that can then be called
+    /// from the sources parsed afterwards, such as `lookup("key")`.
+    ///
+    /// The builtin functions, such as `List` or `read`, take precedence over registered ones.
+    /// Checking the number and the types of the arguments is up to the function,
+    /// the errors it returns without a span being reported on the call.
+    /// The modules imported by the sources can call it as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the function is called with.
+    /// * `f` - The function, called with the evaluated arguments.
+    pub fn register_fn(
+        &mut self,
+        name: &str,
+        f: impl Fn(&[PklValue]) -> PklResult<PklValue> + Send + Sync + 'static,
+    ) {
+        self.functions.register(name, Arc::new(f));
+    }
+
     /// Parses a PKL source string and populates the internal context.
     ///
+    /// Calling it on an already populated instance overwrites the members defined again,
+    /// see [`Pkl::parse_append`] and [`Pkl::parse_replace`] for the other behaviors.
+    ///
     /// # Arguments
     ///
     /// * `source` - The PKL source string to parse.
@@ -39,8 +232,7 @@ impl Pkl {
     ///
     /// A `PklResult` indicating success or failure.
     pub fn parse(&mut self, source: &str) -> PklResult<()> {
-        let parsed = self.generate_ast(source)?;
-        let table = ast_to_table(parsed)?;
+        let table = self.build_table(source)?;
 
         if self.table.is_empty() {
             self.table = table;
@@ -52,6 +244,199 @@ impl Pkl {
         Ok(())
     }
 
+    /// Checks a PKL source string without stopping at the first error.
+    ///
+    /// Every property is evaluated, the type errors and unknown members of
+    /// all of them being reported. A property whose value cannot be computed
+    /// is skipped, which does not prevent checking the following ones.
+    /// The internal context is left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The PKL source string to check.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the source is valid, or all the errors that were found.
+    pub fn validate(&self, source: &str) -> Result<(), Vec<PklError>> {
+        let parsed = self.generate_ast(source).map_err(|e| vec![e])?;
+        let errors = validate_ast(
+            parsed,
+            self.settings,
+            self.importer.to_owned(),
+            self.functions.to_owned(),
+        );
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Checks that a PKL source string is syntactically valid, without evaluating it.
+    ///
+    /// Imports are not resolved and types are not checked,
+    /// making it much cheaper than [`Pkl::parse`] or [`Pkl::validate`].
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The PKL source string to check.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` indicating whether the source could be parsed.
+    pub fn check_syntax(source: &str) -> PklResult<()> {
+        use logos::Logos;
+        let mut lexer = PklToken::lexer(source);
+        parse_pkl(&mut lexer)?;
+
+        Ok(())
+    }
+
+    /// Resolves the imports, amends and extends clauses of a PKL source
+    /// and renders it as a single self-contained PKL document.
+    ///
+    /// The members are rendered fully evaluated, the values they borrow from other
+    /// modules being inlined, so that no name from another module can collide with
+    /// the ones of the bundled module. Classes are not part of the document,
+    /// their instances are rendered as dynamic objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The PKL source string to bundle.
+    /// * `base_dir` - The directory relative file imports are resolved against.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the bundled document or an error if the source cannot be evaluated.
+    pub fn bundle(source: &str, base_dir: impl AsRef<Path>) -> PklResult<String> {
+        let mut pkl = Pkl::new().with_base_path(base_dir);
+        pkl.parse(source)?;
+
+        Ok(pkl.table.render_standalone())
+    }
+
+    /// Lists the top-level statements of a PKL source string along with their spans,
+    /// such as to build the outline of a document in an editor.
+    ///
+    /// The source is only parsed, not evaluated.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The PKL source string to outline.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the kind of each statement and its span, in source order.
+    pub fn outline(source: &str) -> PklResult<Vec<(StatementKind, Range<usize>)>> {
+        use logos::Logos;
+        use parser::statement::{
+            amends::Amends, class::ClassDeclaration, extends::Extends, import::Import,
+            module::Module, property::Property, typealias::TypeAlias,
+        };
+
+        let mut lexer = PklToken::lexer(source);
+        let statements = parse_pkl(&mut lexer)?;
+
+        let outline = statements
+            .iter()
+            .map(|stmt| {
+                let kind = match stmt.inner() {
+                    PklStatement::Property(Property { name, .. }) => {
+                        StatementKind::Property(name.value().to_owned())
+                    }
+                    PklStatement::Class(ClassDeclaration { name, .. }) => {
+                        StatementKind::Class(name.value().to_owned())
+                    }
+                    PklStatement::TypeAlias(TypeAlias { name, .. }) => {
+                        StatementKind::TypeAlias(name.value().to_owned())
+                    }
+                    PklStatement::Import(Import {
+                        name, local_name, ..
+                    }) => StatementKind::Import {
+                        uri: name.to_string(),
+                        name: local_name
+                            .as_deref()
+                            .unwrap_or_else(|| Importer::module_id_from_uri(name))
+                            .to_owned(),
+                    },
+                    PklStatement::ModuleClause(Module { full_name, .. }) => {
+                        StatementKind::Module(full_name.value().to_owned())
+                    }
+                    PklStatement::AmendsClause(Amends { name, .. }) => {
+                        StatementKind::Amends(name.to_string())
+                    }
+                    PklStatement::ExtendsClause(Extends { name, .. }) => {
+                        StatementKind::Extends(name.to_string())
+                    }
+                    PklStatement::When(_) => StatementKind::When,
+                    PklStatement::Local(..) | PklStatement::Const(..) | PklStatement::Fixed(..) => {
+                        unreachable!("the modifiers are unwrapped by `inner`")
+                    }
+                };
+
+                (kind, stmt.span())
+            })
+            .collect();
+
+        Ok(outline)
+    }
+
+    /// Parses a PKL source string and adds its members to the internal context.
+    ///
+    /// Redefining a member that is neither amended nor extended is an error,
+    /// in which case the internal context is left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The PKL source string to parse.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` indicating success or failure.
+    pub fn parse_append(&mut self, source: &str) -> PklResult<()> {
+        let table = self.build_table(source)?;
+
+        if self.table.is_empty() {
+            self.table = table;
+            return Ok(());
+        }
+
+        self.table.try_extend(table).map_err(|name| {
+            PklError::WithoutContext(format!("Duplicate definition of member `{}`", name), None)
+        })
+    }
+
+    /// Parses a PKL source string and replaces the internal context with its members.
+    ///
+    /// The internal context is only cleared once the source has been successfully parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The PKL source string to parse.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` indicating success or failure.
+    pub fn parse_replace(&mut self, source: &str) -> PklResult<()> {
+        self.table = self.build_table(source)?;
+
+        Ok(())
+    }
+
+    /// Builds the table of a PKL source string with the settings,
+    /// the modules and the functions of this instance.
+    fn build_table(&self, source: &str) -> PklResult<PklTable> {
+        let parsed = self.generate_ast(source)?;
+        ast_to_table(
+            parsed,
+            self.settings,
+            self.importer.to_owned(),
+            self.functions.to_owned(),
+        )
+    }
+
     /// Generates an AST from a PKL source string.
     ///
     /// # Arguments
@@ -67,6 +452,81 @@ impl Pkl {
         parse_pkl(&mut lexer)
     }
 
+    /// Serializes the value members of the context to a JSON object.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - How `NaN` and `Infinity` floats are serialized.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the JSON string or an error message if a value cannot be represented in JSON.
+    pub fn to_json(&self, policy: NonFiniteFloatPolicy) -> PklResult<String> {
+        self.table.to_json(policy)
+    }
+
+    /// Evaluates an expression against the context, with extra temporary bindings.
+    ///
+    /// The bindings shadow the members of the same name for this evaluation only,
+    /// the internal context being left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `expr_src` - The PKL source of the expression to evaluate, such as `a + b`.
+    /// * `bindings` - The names and values available to the expression.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the value of the expression or an error message.
+    pub fn evaluate_in_scope(
+        &self,
+        expr_src: &str,
+        bindings: &[(&str, PklValue)],
+    ) -> PklResult<PklValue> {
+        use logos::Logos;
+        let mut lexer = PklToken::lexer(expr_src);
+        let expr = parse_expr(&mut lexer).map_err(PklError::into_syntax)?;
+
+        // only blank characters may follow the expression
+        while let Some(token) = lexer.next() {
+            match token {
+                Ok(PklToken::Space) | Ok(PklToken::NewLine) => continue,
+                Err(e) => return Err((e, lexer.span()).into()),
+                Ok(_) => {
+                    return Err(PklError::Syntax(
+                        "Unexpected token after the expression".to_owned(),
+                        lexer.span(),
+                        None,
+                    ))
+                }
+            }
+        }
+
+        let mut scope = ScopedTable::new(&self.table);
+        for (name, value) in bindings {
+            scope.bind(*name, value.to_owned());
+        }
+
+        scope.evaluate(expr)
+    }
+
+    /// Evaluates an expression against the context, along with its inferred type.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The PKL source of the expression to evaluate, such as `List(1, 2)`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the value of the expression and its type, see [`PklValue::infer_type`],
+    /// or an error message.
+    pub fn parse_expr_value(&self, src: &str) -> PklResult<(PklValue, PklType)> {
+        let value = self.evaluate_in_scope(src, &[])?;
+        let _type = value.infer_type();
+
+        Ok((value, _type))
+    }
+
     /// Retrieves a value from the context by name.
     ///
     /// # Arguments
@@ -84,6 +544,31 @@ impl Pkl {
             .flatten()
     }
 
+    /// Retrieves a nested value from the context by its dotted path.
+    ///
+    /// Each component after the first indexes the fields of an object or a class instance,
+    /// or the entries of a mapping.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The dotted path of the value, such as `server.port`.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the value found at the path, or `None` if any component is not found.
+    pub fn get_path(&self, path: &str) -> Option<PklValue> {
+        let mut components = path.split('.');
+        let mut value = self.get_value(components.next()?)?;
+
+        for component in components {
+            value = value
+                .index(&PklValue::String(component.to_owned()))?
+                .to_owned();
+        }
+
+        Some(value)
+    }
+
     /// Retrieves a class schema from the context by name.
     ///
     /// # Arguments
@@ -101,6 +586,163 @@ impl Pkl {
             .flatten()
     }
 
+    /// Derives a class schema from the fields of an object or a class instance,
+    /// each field being mapped to the type inferred from its value, such as to document example data.
+    ///
+    /// Nested objects are typed as `Dynamic` and class instances by their class name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the property holding the object.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the derived schema,
+    /// or `None` if the property is not found or does not hold an object or a class instance.
+    pub fn schema_of(&self, name: &str) -> Option<ClassSchema> {
+        match self.table.get(name)? {
+            PklMember::Value {
+                value: PklValue::Object(fields) | PklValue::ClassInstance(_, fields),
+                ..
+            } => Some(
+                fields
+                    .iter()
+                    .map(|(field, value)| (field.to_owned(), value.infer_type()))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a member, either a value or a class, from the context by name,
+    /// without removing it.
+    ///
+    /// Useful to inspect the flags of the member, such as `is_const` or `is_local`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the member to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a reference to the `PklMember` associated with the name,
+    /// or `None` if the member is not found.
+    pub fn get_member(&self, name: &str) -> Option<&PklMember> {
+        self.table.get(name)
+    }
+
+    /// Collects the values of the context matching the predicate, local members excluded.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - The function values are filtered with.
+    ///
+    /// # Returns
+    ///
+    /// The names and values of the matching members, sorted by name.
+    pub fn values_of_type(&self, predicate: impl Fn(&PklValue) -> bool) -> Vec<(&str, PklValue)> {
+        let mut values = self
+            .table
+            .members
+            .iter()
+            .filter_map(|(name, member)| match member {
+                PklMember::Value {
+                    value,
+                    is_local: false,
+                    ..
+                } if predicate(value) => Some((name.as_str(), value.to_owned())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        values.sort_by_key(|(name, _)| *name);
+
+        values
+    }
+
+    /// Collects the string values of the context, local members excluded.
+    ///
+    /// # Returns
+    ///
+    /// The names and strings of the matching members, sorted by name.
+    pub fn string_values(&self) -> Vec<(&str, String)> {
+        self.values_of_type(PklValue::is_string)
+            .into_iter()
+            .filter_map(|(name, value)| match value {
+                PklValue::String(s) => Some((name, s)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Collects the object values of the context, local members excluded.
+    ///
+    /// # Returns
+    ///
+    /// The names and fields of the matching members, sorted by name.
+    pub fn object_values(&self) -> Vec<(&str, IndexMap<String, PklValue>)> {
+        self.values_of_type(PklValue::is_object)
+            .into_iter()
+            .filter_map(|(name, value)| match value {
+                PklValue::Object(fields) => Some((name, fields)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Retrieves the doc comment of a class field or an object entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The dotted path of the field, such as `Server.port` or `server.host.name`.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the lines of the doc comment joined with `\n`,
+    /// or `None` if the field is not documented.
+    pub fn get_doc(&self, path: &str) -> Option<&str> {
+        self.table.docs.get(path).map(String::as_str)
+    }
+
+    /// Returns the number of values and classes in the context, local members excluded.
+    pub fn len(&self) -> usize {
+        self.table
+            .members
+            .values()
+            .filter(|member| !member.is_local())
+            .count()
+    }
+
+    /// Returns `true` if the context contains no value nor class, local members excluded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Counts the values, classes and imported modules of the context,
+    /// the values and classes adding up to [`Pkl::len`].
+    ///
+    /// # Returns
+    ///
+    /// A `PklSummary` containing the counts.
+    pub fn summary(&self) -> PklSummary {
+        let mut summary = PklSummary {
+            imports: self.table.imports.len(),
+            ..Default::default()
+        };
+
+        for member in self.table.members.values() {
+            if member.is_local() {
+                continue;
+            }
+            if member.is_class() {
+                summary.classes += 1;
+            } else {
+                summary.values += 1;
+            }
+        }
+
+        summary
+    }
+
     /// Sets or modifies a value in the context by name.
     ///
     /// # Arguments
@@ -162,6 +804,27 @@ impl Pkl {
         }
     }
 
+    /// Retrieves a boolean value from the context, or a default one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the variable to retrieve.
+    /// * `default` - The value returned if the variable is not found or is `null`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the boolean value, the default value or an error message if wrong type.
+    pub fn get_bool_or(&self, name: &str, default: bool) -> PklResult<bool> {
+        match self.get_value(name) {
+            Some(PklValue::Bool(v)) => Ok(v),
+            None | Some(PklValue::Null) => Ok(default),
+            Some(_) => Err(PklError::WithoutContext(
+                format!("Property `{}` is not a boolean", name),
+                None,
+            )),
+        }
+    }
+
     /// Retrieves an integer value from the context.
     ///
     /// # Arguments
@@ -193,6 +856,27 @@ impl Pkl {
         }
     }
 
+    /// Retrieves an integer value from the context, or a default one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the variable to retrieve.
+    /// * `default` - The value returned if the variable is not found or is `null`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the integer value, the default value or an error message if wrong type.
+    pub fn get_int_or(&self, name: &str, default: i64) -> PklResult<i64> {
+        match self.get_value(name) {
+            Some(PklValue::Int(v)) => Ok(v),
+            None | Some(PklValue::Null) => Ok(default),
+            Some(_) => Err(PklError::WithoutContext(
+                format!("Property `{}` is not an int", name),
+                None,
+            )),
+        }
+    }
+
     /// Retrieves a floating-point value from the context.
     ///
     /// # Arguments
@@ -224,6 +908,27 @@ impl Pkl {
         }
     }
 
+    /// Retrieves a floating-point value from the context, or a default one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the variable to retrieve.
+    /// * `default` - The value returned if the variable is not found or is `null`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the floating-point value, the default value or an error message if wrong type.
+    pub fn get_float_or(&self, name: &str, default: f64) -> PklResult<f64> {
+        match self.get_value(name) {
+            Some(PklValue::Float(v)) => Ok(v),
+            None | Some(PklValue::Null) => Ok(default),
+            Some(_) => Err(PklError::WithoutContext(
+                format!("Property `{}` is not a float", name),
+                None,
+            )),
+        }
+    }
+
     /// Retrieves a string value from the context.
     ///
     /// # Arguments
@@ -255,6 +960,27 @@ impl Pkl {
         }
     }
 
+    /// Retrieves a string value from the context, or a default one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the variable to retrieve.
+    /// * `default` - The value returned if the variable is not found or is `null`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the string value, the default value or an error message if wrong type.
+    pub fn get_string_or(&self, name: &str, default: impl Into<String>) -> PklResult<String> {
+        match self.get_value(name) {
+            Some(PklValue::String(v)) => Ok(v),
+            None | Some(PklValue::Null) => Ok(default.into()),
+            Some(_) => Err(PklError::WithoutContext(
+                format!("Property `{}` is not a string", name),
+                None,
+            )),
+        }
+    }
+
     /// Retrieves an object value from the context.
     ///
     /// # Arguments
@@ -264,7 +990,7 @@ impl Pkl {
     /// # Returns
     ///
     /// A `PklResult` containing the object value or an error message if not found or wrong type.
-    pub fn get_object(&self, name: &str) -> PklResult<HashMap<String, PklValue>> {
+    pub fn get_object(&self, name: &str) -> PklResult<IndexMap<String, PklValue>> {
         if let Some(v) = self
             .table
             .get(name)
@@ -285,6 +1011,149 @@ impl Pkl {
             ))
         }
     }
+
+    /// Retrieves a class instance value from the context.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the variable to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the class name and the fields of the instance or an error message if not found or wrong type.
+    pub fn get_class_instance(
+        &self,
+        name: &str,
+    ) -> PklResult<(String, IndexMap<String, PklValue>)> {
+        if let Some(v) = self
+            .table
+            .get(name)
+            .map(|v| v.to_owned().extract_value())
+            .flatten()
+        {
+            match v {
+                PklValue::ClassInstance(class_name, fields) => Ok((class_name, fields)),
+                _ => Err(PklError::WithoutContext(
+                    format!("Property `{}` is not a class instance", name),
+                    None,
+                )),
+            }
+        } else {
+            Err(PklError::WithoutContext(
+                format!("Property `{}` not found", name),
+                None,
+            ))
+        }
+    }
+
+    /// Retrieves a nested boolean value from the context by its dotted path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The dotted path of the value, such as `server.port`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the boolean value or an error message if not found or wrong type.
+    pub fn get_bool_at(&self, path: &str) -> PklResult<bool> {
+        match self.get_path_or_err(path)? {
+            PklValue::Bool(v) => Ok(v),
+            _ => Err(PklError::WithoutContext(
+                format!("Property `{}` is not a boolean", path),
+                None,
+            )),
+        }
+    }
+
+    /// Retrieves a nested integer value from the context by its dotted path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The dotted path of the value, such as `server.port`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the integer value or an error message if not found or wrong type.
+    pub fn get_int_at(&self, path: &str) -> PklResult<i64> {
+        match self.get_path_or_err(path)? {
+            PklValue::Int(v) => Ok(v),
+            _ => Err(PklError::WithoutContext(
+                format!("Property `{}` is not an int", path),
+                None,
+            )),
+        }
+    }
+
+    /// Retrieves a nested floating-point value from the context by its dotted path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The dotted path of the value, such as `server.port`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the floating-point value or an error message if not found or wrong type.
+    pub fn get_float_at(&self, path: &str) -> PklResult<f64> {
+        match self.get_path_or_err(path)? {
+            PklValue::Float(v) => Ok(v),
+            _ => Err(PklError::WithoutContext(
+                format!("Property `{}` is not a float", path),
+                None,
+            )),
+        }
+    }
+
+    /// Retrieves a nested string value from the context by its dotted path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The dotted path of the value, such as `server.port`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the string value or an error message if not found or wrong type.
+    pub fn get_string_at(&self, path: &str) -> PklResult<String> {
+        match self.get_path_or_err(path)? {
+            PklValue::String(v) => Ok(v),
+            _ => Err(PklError::WithoutContext(
+                format!("Property `{}` is not a string", path),
+                None,
+            )),
+        }
+    }
+
+    /// Retrieves a nested object value from the context by its dotted path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The dotted path of the value, such as `server.tls`.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the object value or an error message if not found or wrong type.
+    pub fn get_object_at(&self, path: &str) -> PklResult<IndexMap<String, PklValue>> {
+        match self.get_path_or_err(path)? {
+            PklValue::Object(v) => Ok(v),
+            _ => Err(PklError::WithoutContext(
+                format!("Property `{}` is not an object", path),
+                None,
+            )),
+        }
+    }
+
+    fn get_path_or_err(&self, path: &str) -> PklResult<PklValue> {
+        self.get_path(path)
+            .ok_or_else(|| PklError::WithoutContext(format!("Property `{}` not found", path), None))
+    }
+}
+
+/// Renders the value members of the context as Pkl properties, sorted by name.
+///
+/// Classes and local members are omitted, objects are rendered as blocks indented by two spaces.
+impl fmt::Display for Pkl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.table.render())
+    }
 }
 
 impl Default for Pkl {
@@ -297,3 +1166,557 @@ pub mod values {
     pub use crate::table::base::data_size::{Byte, Unit as DataSizeUnit};
     pub use crate::table::base::duration::Unit as DurationUnit;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{error_slice, parse, parse_err},
+        Pkl, PklError, PklSummary, PklType, PklValue, StatementKind,
+    };
+
+    #[test]
+    fn parse_overwrites_redefined_members() {
+        let mut pkl = parse("a = 1\nb = 2");
+        assert!(pkl.parse("a = 3").is_ok());
+
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(3)));
+        assert_eq!(pkl.get_value("b"), Some(PklValue::Int(2)));
+    }
+
+    #[test]
+    fn parse_append_rejects_redefined_members() {
+        let mut pkl = parse("a = 1\nb = 2");
+
+        let error = pkl.parse_append("c = 3\na = 3").err().unwrap();
+        assert_eq!(error.msg(), "Duplicate definition of member `a`");
+        // the context is left untouched
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(1)));
+        assert_eq!(pkl.get_value("c"), None);
+
+        assert!(pkl.parse_append("c = 3").is_ok());
+        assert_eq!(pkl.get_value("c"), Some(PklValue::Int(3)));
+    }
+
+    #[test]
+    fn parse_replace_clears_the_context() {
+        let mut pkl = parse("a = 1\nb = 2");
+        assert!(pkl.parse_replace("a = 3").is_ok());
+
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(3)));
+        assert_eq!(pkl.get_value("b"), None);
+
+        // a failing source leaves the context untouched
+        assert!(pkl.parse_replace("a = ").is_err());
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(3)));
+    }
+
+    #[test]
+    fn get_or_getters_fall_back_on_absent_or_null_members() {
+        let pkl = parse("b = true\ni = 1\nf = 1.5\ns = \"s\"\nn = null");
+
+        assert_eq!(pkl.get_bool_or("b", false).ok(), Some(true));
+        assert_eq!(pkl.get_int_or("i", 2).ok(), Some(1));
+        assert_eq!(pkl.get_float_or("f", 2.5).ok(), Some(1.5));
+        assert_eq!(pkl.get_string_or("s", "d").ok(), Some("s".to_owned()));
+
+        assert_eq!(pkl.get_bool_or("n", true).ok(), Some(true));
+        assert_eq!(pkl.get_int_or("absent", 2).ok(), Some(2));
+        assert_eq!(pkl.get_float_or("n", 2.5).ok(), Some(2.5));
+        assert_eq!(pkl.get_string_or("absent", "d").ok(), Some("d".to_owned()));
+
+        assert!(pkl.get_bool_or("s", true).is_err());
+        assert!(pkl.get_int_or("s", 2).is_err());
+        assert!(pkl.get_float_or("s", 2.5).is_err());
+        assert!(pkl.get_string_or("i", "d").is_err());
+    }
+
+    #[test]
+    fn get_class_instance_returns_the_class_name_and_fields() {
+        let pkl =
+            parse("class Person {\n  name: String\n}\np = new Person { name = \"x\" }\nn = 1");
+
+        let (class_name, fields) = pkl.get_class_instance("p").ok().unwrap();
+        assert_eq!(class_name, "Person");
+        assert_eq!(fields.get("name"), Some(&PklValue::String("x".to_owned())));
+
+        let not_an_instance = pkl.get_class_instance("n").err().unwrap();
+        assert_eq!(
+            not_an_instance.msg(),
+            "Property `n` is not a class instance"
+        );
+        let missing = pkl.get_class_instance("missing").err().unwrap();
+        assert_eq!(missing.msg(), "Property `missing` not found");
+    }
+
+    #[test]
+    fn deep_amend_merges_nested_objects_instead_of_replacing_them() {
+        let source = "base {\n  a {\n    x = 1\n    y = 2\n  }\n}\nderived = (base) {\n  a {\n    y = 3\n  }\n}";
+
+        let shallow = parse(source);
+        assert_eq!(shallow.get_path("derived.a.x"), None);
+
+        let mut deep = Pkl::new();
+        deep.set_deep_amend(true);
+        assert!(deep.parse(source).is_ok());
+        assert_eq!(deep.get_path("derived.a.x"), Some(PklValue::Int(1)));
+        assert_eq!(deep.get_path("derived.a.y"), Some(PklValue::Int(3)));
+    }
+
+    #[test]
+    fn get_member_exposes_the_flags_of_a_member() {
+        let pkl = parse("const a = 1\nfixed b = 2\nclass C {\n  x: Int\n}");
+
+        let a = pkl.get_member("a").unwrap();
+        assert!(a.is_const() && !a.is_fixed() && !a.is_local());
+        assert_eq!(a.to_owned().extract_value(), Some(PklValue::Int(1)));
+
+        assert!(pkl.get_member("b").unwrap().is_fixed());
+        assert!(pkl.get_member("C").unwrap().is_class());
+        assert!(pkl.get_member("missing").is_none());
+    }
+
+    #[test]
+    fn len_and_summary_count_the_same_members() {
+        let mut pkl = Pkl::new();
+        assert!(pkl.is_empty());
+
+        pkl.register_module("base", "x = 1");
+        let source = "import \"mem:base\"\nimport \"mem:base\" as other\nlocal secret = 1\nconst c = 2\na = 3\nclass A {\n  x: Int\n}";
+        assert!(pkl.parse(source).is_ok());
+
+        let summary = pkl.summary();
+        assert_eq!(
+            summary,
+            PklSummary {
+                values: 2,
+                classes: 1,
+                imports: 2
+            }
+        );
+        assert_eq!(pkl.len(), summary.values + summary.classes);
+        assert!(!pkl.is_empty());
+    }
+
+    #[test]
+    fn from_reader_parses_the_source_it_reads() {
+        let pkl = Pkl::from_reader(std::io::Cursor::new(b"a = 1\nb = a + 1".as_slice()));
+        assert_eq!(
+            pkl.ok().and_then(|pkl| pkl.get_value("b")),
+            Some(PklValue::Int(2))
+        );
+
+        let invalid_utf8 = std::io::Cursor::new([b'a', b' ', b'=', b' ', 0xff].as_slice());
+        let error = Pkl::from_reader(invalid_utf8).err().unwrap();
+        assert_eq!(
+            error.msg(),
+            "Error reading source: stream did not contain valid UTF-8"
+        );
+    }
+
+    #[test]
+    fn property_names_that_are_not_camel_case_emit_warnings() {
+        let mut pkl = Pkl::new();
+        pkl.set_lint_property_names(true);
+        let source = "myProp = 1\nmy_prop = 2\nhttp2Port = 3";
+        assert!(pkl.parse(source).is_ok());
+
+        let warnings = pkl.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "Property name `my_prop` is not camelCase"
+        );
+        assert_eq!(&source[warnings[0].span.to_owned()], "my_prop");
+
+        assert!(parse("my_prop = 2").warnings().is_empty());
+    }
+
+    #[test]
+    fn display_renders_value_members_deterministically() {
+        let pkl = parse("b = \"x\"\nlocal secret = 1\nclass A {\n  x: Int\n}\na {\n  n = 1.5\n  l = List(1, 2)\n}");
+
+        // members are sorted by name, classes and locals omitted
+        assert_eq!(
+            pkl.to_string(),
+            "a {\n  n = 1.5\n  l = List(1, 2)\n}\nb = \"x\"\n"
+        );
+    }
+
+    #[test]
+    fn validate_reports_the_errors_of_every_property() {
+        let source = "a: Int = \"x\"\nb: String = 1\nc = 1\nd = unknown\ne: Boolean = 2";
+        let pkl = Pkl::new();
+
+        let errors = pkl.validate(source).err().unwrap();
+        let slices = errors
+            .iter()
+            .map(|e| &source[e.span().unwrap()])
+            .collect::<Vec<_>>();
+        // type errors point at the declared type
+        assert_eq!(slices, ["Int", "String", "unknown", "Boolean"]);
+        assert!(pkl.is_empty());
+
+        assert!(pkl.validate("a: Int = 1").is_ok());
+    }
+
+    #[test]
+    fn typed_value_helpers_collect_matching_top_level_members() {
+        let pkl = parse(
+            r#"name = "pkl"
+count = 3
+local secret = "hidden"
+server {
+  port = 8080
+}
+title = "fast"
+client {
+  retries = 2
+}
+"#,
+        );
+
+        assert_eq!(
+            pkl.string_values(),
+            vec![("name", "pkl".to_owned()), ("title", "fast".to_owned())]
+        );
+
+        let objects = pkl.object_values();
+        assert_eq!(
+            objects.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            vec!["client", "server"]
+        );
+        assert_eq!(objects[1].1.get("port"), Some(&PklValue::Int(8080)));
+
+        assert_eq!(
+            pkl.values_of_type(|value| matches!(value, PklValue::Int(_))),
+            vec![("count", PklValue::Int(3))]
+        );
+    }
+
+    #[test]
+    fn get_doc_returns_the_doc_comments_of_fields_and_entries() {
+        let pkl = parse(
+            r#"class Server {
+  /// The port to listen on.
+  /// Defaults to 80.
+  port: Int
+  host: String
+}
+
+server {
+  /// The name of the host.
+  host {
+    /// Fully qualified.
+    name = "example.com"
+  }
+  port = 80
+}
+"#,
+        );
+
+        assert_eq!(
+            pkl.get_doc("Server.port"),
+            Some("The port to listen on.\nDefaults to 80.")
+        );
+        assert_eq!(pkl.get_doc("Server.host"), None);
+        assert_eq!(pkl.get_doc("server.host"), Some("The name of the host."));
+        assert_eq!(pkl.get_doc("server.host.name"), Some("Fully qualified."));
+        assert_eq!(pkl.get_doc("server.port"), None);
+    }
+
+    #[test]
+    fn evaluate_in_scope_layers_bindings_without_mutating_the_context() {
+        let pkl = parse("a = 1\nc = 10");
+
+        let sum = pkl
+            .evaluate_in_scope("a + b", &[("a", PklValue::Int(2)), ("b", PklValue::Int(3))])
+            .unwrap_or_else(|e| panic!("{}", e.msg()));
+        assert_eq!(sum, PklValue::Int(5));
+
+        let with_member = pkl
+            .evaluate_in_scope("c * b", &[("b", PklValue::Int(3))])
+            .unwrap_or_else(|e| panic!("{}", e.msg()));
+        assert_eq!(with_member, PklValue::Int(30));
+
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(1)));
+        assert_eq!(pkl.get_value("b"), None);
+    }
+
+    #[test]
+    fn check_syntax_does_not_evaluate_the_source() {
+        let source = "import \"missing_module.pkl\"\na: Int = \"not an int\"";
+        assert!(Pkl::check_syntax(source).is_ok());
+        assert!(Pkl::new().parse(source).is_err());
+
+        let error = Pkl::check_syntax("a = (1").err().unwrap();
+        assert!(error.span().is_some());
+    }
+
+    #[test]
+    fn amends_and_extends_targets_are_exposed() {
+        let mut amending = Pkl::new();
+        amending.register_module("base", "x = 1");
+        assert!(amending.parse("amends \"mem:base\"\nx = 2").is_ok());
+        assert_eq!(amending.amends_target(), Some("base"));
+        assert_eq!(amending.extends_target(), None);
+        assert!(amending.is_amending());
+        assert!(!amending.is_extending());
+
+        let mut extending = Pkl::new();
+        extending.register_module("base", "open module base\nx = 1");
+        assert!(extending.parse("extends \"mem:base\"\ny = 2").is_ok());
+        assert_eq!(extending.extends_target(), Some("base"));
+        assert_eq!(extending.amends_target(), None);
+        assert!(extending.is_extending());
+        assert!(!extending.is_amending());
+
+        let standalone = parse("x = 1");
+        assert!(!standalone.is_amending() && !standalone.is_extending());
+    }
+
+    #[test]
+    fn lenient_instances_warn_about_missing_and_unknown_fields() {
+        let source = "class Server {\n  host: String\n  port: Int\n}\ns = new Server { host = \"a\" }\nt = new Server {\n  host = \"b\"\n  port = 1\n  debug = true\n}\n";
+
+        assert_eq!(
+            parse_err(source).msg(),
+            "Missing key 'port' in instance of Server"
+        );
+
+        let mut pkl = Pkl::new();
+        pkl.set_lenient_instances(true);
+        if let Err(e) = pkl.parse(source) {
+            panic!("{}", e.msg());
+        }
+
+        let warnings = pkl
+            .warnings()
+            .iter()
+            .map(|warning| warning.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                "Missing key 'port' in instance of Server",
+                "Unknown key 'debug' in instance of Server"
+            ]
+        );
+        assert_eq!(
+            pkl.get_class_instance("t")
+                .ok()
+                .map(|(_, fields)| fields.len()),
+            Some(3)
+        );
+
+        // the types of the fields are still checked
+        let error = pkl
+            .parse("class Server {\n  port: Int\n}\ns = new Server { port = \"80\" }")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.msg(),
+            "Invalid type for key 'port', expected 'Int' but found 'String'"
+        );
+    }
+
+    #[test]
+    fn bundle_inlines_imported_values_into_a_standalone_document() {
+        let dir = std::env::temp_dir().join(format!("pkl_fast_bundle_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("shapes.pkl"),
+            "class Circle {\n  radius: Float\n}\nunit = 2.0\n",
+        )
+        .unwrap();
+
+        let bundled = Pkl::bundle(
+            "import \"shapes.pkl\"\nsize = shapes.unit\ncircle = new shapes.Circle { radius = 1.0 }\n",
+            &dir,
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+        let bundled = bundled.unwrap_or_else(|e| panic!("{}", e.msg()));
+
+        assert!(!bundled.contains("import"));
+        assert!(!bundled.contains("shapes"));
+
+        let pkl = parse(&bundled);
+        assert_eq!(pkl.get_value("size"), Some(PklValue::Float(2.0)));
+        assert_eq!(pkl.get_path("circle.radius"), Some(PklValue::Float(1.0)));
+    }
+
+    #[test]
+    fn outline_lists_the_top_level_statements_with_their_spans() {
+        let source = "import \"shapes.pkl\" as s\nlocal port = 80\nclass Server {\n  port: Int\n}\nwhen (true) {\n  a = 1\n}\n";
+        let outline = Pkl::outline(source).unwrap_or_else(|e| panic!("{}", e.msg()));
+
+        let kinds = outline
+            .iter()
+            .map(|(kind, _)| kind.to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                StatementKind::Import {
+                    uri: "shapes.pkl".to_owned(),
+                    name: "s".to_owned()
+                },
+                StatementKind::Property("port".to_owned()),
+                StatementKind::Class("Server".to_owned()),
+                StatementKind::When,
+            ]
+        );
+
+        let slices = outline
+            .iter()
+            .map(|(_, span)| &source[span.to_owned()])
+            .collect::<Vec<_>>();
+        assert_eq!(slices[1], "local port = 80");
+        assert!(slices[2].starts_with("class Server"));
+        assert!(slices[3].starts_with("when (true)"));
+    }
+
+    #[test]
+    fn path_aware_getters_enforce_the_type_of_the_leaf() {
+        let pkl = parse("server {\n  port = 8080\n  host {\n    name = \"localhost\"\n  }\n}\n");
+
+        assert_eq!(pkl.get_int_at("server.port").ok(), Some(8080));
+        assert_eq!(
+            pkl.get_string_at("server.host.name").ok(),
+            Some("localhost".to_owned())
+        );
+        assert_eq!(pkl.get_path("server.port"), Some(PklValue::Int(8080)));
+
+        assert_eq!(
+            pkl.get_string_at("server.port").err().unwrap().msg(),
+            "Property `server.port` is not a string"
+        );
+        assert_eq!(
+            pkl.get_int_at("server.missing").err().unwrap().msg(),
+            "Property `server.missing` not found"
+        );
+        assert!(pkl.get_int_at("server.port.value").is_err());
+    }
+
+    #[test]
+    fn registered_functions_are_callable_from_properties() {
+        let mut pkl = Pkl::new();
+        pkl.register_fn("double", |args| match args {
+            [PklValue::Int(x)] => Ok(PklValue::Int(x * 2)),
+            _ => Err(PklError::WithoutContext(
+                "double expects a single Int".to_owned(),
+                None,
+            )),
+        });
+
+        let source = "a = double(21)\nb = double(a)";
+        if let Err(e) = pkl.parse(source) {
+            panic!("{}", e.msg());
+        }
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(42)));
+        assert_eq!(pkl.get_value("b"), Some(PklValue::Int(84)));
+
+        // the errors of the function are reported on the call
+        let source = "c = double(\"x\")";
+        let error = pkl.parse(source).err().unwrap();
+        assert_eq!(error.msg(), "double expects a single Int");
+        assert_eq!(error_slice(source, &error), "double(\"x\")");
+
+        assert_eq!(
+            parse_err("d = triple(1)").msg(),
+            "unknown function `triple`"
+        );
+    }
+
+    #[test]
+    fn parse_expr_value_infers_the_type_of_the_value() {
+        let pkl = parse("class Point {\n  x: Int\n}");
+        let int = || PklType::Basic("Int".to_owned());
+
+        assert_eq!(
+            pkl.parse_expr_value("1 + 2").ok(),
+            Some((PklValue::Int(3), int()))
+        );
+
+        let list = pkl.parse_expr_value("List(1, 2, 3)").ok().map(|(_, t)| t);
+        assert_eq!(
+            list,
+            Some(PklType::WithAttributes {
+                name: "List".to_owned(),
+                attributes: vec![int()],
+            })
+        );
+
+        let instance = pkl
+            .parse_expr_value("new Point { x = 1 }")
+            .ok()
+            .map(|(_, t)| t);
+        assert_eq!(instance, Some(PklType::Basic("Point".to_owned())));
+    }
+
+    #[test]
+    fn relative_imports_resolve_against_the_base_path() {
+        let dir = std::env::temp_dir().join(format!("pkl_fast_base_path_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("base_path_shared.pkl"), "port = 8080\n").unwrap();
+
+        let source = "import \"base_path_shared.pkl\"\nport = base_path_shared.port";
+        // the file does not exist relative to the current directory
+        assert!(Pkl::new().parse(source).is_err());
+
+        let mut pkl = Pkl::new().with_base_path(&dir);
+        let parsed = pkl.parse(source);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(parsed.is_ok());
+        assert_eq!(pkl.get_value("port"), Some(PklValue::Int(8080)));
+    }
+
+    #[test]
+    fn schema_of_infers_the_types_of_the_fields() {
+        let pkl = parse(
+            "cfg {\n  port = 8080\n  host = \"x\"\n  tls {\n    enabled = true\n  }\n}\nport = 1",
+        );
+        let schema = pkl.schema_of("cfg").unwrap();
+
+        assert_eq!(schema.len(), 3);
+        assert_eq!(schema.get("port"), Some(&PklType::Basic("Int".to_owned())));
+        assert_eq!(
+            schema.get("host"),
+            Some(&PklType::Basic("String".to_owned()))
+        );
+        assert_eq!(
+            schema.get("tls"),
+            Some(&PklType::Basic("Dynamic".to_owned()))
+        );
+
+        assert!(pkl.schema_of("port").is_none());
+        assert!(pkl.schema_of("missing").is_none());
+    }
+
+    #[test]
+    fn repeated_unaliased_imports_are_deduplicated_or_denied() {
+        let source = "import \"mem:shared\"\nimport \"mem:shared\"\nx = shared.port";
+
+        let mut pkl = Pkl::new();
+        pkl.register_module("shared", "port = 80");
+        assert!(pkl.parse(source).is_ok());
+        assert_eq!(pkl.get_value("x"), Some(PklValue::Int(80)));
+
+        let mut pkl = Pkl::new();
+        pkl.register_module("shared", "port = 80");
+        pkl.set_deny_duplicate_imports(true);
+        let error = pkl.parse(source).err().unwrap();
+        assert_eq!(error.msg(), "Module `mem:shared` is already imported");
+        assert_eq!(error_slice(source, &error), "import \"mem:shared\"");
+        // the second import is the one reported
+        assert_eq!(error.span().unwrap().start, source.rfind("import").unwrap());
+
+        // aliases keep both imports apart
+        let mut pkl = Pkl::new();
+        pkl.register_module("shared", "port = 80");
+        pkl.set_deny_duplicate_imports(true);
+        assert!(pkl
+            .parse("import \"mem:shared\"\nimport \"mem:shared\" as other\nx = other.port")
+            .is_ok());
+    }
+}
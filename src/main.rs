@@ -1,116 +1,72 @@
-use new_pkl::{
-    pest::{parse, Rule},
-    Pkl, PklError,
-};
-use std::{env::args, fs, time::Instant};
+use new_pkl::{Pkl, PklError};
+use std::{env::args, fs, process::ExitCode};
 
-fn main() -> Result<(), (String, String, Option<String>)> {
-    let args = args().into_iter().collect::<Vec<_>>();
-
-    match args.get(1) {
-        Some(_) => {
-            let src = fs::read_to_string("main.pkl").unwrap();
-
-            let src = src.repeat(1000);
-            let time = Instant::now();
-
-            let mut pkl = Pkl::new();
+fn main() -> ExitCode {
+    let Some(path) = args().nth(1) else {
+        eprintln!("Usage: new-pkl <file.pkl>");
+        return ExitCode::FAILURE;
+    };
 
-            let ast = pkl.generate_ast(&src).map_err(|e: PklError| {
-                (
-                    e.msg().to_owned(),
-                    src[e.span().unwrap(/* safe */)].to_owned(),
-                    e.file_name().to_owned(),
-                )
-            })?;
-            // pkl.parse(&src).map_err(|e: PklError| {
-            //     (
-            //         e.msg().to_owned(),
-            //         src[e.span().unwrap(/* safe */)].to_owned(),
-            //         e.file_name().to_owned(),
-            //     )
-            // })?;
+    let src = match fs::read_to_string(&path) {
+        Ok(src) => src,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
 
-            // for stmt in pkl.generate_ast(&src).unwrap() {
-            //     println!("{stmt:?}",);
-            // }
+    let mut pkl = Pkl::new();
 
-            println!(
-                "{}ms to parse {} chars",
-                time.elapsed().as_millis(),
-                src.len()
-            );
-
-            println!("{:?}", pkl);
+    match pkl.parse(&src) {
+        Ok(()) => {
+            print!("{}", pkl);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            report(e.with_file_name(path), src);
+            ExitCode::FAILURE
         }
-        None => {
-            let src = fs::read_to_string("a.pkl").unwrap();
+    }
+}
 
-            let src = src.repeat(1);
-            let time = Instant::now();
+/// Prints the error as a rich diagnostic, the source being displayed around its span.
+#[cfg(feature = "miette")]
+fn report(error: PklError, src: String) {
+    use miette::GraphicalReportHandler;
 
-            let mut result = parse(&src).unwrap();
+    let report = error.into_report(src);
+    let mut output = String::new();
 
-            let file = result.next().unwrap().into_inner();
+    match GraphicalReportHandler::new().render_report(&mut output, report.as_ref()) {
+        Ok(()) => eprint!("{}", output),
+        Err(_) => eprintln!("Error: {}", report),
+    }
+}
+
+/// Prints the error along with the line and column it occurred at.
+#[cfg(not(feature = "miette"))]
+fn report(error: PklError, src: String) {
+    let file_name = error.file_name().to_owned().unwrap_or_default();
 
-            let mut doc_comment: Option<String> = None;
-            for record in file {
-                match record.as_rule() {
-                    Rule::stmt => {
-                        for stmt in record.into_inner() {
-                            match stmt.as_rule() {
-                                _ => {
-                                    // when managing the statement
-                                    // take the value inside the doc
-                                    // comment to attach it to attach
-                                    // it to the stmt if necessary
-                                    for stmt in stmt.into_inner() {
-                                        match stmt.as_rule() {
-                                            Rule::COMMENT => {
-                                                // println!("comment: {:?}", stmt.as_rule())
-                                            }
-                                            _ => {
-                                                // println!("any stmt: {:?}", stmt.as_rule());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Rule::EOI => (),
-                    Rule::COMMENT => {
-                        for com in record.into_inner() {
-                            match com.as_rule() {
-                                Rule::doc_comment => {
-                                    doc_comment = Some(
-                                        doc_comment
-                                            .map(|mut x| {
-                                                x.push_str(com.as_str());
-                                                x
-                                            })
-                                            .unwrap_or(String::from(com.as_str())),
-                                    );
-                                }
-                                Rule::annotation => {
-                                    // take care of them here
-                                }
-                                Rule::line_comment | Rule::multiline_comment => (),
-                                _ => unreachable!(),
-                            }
-                        }
-                    }
-                    _ => unreachable!(),
-                }
-            }
+    match error.span() {
+        Some(span) => {
+            let before = &src[..span.start.min(src.len())];
+            let line = before.matches('\n').count() + 1;
+            let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
 
-            println!(
-                "{}ms to parse {} chars",
-                time.elapsed().as_millis(),
-                src.len()
+            eprintln!(
+                "Error: {}\n  --> {}:{}:{}",
+                error.msg(),
+                file_name,
+                line,
+                column
             );
         }
-    };
+        None => eprintln!("Error: {}\n  --> {}", error.msg(), file_name),
+    }
 
-    Ok(())
+    let chain = error.import_chain();
+    if chain.len() > 1 {
+        eprintln!("  = note: in imported module {}", chain.join(" <- "));
+    }
 }
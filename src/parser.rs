@@ -1,12 +1,15 @@
-use crate::{lexer::PklToken, PklResult};
+use crate::{
+    lexer::{decode_escapes, PklToken},
+    PklError, PklResult,
+};
 use expr::{member_expr::parse_member_expr_member, object::parse_object, PklExpr};
-use hashbrown::HashMap;
+use indexmap::IndexMap;
 use logos::{Lexer, Source};
 use statement::{
     import::Import, module::Module, parse_stmt, property::Property, typealias::TypeAlias,
     PklStatement,
 };
-use std::ops::Range;
+use std::{borrow::Cow, ops::Range};
 use types::{parse_type, AstPklType};
 use utils::parse_id;
 use value::AstPklValue;
@@ -18,22 +21,54 @@ pub mod value;
 
 mod utils;
 
-pub type ExprHash<'a> = (HashMap<&'a str, PklExpr<'a>>, Range<usize>);
+/// The fields and entries of an object, kept together in their declaration order,
+/// followed by its span, its elements, such as the `1` and `2` of `new Listing { 1 2 }`,
+/// and the doc comments of its fields and entries by name.
+pub type ExprHash<'a> = (
+    Vec<(ObjectKey<'a>, PklExpr<'a>)>,
+    Range<usize>,
+    Vec<PklExpr<'a>>,
+    IndexMap<Cow<'a, str>, String>,
+);
+
+/// The key of an object member, either the name of a field, such as `name = 1`,
+/// or the key of an entry, such as `["my-key"] = 1`, which is an expression
+/// evaluated along with the object.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ObjectKey<'a> {
+    Field(Cow<'a, str>),
+    Entry(PklExpr<'a>),
+}
 
+/// A name and its span, the escape sequences of a name
+/// enclosed in backticks, such as `` `a\u{20}b` ``, being decoded.
 #[derive(Debug, PartialEq, Clone)]
-pub struct Identifier<'a>(pub &'a str, pub Range<usize>);
+pub struct Identifier<'a>(pub Cow<'a, str>, pub Range<usize>);
 
 impl<'a> Identifier<'a> {
+    /// Builds the identifier of a name as written in the source, decoding its escape sequences.
+    pub fn new(raw: &'a str, span: Range<usize>) -> Self {
+        Self(decode_escapes(raw), span)
+    }
     pub fn span(&self) -> Range<usize> {
         self.1.to_owned()
     }
     pub fn value(&self) -> &str {
-        self.0
+        &self.0
     }
 }
 
-/// Parse a token stream into a Pkl statement.
+/// Parse a token stream into Pkl statements, reporting the errors as syntax errors.
 pub fn parse_pkl<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<PklStatement<'a>>> {
+    parse_statements(lexer, false).map_err(PklError::into_syntax)
+}
+
+/// Parses statements until the end of the input,
+/// or until a closing brace when `in_block` is set.
+fn parse_statements<'a>(
+    lexer: &mut Lexer<'a, PklToken<'a>>,
+    in_block: bool,
+) -> PklResult<Vec<PklStatement<'a>>> {
     let mut statements = Vec::with_capacity(16); // Assuming typical file size for preallocation
     let mut is_newline = true;
 
@@ -91,8 +126,9 @@ pub fn parse_pkl<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<PklSt
             }
 
             Ok(PklToken::Dot) => {
-                if let Some(PklStatement::Property(Property { value, .. })) =
-                    statements.last_mut().map(PklStatement::inner_mut)
+                if let Some(PklStatement::Property(Property {
+                    value: Some(value), ..
+                })) = statements.last_mut().map(PklStatement::inner_mut)
                 {
                     let expr_member = parse_member_expr_member(lexer)?;
                     let expr_start = value.span().start;
@@ -109,7 +145,7 @@ pub fn parse_pkl<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<PklSt
                 {
                     let other_component = parse_id(lexer)?;
                     let new_span = full_name.1.start..other_component.1.end;
-                    *full_name = Identifier(
+                    *full_name = Identifier::new(
                         lexer.source().slice(new_span.to_owned()).unwrap(),
                         new_span.to_owned(),
                     );
@@ -123,18 +159,21 @@ pub fn parse_pkl<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<PklSt
                 }
             }
             Ok(PklToken::OpenBrace) => {
-                if let Some(PklStatement::Property(Property { value, span, .. })) =
-                    statements.last_mut().map(PklStatement::inner_mut)
+                if let Some(PklStatement::Property(Property {
+                    value: Some(value),
+                    span,
+                    ..
+                })) = statements.last_mut().map(PklStatement::inner_mut)
                 {
                     match value {
                         PklExpr::Value(AstPklValue::Object(_))
                         | PklExpr::Value(AstPklValue::AmendingObject(_, _, _))
                         | PklExpr::Value(AstPklValue::AmendedObject(_, _, _)) => {
-                            let (new_object, object_span) = parse_object(lexer)?;
-                            let end = object_span.end;
+                            let object = parse_object(lexer)?;
+                            let end = object.1.end;
                             *value = AstPklValue::AmendedObject(
                                 Box::new(value.clone().extract_value()),
-                                (new_object, object_span),
+                                object,
                                 span.start..end,
                             )
                             .into();
@@ -155,10 +194,14 @@ pub fn parse_pkl<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<PklSt
                         .into());
                 }
             }
-            Ok(PklToken::Space)
-            | Ok(PklToken::DocComment(_))
-            | Ok(PklToken::LineComment(_))
-            | Ok(PklToken::MultilineComment(_)) => {
+            Ok(PklToken::MultilineComment(comment)) => {
+                // a comment spanning multiple lines separates statements as well
+                if comment.contains('\n') {
+                    is_newline = true;
+                }
+                continue;
+            }
+            Ok(PklToken::Space) | Ok(PklToken::DocComment(_)) | Ok(PklToken::LineComment(_)) => {
                 // Skip spaces and comments
                 continue;
             }
@@ -166,13 +209,21 @@ pub fn parse_pkl<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<PklSt
                 is_newline = true;
                 continue;
             }
+            Ok(PklToken::CloseBrace) if in_block => return Ok(statements),
             // parses any statement
             Ok(token) if is_newline => {
-                let stmt = parse_stmt(lexer, Some(token))?;
+                let (stmt, consumed_newline) = parse_stmt(lexer, Some(token))?;
                 statements.push(stmt);
-                is_newline = false;
+                is_newline = consumed_newline;
             }
-            Err(e) => return Err((e.to_string(), lexer.span()).into()),
+            Ok(token) if is_statement_start(&token) => {
+                return Err((
+                    "statements must be separated by a newline".to_owned(),
+                    lexer.span(),
+                )
+                    .into());
+            }
+            Err(e) => return Err((e, lexer.span()).into()),
             _ => {
                 return Err((
                     "unexpected token here (context: global)".to_owned(),
@@ -183,5 +234,58 @@ pub fn parse_pkl<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<PklSt
         }
     }
 
+    if in_block {
+        return Err(("Missing closing brace".to_owned(), lexer.span()).into());
+    }
+
     Ok(statements)
 }
+
+/// Whether the token can be the first token of a statement.
+fn is_statement_start(token: &PklToken<'_>) -> bool {
+    matches!(
+        token,
+        PklToken::TypeAlias
+            | PklToken::Import
+            | PklToken::Extends
+            | PklToken::Amends
+            | PklToken::Class
+            | PklToken::OpenClass
+            | PklToken::AbstractClass
+            | PklToken::Module
+            | PklToken::OpenModule
+            | PklToken::Fixed
+            | PklToken::Const
+            | PklToken::Local
+            | PklToken::When
+            | PklToken::Identifier(_)
+            | PklToken::IllegalIdentifier(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{error_slice, parse, parse_err},
+        PklValue,
+    };
+
+    #[test]
+    fn statements_on_separate_lines_are_parsed() {
+        let pkl = parse("a = 1\n\nb = 2 // comment\n/* multiline\ncomment */ c = 3");
+        assert_eq!(pkl.get_value("b"), Some(PklValue::Int(2)));
+        assert_eq!(pkl.get_value("c"), Some(PklValue::Int(3)));
+    }
+
+    #[test]
+    fn statements_on_the_same_line_are_rejected() {
+        let source = "a = 1 b = 2";
+        let error = parse_err(source);
+
+        assert_eq!(error.msg(), "statements must be separated by a newline");
+        assert_eq!(error_slice(source, &error), "b");
+
+        let error = parse_err("a = 1 /* comment */ b = 2");
+        assert_eq!(error.msg(), "statements must be separated by a newline");
+    }
+}
@@ -1,15 +1,21 @@
-use super::{value::AstPklValue, ExprHash, Identifier, PklResult};
+use super::{utils::peek_non_space, value::AstPklValue, ExprHash, Identifier, PklResult};
 use crate::lexer::PklToken;
 use class::parse_class_instance;
 use fn_call::{parse_fn_call, FuncCall};
+use lambda::{is_lambda, parse_lambda, Lambda};
 use logos::{Lexer, Span};
-use member_expr::ExprMember;
+use member_expr::{parse_member_expr_member, ExprMember};
 use object::parse_amended_object;
+use operation::{parse_operation, Operation};
+use string::parse_multiline_string;
 
 pub mod class;
 pub mod fn_call;
+pub mod lambda;
 pub mod member_expr;
 pub mod object;
+pub mod operation;
+pub mod string;
 
 pub mod long;
 
@@ -19,6 +25,18 @@ pub enum PklExpr<'a> {
     Value(AstPklValue<'a>),
     MemberExpression(Box<PklExpr<'a>>, ExprMember<'a>, Span),
     FuncCall(FuncCall<'a>),
+    Operation(Operation<'a>),
+    Parenthesized(Box<PklExpr<'a>>, Span),
+    /// A spread expression, such as `...xs`, only valid in `List(...)` arguments.
+    Spread(Box<PklExpr<'a>>, Span),
+    /// A logical negation, such as `!enabled`.
+    Not(Box<PklExpr<'a>>, Span),
+    /// A numeric negation, such as `-offset`.
+    Negate(Box<PklExpr<'a>>, Span),
+    /// A non-null assertion, such as `port!!`.
+    NonNull(Box<PklExpr<'a>>, Span),
+    /// A lambda, such as `(n) -> n * 2`, only valid as the argument of a method taking a function.
+    Lambda(Lambda<'a>),
 }
 
 impl<'a> PklExpr<'a> {
@@ -36,20 +54,87 @@ impl<'a> PklExpr<'a> {
             Self::Identifier(Identifier(_, span)) => span.to_owned(),
             Self::MemberExpression(_, _, span) => span.to_owned(),
             Self::FuncCall(FuncCall(_, _, span)) => span.to_owned(),
+            Self::Operation(operation) => operation.span(),
+            Self::Parenthesized(_, span) => span.to_owned(),
+            Self::Spread(_, span) => span.to_owned(),
+            Self::Not(_, span) => span.to_owned(),
+            Self::Negate(_, span) => span.to_owned(),
+            Self::NonNull(_, span) => span.to_owned(),
+            Self::Lambda(lambda) => lambda.span(),
         }
     }
 }
 
+/// Parses a whole expression, operations included.
 pub fn parse_expr<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklExpr<'a>> {
+    let operand = parse_operand(lexer)?;
+    parse_operation(lexer, operand, 0)
+}
+
+/// Parses an operand, that is a primary expression
+/// followed by its eventual member accesses.
+pub fn parse_operand<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklExpr<'a>> {
+    let expr = parse_primary_expr(lexer)?;
+    parse_operand_accesses(lexer, expr)
+}
+
+/// Parses the member accesses and non-null assertions following an already parsed expression.
+pub fn parse_operand_accesses<'a>(
+    lexer: &mut Lexer<'a, PklToken<'a>>,
+    mut expr: PklExpr<'a>,
+) -> PklResult<PklExpr<'a>> {
+    loop {
+        match peek_non_space(lexer) {
+            (Some(Ok(PklToken::Dot)), peeked) => {
+                *lexer = peeked;
+
+                let expr_member = parse_member_expr_member(lexer)?;
+                let expr_start = expr.span().start;
+                let expr_end = expr_member.span().end;
+
+                expr = PklExpr::MemberExpression(Box::new(expr), expr_member, expr_start..expr_end);
+            }
+            (Some(Ok(PklToken::NonNullAssertion)), peeked) => {
+                *lexer = peeked;
+
+                let span = expr.span().start..lexer.span().end;
+                expr = PklExpr::NonNull(Box::new(expr), span);
+            }
+            _ => return Ok(expr),
+        }
+    }
+}
+
+fn parse_primary_expr<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklExpr<'a>> {
     while let Some(token) = lexer.next() {
         match token {
             Ok(PklToken::Bool(b)) => return Ok(AstPklValue::Bool(b, lexer.span()).into()),
             Ok(PklToken::Identifier(id)) | Ok(PklToken::IllegalIdentifier(id)) => {
-                return Ok(PklExpr::Identifier(Identifier(id, lexer.span())))
+                return Ok(PklExpr::Identifier(Identifier::new(id, lexer.span())))
             }
             Ok(PklToken::New) => return parse_class_instance(lexer),
+            Ok(PklToken::OperatorNot) => {
+                // the negation applies to the whole operand, such as `!list.isEmpty`
+                let start = lexer.span().start;
+                let operand = parse_operand(lexer)?;
+                let end = operand.span().end;
+
+                return Ok(PklExpr::Not(Box::new(operand), start..end));
+            }
+            Ok(PklToken::OperatorMinus) => {
+                if let Some(min_int) = parse_min_int(lexer) {
+                    return parse_operand_accesses(lexer, min_int);
+                }
+
+                // as `!`, the negation applies to the whole operand, such as `-list.length`
+                let start = lexer.span().start;
+                let operand = parse_operand(lexer)?;
+                let end = operand.span().end;
+
+                return Ok(PklExpr::Negate(Box::new(operand), start..end));
+            }
             Ok(PklToken::FunctionCall(fn_name)) => {
-                let fn_call = parse_fn_call(lexer, Identifier(fn_name, lexer.span()))?;
+                let fn_call = parse_fn_call(lexer, Identifier::new(fn_name, lexer.span()))?;
 
                 return Ok(PklExpr::FuncCall(fn_call));
             }
@@ -61,21 +146,97 @@ pub fn parse_expr<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklExpr<
             Ok(PklToken::Float(f)) => return Ok(AstPklValue::Float(f, lexer.span()).into()),
             Ok(PklToken::String(s)) => return Ok(AstPklValue::String(s, lexer.span()).into()),
             Ok(PklToken::MultiLineString(s)) => {
-                return Ok(AstPklValue::MultiLineString(s, lexer.span()).into())
+                // the content starts after the opening `"""` and its newline
+                let start = lexer.span().start + 4;
+                let fragments = parse_multiline_string(lexer.source(), s, start)?;
+                return Ok(AstPklValue::MultiLineString(fragments, lexer.span()).into());
             }
-            Ok(PklToken::OpenParen) => return Ok(parse_amended_object(lexer)?.into()),
+            Ok(PklToken::OpenParen) if is_amended_object(lexer) => {
+                return Ok(parse_amended_object(lexer)?.into())
+            }
+            Ok(PklToken::OpenParen) if is_lambda(lexer) => {
+                return Ok(PklExpr::Lambda(parse_lambda(lexer)?))
+            }
+            Ok(PklToken::OpenParen) => return parse_parenthesized_expr(lexer),
             Ok(PklToken::Space)
             | Ok(PklToken::NewLine)
             | Ok(PklToken::DocComment(_))
             | Ok(PklToken::LineComment(_))
             | Ok(PklToken::MultilineComment(_)) => continue,
-            Err(e) => return Err((e.to_string(), lexer.span()).into()),
+            Err(e) => return Err((e, lexer.span()).into()),
             _ => return Err(("unexpected token here".to_owned(), lexer.span()).into()),
         }
     }
     Err(("empty expressions are not allowed".to_owned(), lexer.span()).into())
 }
 
+/// Parses `-9223372036854775808`, the minimum Int, following its `-`,
+/// as decimal literals are unsigned and `9223372036854775808` alone is out of range.
+fn parse_min_int<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> Option<PklExpr<'a>> {
+    let start = lexer.span().start;
+    let mut peeked = lexer.to_owned();
+
+    match peeked.next() {
+        Some(Err(_))
+            if peeked.span().start == start + 1
+                && peeked.slice().replace('_', "") == "9223372036854775808" =>
+        {
+            *lexer = peeked;
+            Some(AstPklValue::Int(i64::MIN, start..lexer.span().end).into())
+        }
+        _ => None,
+    }
+}
+
+/// Checks, without consuming anything, if the tokens following an open parenthesis
+/// are the `(name) {` amend form rather than a grouping expression.
+fn is_amended_object<'a>(lexer: &Lexer<'a, PklToken<'a>>) -> bool {
+    let mut lexer = lexer.to_owned();
+
+    if !matches!(
+        lexer.next(),
+        Some(Ok(PklToken::Identifier(_))) | Some(Ok(PklToken::IllegalIdentifier(_)))
+    ) {
+        return false;
+    }
+    if !matches!(lexer.next(), Some(Ok(PklToken::CloseParen))) {
+        return false;
+    }
+
+    matches!(peek_non_space(&lexer).0, Some(Ok(PklToken::OpenBrace)))
+}
+
+pub(crate) fn parse_parenthesized_expr<'a>(
+    lexer: &mut Lexer<'a, PklToken<'a>>,
+) -> PklResult<PklExpr<'a>> {
+    let start = lexer.span().start;
+    let expr = parse_expr(lexer)?;
+
+    while let Some(token) = lexer.next() {
+        match token {
+            Ok(PklToken::CloseParen) => {
+                let end = lexer.span().end;
+                return Ok(PklExpr::Parenthesized(Box::new(expr), start..end));
+            }
+            Ok(PklToken::Space)
+            | Ok(PklToken::NewLine)
+            | Ok(PklToken::DocComment(_))
+            | Ok(PklToken::LineComment(_))
+            | Ok(PklToken::MultilineComment(_)) => continue,
+            Err(e) => return Err((e, lexer.span()).into()),
+            _ => {
+                return Err((
+                    "expected close parenthesis (context: parenthesized expression)".to_owned(),
+                    lexer.span(),
+                )
+                    .into())
+            }
+        }
+    }
+
+    Err(("Missing close parenthesis".to_owned(), lexer.span()).into())
+}
+
 impl<'a> From<AstPklValue<'a>> for PklExpr<'a> {
     fn from(value: AstPklValue<'a>) -> Self {
         PklExpr::Value(value)
@@ -83,7 +244,7 @@ impl<'a> From<AstPklValue<'a>> for PklExpr<'a> {
 }
 impl<'a> From<(&'a str, Span)> for PklExpr<'a> {
     fn from((value, indexes): (&'a str, Span)) -> Self {
-        PklExpr::Identifier(Identifier(value, indexes))
+        PklExpr::Identifier(Identifier::new(value, indexes))
     }
 }
 impl<'a> From<ExprHash<'a>> for PklExpr<'a> {
@@ -91,3 +252,38 @@ impl<'a> From<ExprHash<'a>> for PklExpr<'a> {
         PklExpr::Value(value.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{eval, parse},
+        PklValue,
+    };
+
+    #[test]
+    fn parenthesized_expressions_group_operations() {
+        assert_eq!(eval("(1 + 2) * 3 == 9"), PklValue::Bool(true));
+        assert_eq!(eval("1 + 2 * 3"), PklValue::Int(7));
+        assert_eq!(eval("((1 + 2)) * (3)"), PklValue::Int(9));
+    }
+
+    #[test]
+    fn parenthesized_names_followed_by_a_body_are_amended() {
+        let pkl = parse("base {\n  y = 2\n}\nx = (base) {\n  x = 1\n}\ngroup = (base).y");
+
+        let PklValue::Object(fields) = pkl.get_value("x").unwrap() else {
+            panic!("`x` should be an object");
+        };
+        assert_eq!(fields.get("x"), Some(&PklValue::Int(1)));
+        assert_eq!(fields.get("y"), Some(&PklValue::Int(2)));
+        assert_eq!(pkl.get_value("group"), Some(PklValue::Int(2)));
+    }
+
+    #[test]
+    fn single_line_strings_have_their_escapes_decoded() {
+        assert_eq!(
+            eval(r#""a\tb\u{41}\"\\""#),
+            PklValue::String("a\tbA\"\\".to_owned())
+        );
+    }
+}
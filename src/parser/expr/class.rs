@@ -1,19 +1,14 @@
 use super::PklExpr;
 use crate::parser::expr::object::parse_object;
-use crate::parser::utils::parse_open_brace;
+use crate::parser::utils::{parse_id, parse_open_brace, peek_non_space};
 use crate::parser::value::AstPklValue;
-use crate::parser::Identifier;
+use crate::parser::{ExprHash, Identifier};
 use crate::PklResult;
 use crate::{lexer::PklToken, parser::utils::parse_multispaces_until};
-use hashbrown::HashMap;
 use logos::{Lexer, Span};
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct ClassInstance<'a>(
-    pub Option<Identifier<'a>>,
-    pub (HashMap<&'a str, PklExpr<'a>>, Span),
-    pub Span,
-);
+pub struct ClassInstance<'a>(pub Option<Identifier<'a>>, pub ExprHash<'a>, pub Span);
 
 fn parse_id_or_open_brace<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklToken<'a>> {
     parse_multispaces_until!(
@@ -24,6 +19,23 @@ fn parse_id_or_open_brace<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<
     )
 }
 
+/// Parses the remaining components of a class name qualified
+/// by the name of an imported module, such as `shapes.Circle`.
+fn parse_qualified_name<'a>(
+    lexer: &mut Lexer<'a, PklToken<'a>>,
+    mut name: Identifier<'a>,
+) -> PklResult<Identifier<'a>> {
+    while let (Some(Ok(PklToken::Dot)), peeked) = peek_non_space(lexer) {
+        *lexer = peeked;
+
+        let component = parse_id(lexer)?;
+        let span = name.1.start..component.1.end;
+        name = Identifier(format!("{}.{}", name.0, component.0).into(), span);
+    }
+
+    Ok(name)
+}
+
 /// Function called after 'new' keyword is found.
 pub fn parse_class_instance<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklExpr<'a>> {
     let start = lexer.span().start;
@@ -31,7 +43,10 @@ pub fn parse_class_instance<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResul
     let class_name = match parse_id_or_open_brace(lexer)? {
         PklToken::OpenBrace => None,
         PklToken::Identifier(id) | PklToken::IllegalIdentifier(id) => {
-            let name = Some(Identifier(id, lexer.span()));
+            let name = Some(parse_qualified_name(
+                lexer,
+                Identifier::new(id, lexer.span()),
+            )?);
             parse_open_brace(lexer)?;
             name
         }
@@ -1,9 +1,5 @@
-use super::{member_expr::parse_member_expr_member, PklExpr};
-use crate::{
-    lexer::PklToken,
-    parser::{expr::class::parse_class_instance, value::AstPklValue, Identifier},
-    PklResult,
-};
+use super::{parse_expr, PklExpr};
+use crate::{lexer::PklToken, parser::Identifier, PklResult};
 use logos::{Lexer, Span};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -24,79 +20,48 @@ pub fn parse_fn_call<'a>(
     let mut is_comma = true;
 
     loop {
-        match lexer.next() {
-            Some(Ok(token)) => match token {
-                PklToken::Dot if !is_comma => {
-                    if let Some(last) = values.last_mut() {
-                        let expr_member = parse_member_expr_member(lexer)?;
-                        let expr_start = last.span().start;
-                        let expr_end = expr_member.span().end;
+        let mut peeked = lexer.clone();
 
-                        *last = PklExpr::MemberExpression(
-                            Box::new(last.clone()),
-                            expr_member,
-                            expr_start..expr_end,
-                        );
-                    } else {
-                        return Err(("unexpected token '.'".to_owned(), lexer.span()).into());
-                    }
-                }
+        match peeked.next() {
+            Some(Ok(token)) => match token {
                 PklToken::Comma if !is_comma => {
+                    *lexer = peeked;
                     is_comma = true;
                 }
                 PklToken::CloseParen => {
+                    *lexer = peeked;
                     let end = lexer.span().end;
-                    return Ok(FuncCall(id, values.into(), start..end));
+                    return Ok(FuncCall(id, values, start..end));
                 }
                 PklToken::Space
                 | PklToken::NewLine
                 | PklToken::DocComment(_)
                 | PklToken::LineComment(_)
-                | PklToken::MultilineComment(_) => {}
-                PklToken::Bool(b) if is_comma => {
-                    values.push(AstPklValue::Bool(b, lexer.span()).into());
-                    is_comma = false;
+                | PklToken::MultilineComment(_) => {
+                    *lexer = peeked;
                 }
-                PklToken::Identifier(id) | PklToken::IllegalIdentifier(id) if is_comma => {
-                    values.push(PklExpr::Identifier(Identifier(id, lexer.span())));
-                    is_comma = false;
-                }
-                PklToken::New if is_comma => {
-                    values.push(parse_class_instance(lexer)?);
-                    is_comma = false;
-                }
-                PklToken::FunctionCall(fn_name) if is_comma => {
-                    values.push(PklExpr::FuncCall(parse_fn_call(
-                        lexer,
-                        Identifier(fn_name, lexer.span()),
-                    )?));
+                PklToken::Spread if is_comma => {
+                    *lexer = peeked;
+                    let spread_start = lexer.span().start;
+                    let expr = parse_expr(lexer)?;
+                    let spread_end = expr.span().end;
 
+                    values.push(PklExpr::Spread(Box::new(expr), spread_start..spread_end));
                     is_comma = false;
                 }
-                PklToken::Int(i)
-                | PklToken::OctalInt(i)
-                | PklToken::HexInt(i)
-                | PklToken::BinaryInt(i)
-                    if is_comma =>
-                {
-                    values.push(AstPklValue::Int(i, lexer.span()).into());
-                    is_comma = false;
-                }
-                PklToken::Float(f) if is_comma => {
-                    values.push(AstPklValue::Float(f, lexer.span()).into());
+                _ if is_comma => {
+                    values.push(parse_expr(lexer)?);
                     is_comma = false;
                 }
-                PklToken::String(s) if is_comma => {
-                    values.push(AstPklValue::String(s, lexer.span()).into());
-                    is_comma = false;
-                }
-                PklToken::MultiLineString(s) if is_comma => {
-                    values.push(AstPklValue::MultiLineString(s, lexer.span()).into());
-                    is_comma = false;
+                _ => {
+                    *lexer = peeked;
+                    return Err(("unexpected token here".to_owned(), lexer.span()).into());
                 }
-                _ => return Err(("unexpected token here".to_owned(), lexer.span()).into()),
             },
-            Some(Err(e)) => return Err((e.to_string(), lexer.span()).into()),
+            Some(Err(e)) => {
+                *lexer = peeked;
+                return Err((e, lexer.span()).into());
+            }
             None => return Err(("Missing list close parenthesis".to_owned(), lexer.span()).into()),
         }
     }
@@ -0,0 +1,67 @@
+use super::{parse_expr, PklExpr};
+use crate::{
+    lexer::PklToken,
+    parser::{utils::peek_non_space, Identifier},
+    PklResult,
+};
+use logos::{Lexer, Span};
+
+/// A function literal: its parameters, its body and the range, such as `(n) -> n % 2 == 0`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lambda<'a>(pub Vec<Identifier<'a>>, pub Box<PklExpr<'a>>, pub Span);
+
+impl<'a> Lambda<'a> {
+    pub fn span(&self) -> Span {
+        self.2.to_owned()
+    }
+}
+
+/// Checks, without consuming anything, if the tokens following an open parenthesis
+/// are the parameters of a lambda, that is identifiers separated by commas,
+/// a close parenthesis and an arrow.
+pub fn is_lambda<'a>(lexer: &Lexer<'a, PklToken<'a>>) -> bool {
+    let mut lexer = lexer.to_owned();
+    let mut expects_parameter = true;
+
+    loop {
+        let (token, peeked) = peek_non_space(&lexer);
+        lexer = peeked;
+
+        match token {
+            Some(Ok(PklToken::Identifier(_))) | Some(Ok(PklToken::IllegalIdentifier(_)))
+                if expects_parameter =>
+            {
+                expects_parameter = false
+            }
+            Some(Ok(PklToken::Comma)) if !expects_parameter => expects_parameter = true,
+            Some(Ok(PklToken::CloseParen)) => break,
+            _ => return false,
+        }
+    }
+
+    matches!(peek_non_space(&lexer).0, Some(Ok(PklToken::Arrow)))
+}
+
+/// Parses a lambda whose open parenthesis was just consumed,
+/// [`is_lambda`] having checked its parameters.
+pub fn parse_lambda<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Lambda<'a>> {
+    let start = lexer.span().start;
+    let mut parameters = Vec::new();
+
+    loop {
+        match lexer.next() {
+            Some(Ok(PklToken::Identifier(name))) | Some(Ok(PklToken::IllegalIdentifier(name))) => {
+                parameters.push(Identifier::new(name, lexer.span()))
+            }
+            Some(Ok(PklToken::Arrow)) => break,
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err((e, lexer.span()).into()),
+            None => return Err(("Missing the body of the lambda".to_owned(), lexer.span()).into()),
+        }
+    }
+
+    let body = parse_expr(lexer)?;
+    let end = body.span().end;
+
+    Ok(Lambda(parameters, Box::new(body), start..end))
+}
@@ -29,24 +29,22 @@ impl<'a> From<Identifier<'a>> for ExprMember<'a> {
 pub fn parse_member_expr_member<'a>(
     lexer: &mut Lexer<'a, PklToken<'a>>,
 ) -> PklResult<ExprMember<'a>> {
-    let start = lexer.span().end;
-
     while let Some(token) = lexer.next() {
         match token {
             Ok(PklToken::Identifier(id)) | Ok(PklToken::IllegalIdentifier(id)) => {
-                return Ok(Identifier(id, start..lexer.span().end).into())
+                return Ok(Identifier::new(id, lexer.span()).into())
             }
             Ok(PklToken::FunctionCall(id)) => {
                 return Ok(ExprMember::FuncCall(parse_fn_call(
                     lexer,
-                    Identifier(id, lexer.span()),
+                    Identifier::new(id, lexer.span()),
                 )?))
             }
             Ok(PklToken::NewLine) | Ok(PklToken::Space) => {
                 // Skip spaces and newlines
             }
             Err(e) => {
-                return Err((e.to_string(), lexer.span()).into());
+                return Err((e, lexer.span()).into());
             }
             _ => {
                 return Err((
@@ -64,3 +62,19 @@ pub fn parse_member_expr_member<'a>(
     )
         .into())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{error_slice, eval_err};
+
+    #[test]
+    fn errors_in_a_chain_point_at_the_failing_call() {
+        let source = "\" a,b \".trim().toUpperCase().split(1)";
+        let error = eval_err(source);
+        assert_eq!(error_slice(source, &error), "split(1)");
+
+        let source = "\"a\".trim().bogus.length";
+        let error = eval_err(source);
+        assert_eq!(error_slice(source, &error), "bogus");
+    }
+}
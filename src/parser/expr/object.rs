@@ -1,19 +1,41 @@
-use super::PklExpr;
+use super::{parse_expr, PklExpr};
 use crate::{
-    lexer::PklToken,
-    parser::{statement::property::parse_property_expr_without_type, value::AstPklValue, ExprHash},
+    lexer::{decode_escapes, PklToken},
+    parser::{
+        statement::property::parse_property_expr_without_type,
+        utils::{peek_non_space, take_doc_comment},
+        value::AstPklValue,
+        ExprHash, ObjectKey,
+    },
     PklResult,
 };
-use hashbrown::HashMap;
+use indexmap::IndexMap;
 use logos::Lexer;
 
 pub fn parse_object<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<ExprHash<'a>> {
     let start = lexer.span().start;
-    let mut hashmap = HashMap::with_capacity(8); // Assuming typical small object size
+    let mut members = Vec::with_capacity(8); // Assuming typical small object size
+    let mut elements = Vec::new();
+    let mut docs = IndexMap::new();
+    let mut doc_lines = Vec::new();
     let mut expect_new_entry = true;
 
-    while let Some(token) = lexer.next() {
+    loop {
+        // kept to parse back an element from its first token
+        let before_token = lexer.clone();
+        let Some(token) = lexer.next() else {
+            break;
+        };
+
         match token {
+            Ok(PklToken::Identifier(_)) | Ok(PklToken::IllegalIdentifier(_))
+                if !is_property_start(lexer) =>
+            {
+                *lexer = before_token;
+                elements.push(parse_expr(lexer)?);
+                doc_lines.clear();
+                expect_new_entry = true;
+            }
             Ok(PklToken::Identifier(id)) | Ok(PklToken::IllegalIdentifier(id)) => {
                 if !expect_new_entry {
                     return Err((
@@ -24,33 +46,96 @@ pub fn parse_object<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<ExprHa
                         .into());
                 }
 
+                let id = decode_escapes(id);
+                if let Some(doc) = take_doc_comment(&mut doc_lines) {
+                    docs.insert(id.clone(), doc);
+                }
+
+                let value = parse_property_expr_without_type(lexer)?;
+                expect_new_entry = matches!(value, PklExpr::Value(AstPklValue::Object(_)));
+                members.push((ObjectKey::Field(id), value));
+            }
+            Ok(PklToken::OpenBracket) => {
+                if !expect_new_entry {
+                    return Err((
+                        "unexpected token here (context: object), expected newline or comma"
+                            .to_owned(),
+                        lexer.span(),
+                    )
+                        .into());
+                }
+
+                let key = parse_entry_key(lexer)?;
+                // only the doc comments of entries with a literal key can be looked up
+                if let Some(doc) = take_doc_comment(&mut doc_lines) {
+                    if let PklExpr::Value(AstPklValue::String(key, _)) = key {
+                        docs.insert(key.into(), doc);
+                    }
+                }
+
                 let value = parse_property_expr_without_type(lexer)?;
-                expect_new_entry = matches!(value, PklExpr::Value(AstPklValue::Object((_, _))));
-                hashmap.insert(id, value);
+                expect_new_entry = matches!(value, PklExpr::Value(AstPklValue::Object(_)));
+                members.push((ObjectKey::Entry(key), value));
             }
             Ok(PklToken::NewLine) => {
                 expect_new_entry = true;
             }
+            Ok(PklToken::DocComment(line)) => doc_lines.push(line),
             Ok(PklToken::Space)
-            | Ok(PklToken::DocComment(_))
             | Ok(PklToken::LineComment(_))
             | Ok(PklToken::MultilineComment(_)) => {}
             Ok(PklToken::CloseBrace) => {
                 let end = lexer.span().end;
-                return Ok((hashmap, start..end));
+                return Ok((members, start..end, elements, docs));
+            }
+            Err(e) => return Err((e, lexer.span()).into()),
+            // any other token starts an element
+            Ok(_) => {
+                *lexer = before_token;
+                elements.push(parse_expr(lexer)?);
+                doc_lines.clear();
+                expect_new_entry = true;
             }
-            Err(e) => return Err((e.to_string(), lexer.span()).into()),
+        }
+    }
+
+    Err(("Missing object close brace".to_owned(), lexer.span()).into())
+}
+
+/// Checks, without consuming anything, if the identifier that was just lexed
+/// is the name of a property, that is followed by `=` or `{`, rather than an element.
+fn is_property_start<'a>(lexer: &Lexer<'a, PklToken<'a>>) -> bool {
+    matches!(
+        peek_non_space(lexer).0,
+        Some(Ok(PklToken::EqualSign)) | Some(Ok(PklToken::OpenBrace))
+    )
+}
+
+/// Parses the key of an `[key] = value` entry, which is any expression,
+/// the open bracket being already consumed.
+fn parse_entry_key<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklExpr<'a>> {
+    let key = parse_expr(lexer)?;
+
+    while let Some(token) = lexer.next() {
+        match token {
+            Ok(PklToken::CloseBracket) => return Ok(key),
+            Ok(PklToken::Space) => continue,
+            Err(e) => return Err((e, lexer.span()).into()),
             _ => {
                 return Err((
-                    "unexpected token here (context: object)".to_owned(),
+                    "expected close bracket (context: object entry)".to_owned(),
                     lexer.span(),
                 )
-                    .into());
+                    .into())
             }
         }
     }
 
-    Err(("Missing object close brace".to_owned(), lexer.span()).into())
+    Err((
+        "Missing object entry close bracket".to_owned(),
+        lexer.span(),
+    )
+        .into())
 }
 
 pub fn parse_amended_object<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<AstPklValue<'a>> {
@@ -59,7 +144,7 @@ pub fn parse_amended_object<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResul
     let amended_object_name = match lexer.next() {
         Some(Ok(PklToken::Identifier(id))) | Some(Ok(PklToken::IllegalIdentifier(id))) => {
             if let Some(Ok(PklToken::CloseParen)) = lexer.next() {
-                id
+                decode_escapes(id)
             } else {
                 return Err((
                     "expected close parenthesis (context: amended_object)".to_owned(),
@@ -68,7 +153,7 @@ pub fn parse_amended_object<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResul
                     .into());
             }
         }
-        Some(Err(e)) => return Err((e.to_string(), lexer.span()).into()),
+        Some(Err(e)) => return Err((e, lexer.span()).into()),
         _ => {
             return Err((
                 "expected identifier here (context: amended_object)".to_owned(),
@@ -90,7 +175,7 @@ pub fn parse_amended_object<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResul
                     start..end,
                 ));
             }
-            Err(e) => return Err((e.to_string(), lexer.span()).into()),
+            Err(e) => return Err((e, lexer.span()).into()),
             _ => {
                 return Err((
                     "expected open brace here (context: amended_object)".to_owned(),
@@ -107,3 +192,56 @@ pub fn parse_amended_object<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResul
     )
         .into())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{error_slice, parse, parse_err},
+        PklValue,
+    };
+
+    #[test]
+    fn entries_with_a_hyphenated_key_can_be_read_back() {
+        let pkl = parse("x { [\"my-key\"] = 1 }");
+        assert_eq!(pkl.get_path("x.my-key"), Some(PklValue::Int(1)));
+    }
+
+    #[test]
+    fn entry_keys_are_evaluated() {
+        let pkl = parse("prefix = \"a\"\nx { [prefix + \"b\"] = 1 }");
+        assert_eq!(pkl.get_path("x.ab"), Some(PklValue::Int(1)));
+    }
+
+    #[test]
+    fn entry_keys_have_their_escapes_decoded() {
+        let pkl = parse("x { [\"a\\u{41}\"] = 1 }");
+        assert_eq!(pkl.get_path("x.aA"), Some(PklValue::Int(1)));
+    }
+
+    #[test]
+    fn fields_and_entries_keep_their_declaration_order() {
+        let pkl = parse("z {\n  [\"a\"] = 1\n  b = 2\n  [\"c\" + b.toString()] = 3\n}");
+        let PklValue::Object(fields) = pkl.get_value("z").unwrap() else {
+            panic!("expected an Object");
+        };
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["a", "b", "c2"]);
+    }
+
+    #[test]
+    fn mapping_entry_keys_are_evaluated() {
+        let pkl = parse("x = new Mapping { [\"a\" + \"b\"] = 1 }");
+        let PklValue::Mapping(entries) = pkl.get_value("x").unwrap() else {
+            panic!("expected a Mapping");
+        };
+        assert_eq!(entries.get("ab"), Some(&PklValue::Int(1)));
+    }
+
+    #[test]
+    fn entry_keys_must_be_strings() {
+        let source = "x { [1 + 1] = 1 }";
+        let error = parse_err(source);
+
+        assert_eq!(error.code(), Some("pkl_fast::type_mismatch"));
+        assert_eq!(error_slice(source, &error), "1 + 1");
+    }
+}
@@ -0,0 +1,284 @@
+use super::{parse_operand, parse_operand_accesses, PklExpr};
+use crate::{
+    lexer::{LexingError, PklToken},
+    parser::{utils::peek_non_space, value::AstPklValue},
+    PklResult,
+};
+use logos::{Lexer, Span};
+use std::fmt;
+
+/// A binary operator, ordered by its precedence
+/// when parsing an operation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operator {
+    NullCoalescing,
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    LessThan,
+    LessOrEqual,
+    MoreThan,
+    MoreOrEqual,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    IntDiv,
+    Modulo,
+    Pow,
+}
+
+impl Operator {
+    pub fn from_token(token: &PklToken<'_>) -> Option<Self> {
+        let op = match token {
+            PklToken::OperatorNullCoalescing => Operator::NullCoalescing,
+            PklToken::OperatorOr => Operator::Or,
+            PklToken::OperatorAnd => Operator::And,
+            PklToken::OperatorEqual => Operator::Equal,
+            PklToken::OperatorNotEqual => Operator::NotEqual,
+            PklToken::OperatorLessThan => Operator::LessThan,
+            PklToken::OperatorLessOrEqual => Operator::LessOrEqual,
+            PklToken::OperatorMoreThan => Operator::MoreThan,
+            PklToken::OperatorMoreOrEqual => Operator::MoreOrEqual,
+            PklToken::OperatorPlus => Operator::Add,
+            PklToken::OperatorMinus => Operator::Sub,
+            PklToken::OperatorMul => Operator::Mul,
+            PklToken::OperatorDiv => Operator::Div,
+            PklToken::OperatorIntDiv => Operator::IntDiv,
+            PklToken::OperatorModulo => Operator::Modulo,
+            PklToken::OperatorPow => Operator::Pow,
+            _ => return None,
+        };
+
+        Some(op)
+    }
+
+    /// Based on the Pkl language reference,
+    /// the higher binds the tighter.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Operator::NullCoalescing => 1,
+            Operator::Or => 2,
+            Operator::And => 3,
+            Operator::Equal | Operator::NotEqual => 4,
+            Operator::LessThan
+            | Operator::LessOrEqual
+            | Operator::MoreThan
+            | Operator::MoreOrEqual => 5,
+            Operator::Add | Operator::Sub => 6,
+            Operator::Mul | Operator::Div | Operator::IntDiv | Operator::Modulo => 7,
+            Operator::Pow => 8,
+        }
+    }
+
+    pub fn is_right_associative(&self) -> bool {
+        matches!(self, Operator::NullCoalescing | Operator::Pow)
+    }
+
+    pub fn is_logical(&self) -> bool {
+        matches!(self, Operator::And | Operator::Or)
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::LessThan | Operator::LessOrEqual | Operator::MoreThan | Operator::MoreOrEqual
+        )
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op_str = match self {
+            Operator::NullCoalescing => "??",
+            Operator::Or => "||",
+            Operator::And => "&&",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::LessThan => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::MoreThan => ">",
+            Operator::MoreOrEqual => ">=",
+            Operator::Add => "+",
+            Operator::Sub => "-",
+            Operator::Mul => "*",
+            Operator::Div => "/",
+            Operator::IntDiv => "~/",
+            Operator::Modulo => "%",
+            Operator::Pow => "**",
+        };
+        write!(f, "{}", op_str)
+    }
+}
+
+/// A binary operation: left operand, operator, right operand and the range.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Operation<'a>(
+    pub Box<PklExpr<'a>>,
+    pub Operator,
+    pub Box<PklExpr<'a>>,
+    pub Span,
+);
+
+impl<'a> Operation<'a> {
+    pub fn span(&self) -> Span {
+        self.3.to_owned()
+    }
+}
+
+/// Parses the operators (and their right operands) following
+/// an already parsed expression, using precedence climbing.
+///
+/// Only spaces are skipped while looking for an operator,
+/// a newline ends the expression.
+///
+/// A signed radix literal following an operand, as in `a -0x1` or `a-0x1`,
+/// is read as an addition or a subtraction of the unsigned literal,
+/// so that spacing does not change the meaning of an operation.
+pub fn parse_operation<'a>(
+    lexer: &mut Lexer<'a, PklToken<'a>>,
+    mut lhs: PklExpr<'a>,
+    min_precedence: u8,
+) -> PklResult<PklExpr<'a>> {
+    while let Some(op) = peek_operator(lexer) {
+        if op.precedence() < min_precedence {
+            break;
+        }
+        // consume the operator
+        let (token, peeked) = peek_non_space(lexer);
+        *lexer = peeked;
+
+        let mut rhs = match token {
+            Some(Ok(token)) if Operator::from_token(&token).is_none() => {
+                let literal = unsigned_literal(token, lexer.slice(), lexer.span())?;
+                parse_operand_accesses(lexer, literal)?
+            }
+            _ => parse_operand(lexer)?,
+        };
+
+        while let Some(next_op) = peek_operator(lexer) {
+            if next_op.precedence() > op.precedence() {
+                rhs = parse_operation(lexer, rhs, op.precedence() + 1)?;
+            } else if next_op.is_right_associative() && next_op.precedence() == op.precedence() {
+                rhs = parse_operation(lexer, rhs, op.precedence())?;
+            } else {
+                break;
+            }
+        }
+
+        let span = lhs.span().start..rhs.span().end;
+
+        // `a < b < c` would compare a Boolean with `c`
+        if op.is_comparison() {
+            if let PklExpr::Operation(Operation(_, lhs_op, _, _)) = &lhs {
+                if lhs_op.is_comparison() {
+                    return Err(("comparison operators cannot be chained".to_owned(), span).into());
+                }
+            }
+        }
+
+        lhs = PklExpr::Operation(Operation(Box::new(lhs), op, Box::new(rhs), span));
+    }
+
+    Ok(lhs)
+}
+
+fn peek_operator<'a>(lexer: &Lexer<'a, PklToken<'a>>) -> Option<Operator> {
+    match peek_non_space(lexer) {
+        (Some(Ok(token)), peeked) => {
+            Operator::from_token(&token).or_else(|| literal_sign(&token, peeked.slice()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the operator standing for the sign of a signed radix literal, if any,
+/// decimal literals being unsigned.
+fn literal_sign(token: &PklToken<'_>, slice: &str) -> Option<Operator> {
+    if !matches!(
+        token,
+        PklToken::HexInt(_) | PklToken::BinaryInt(_) | PklToken::OctalInt(_)
+    ) {
+        return None;
+    }
+
+    match slice.as_bytes().first() {
+        Some(b'-') => Some(Operator::Sub),
+        Some(b'+') => Some(Operator::Add),
+        _ => None,
+    }
+}
+
+/// Converts a signed radix literal into the literal without its sign.
+fn unsigned_literal<'a>(token: PklToken<'a>, slice: &str, span: Span) -> PklResult<PklExpr<'a>> {
+    let is_negative = slice.starts_with('-');
+    let span = span.start + 1..span.end;
+
+    let value = match token {
+        PklToken::HexInt(i) | PklToken::BinaryInt(i) | PklToken::OctalInt(i) => {
+            let i = if is_negative {
+                i.checked_neg()
+            } else {
+                Some(i)
+            };
+            match i {
+                Some(i) => AstPklValue::Int(i, span),
+                None => {
+                    let error = LexingError::InvalidInteger(format!("`{slice}` is out of range"));
+                    return Err((error, span).into());
+                }
+            }
+        }
+        _ => unreachable!("only radix literals have a sign"),
+    };
+
+    Ok(value.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{error_slice, eval, eval_err, parse},
+        PklValue,
+    };
+
+    #[test]
+    fn comparison_operators_cannot_be_chained() {
+        let source = "1 < 2 < 3";
+        let error = eval_err(source);
+
+        assert_eq!(error.msg(), "comparison operators cannot be chained");
+        assert_eq!(error_slice(source, &error), "1 < 2 < 3");
+        assert_eq!(eval("1 < 2 == true"), PklValue::Bool(true));
+    }
+
+    #[test]
+    fn spacing_does_not_change_the_meaning_of_a_minus() {
+        let pkl = parse("a = 5\nb = 3\nx = 2");
+        for (expr, expected) in [
+            ("a-b", 2),
+            ("a - b", 2),
+            ("a -b", 2),
+            ("a+b", 8),
+            ("a +b", 8),
+            ("-x", -2),
+            ("1 - -2", 3),
+            ("1-2", -1),
+            ("1 -2", -1),
+        ] {
+            let value = pkl.evaluate_in_scope(expr, &[]).ok();
+            assert_eq!(value, Some(PklValue::Int(expected)), "{expr}");
+        }
+    }
+
+    #[test]
+    fn signed_radix_literals_after_an_operand_are_operations() {
+        assert_eq!(eval("1-0x1"), PklValue::Int(0));
+        assert_eq!(eval("1 -0x1"), PklValue::Int(0));
+        assert_eq!(eval("1+-0x2"), PklValue::Int(-1));
+        assert_eq!(eval("5-0b11"), PklValue::Int(2));
+        assert_eq!(eval("2 * -0o10"), PklValue::Int(-16));
+        assert_eq!(eval("-0x1"), PklValue::Int(-1));
+    }
+}
@@ -0,0 +1,192 @@
+use super::{parse_expr, PklExpr};
+use crate::{lexer::PklToken, PklResult};
+use logos::{Logos, Span};
+
+/// A part of a string literal, either text whose escape sequences are decoded
+/// or an interpolated expression, such as `\(name)`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum StringFragment<'a> {
+    Text(String),
+    Interpolation(Box<PklExpr<'a>>),
+}
+
+/// Parses the content of a multiline string, as lexed, into fragments.
+///
+/// The indentation of the closing delimiter is removed from every line,
+/// then escape sequences are decoded and interpolated expressions parsed.
+/// The newlines following the opening delimiter and preceding the closing one are not part of the string.
+///
+/// # Arguments
+///
+/// * `source` - The whole source, interpolated expressions being lexed from it.
+/// * `content` - The content of the string, from its first line to the indentation of its closing delimiter.
+/// * `start` - The index of `content` in `source`.
+pub fn parse_multiline_string<'a>(
+    source: &'a str,
+    content: &str,
+    start: usize,
+) -> PklResult<Vec<StringFragment<'a>>> {
+    let (lines, indent) = match content.rfind('\n') {
+        Some(index) => (&content[..index], &content[index + 1..]),
+        None => ("", content),
+    };
+
+    let mut fragments = Vec::new();
+    let mut text = String::new();
+    let mut line_start = start;
+
+    for (i, line) in lines.split('\n').enumerate() {
+        if i != 0 {
+            text.push('\n');
+        }
+
+        let body = match line.strip_prefix(indent) {
+            Some(body) => body,
+            // blank lines may be less indented
+            None if line.trim().is_empty() => "",
+            None => {
+                return Err((
+                    "Line must match or exceed the indentation of the closing delimiter".to_owned(),
+                    line_start..line_start + line.len(),
+                )
+                    .into())
+            }
+        };
+        let body_start = line_start + line.len() - body.len();
+
+        parse_line(source, body, body_start, &mut text, &mut fragments)?;
+        line_start += line.len() + 1;
+    }
+
+    if !text.is_empty() || fragments.is_empty() {
+        fragments.push(StringFragment::Text(text));
+    }
+
+    Ok(fragments)
+}
+
+/// Decodes the escape sequences of a line into `text`, pushing the text
+/// along with an interpolation to `fragments` whenever one is found.
+fn parse_line<'a>(
+    source: &'a str,
+    line: &str,
+    line_start: usize,
+    text: &mut String,
+    fragments: &mut Vec<StringFragment<'a>>,
+) -> PklResult<()> {
+    let mut i = 0;
+
+    while let Some(c) = line[i..].chars().next() {
+        if c != '\\' {
+            text.push(c);
+            i += c.len_utf8();
+            continue;
+        }
+
+        let escape_span = line_start + i..line_start + i + 2;
+        match line[i + 1..].chars().next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some('"') => text.push('"'),
+            Some('\\') => text.push('\\'),
+            Some('u') => {
+                let (c, length) = parse_unicode_escape(&line[i..], escape_span)?;
+                text.push(c);
+                i += length;
+                continue;
+            }
+            Some('(') => {
+                let (expr, end) = parse_interpolation(source, line_start + i + 2)?;
+                if end > line_start + line.len() {
+                    return Err((
+                        "An interpolation cannot span several lines".to_owned(),
+                        escape_span.start..end,
+                    )
+                        .into());
+                }
+                if !text.is_empty() {
+                    fragments.push(StringFragment::Text(std::mem::take(text)));
+                }
+                fragments.push(StringFragment::Interpolation(Box::new(expr)));
+                i = end - line_start;
+                continue;
+            }
+            _ => return Err(("Invalid escape sequence".to_owned(), escape_span).into()),
+        }
+        i += 2;
+    }
+
+    Ok(())
+}
+
+/// Decodes an escape sequence such as `\u{1F600}`,
+/// returning the character along with the length of the sequence.
+fn parse_unicode_escape(escape: &str, span: Span) -> PklResult<(char, usize)> {
+    let digits = escape[2..]
+        .strip_prefix('{')
+        .and_then(|rest| rest.split_once('}'))
+        .map(|(digits, _)| digits);
+
+    match digits.and_then(|digits| {
+        u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .map(|c| (c, digits.len() + 4))
+    }) {
+        Some(decoded) => Ok(decoded),
+        None => Err(("Invalid unicode escape sequence".to_owned(), span).into()),
+    }
+}
+
+/// Parses the expression of an interpolation starting at `start` in the source,
+/// returning it along with the index right after its closing parenthesis.
+fn parse_interpolation(source: &str, start: usize) -> PklResult<(PklExpr<'_>, usize)> {
+    let mut lexer = PklToken::lexer(source);
+    lexer.bump(start);
+
+    let expr = parse_expr(&mut lexer)?;
+
+    loop {
+        match lexer.next() {
+            Some(Ok(PklToken::Space)) => continue,
+            Some(Ok(PklToken::CloseParen)) => return Ok((expr, lexer.span().end)),
+            _ => {
+                return Err((
+                    "Expected a close parenthesis ending the interpolation".to_owned(),
+                    lexer.span(),
+                )
+                    .into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{error_slice, parse, parse_err},
+        PklValue,
+    };
+
+    #[test]
+    fn multiline_strings_are_stripped_decoded_and_interpolated() {
+        let pkl = parse(
+            "name = \"pkl\"\nx = \"\"\"\n    Hello \\(name)!\n      indented\\tend\n    count: \\(1 + 2)\n    \"\"\"",
+        );
+        assert_eq!(
+            pkl.get_value("x"),
+            Some(PklValue::String(
+                "Hello pkl!\n  indented\tend\ncount: 3".to_owned()
+            ))
+        );
+
+        let source = "x = \"\"\"\n    a\n  b\n    \"\"\"";
+        let error = parse_err(source);
+        assert_eq!(
+            error.msg(),
+            "Line must match or exceed the indentation of the closing delimiter"
+        );
+        assert_eq!(error_slice(source, &error), "  b");
+    }
+}
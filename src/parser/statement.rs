@@ -11,6 +11,7 @@ use logos::{Lexer, Span};
 use module::{parse_module_clause, Module};
 use property::{parse_property, Property};
 use typealias::{parse_typealias, TypeAlias};
+use when::{parse_when, When};
 
 pub mod amends;
 mod boxed;
@@ -20,6 +21,7 @@ pub mod import;
 pub mod module;
 pub mod property;
 pub mod typealias;
+pub mod when;
 
 /// Represent any valid Pkl Statement.
 #[derive(Debug, PartialEq, Clone)]
@@ -51,6 +53,10 @@ pub enum PklStatement<'a> {
     /// containing the import values.
     ExtendsClause(Extends<'a>),
 
+    /// A conditional group of statements,
+    /// only the members of the matching branch are added
+    When(When<'a>),
+
     /// A local Statement
     Local(Box<PklStatement<'a>>, Span),
     /// A const Statement
@@ -69,6 +75,7 @@ impl<'a> PklStatement<'a> {
             PklStatement::ModuleClause(Module { span, .. }) => span.clone(),
             PklStatement::AmendsClause(Amends { span, .. }) => span.clone(),
             PklStatement::ExtendsClause(Extends { span, .. }) => span.clone(),
+            PklStatement::When(When { span, .. }) => span.clone(),
             PklStatement::Local(_, span) => span.clone(),
             PklStatement::Const(_, span) => span.clone(),
             PklStatement::Fixed(_, span) => span.clone(),
@@ -102,17 +109,18 @@ impl<'a> PklStatement<'a> {
     }
 }
 
-/// Parses a `PklStatement`.
+/// Parses a `PklStatement`, along with whether the newline ending it was consumed,
+/// as a typed declaration without a value such as `port: Int` does.
 pub fn parse_stmt<'a>(
     lexer: &mut Lexer<'a, PklToken<'a>>,
     current_token: Option<PklToken<'a>>,
-) -> PklResult<PklStatement<'a>> {
+) -> PklResult<(PklStatement<'a>, bool)> {
     let token = match current_token {
         Some(t) => t,
         None => parse_any_token(lexer)?,
     };
 
-    match token {
+    let stmt = match token {
         PklToken::TypeAlias => parse_typealias(lexer),
         PklToken::Import => parse_import(lexer),
         PklToken::Extends => parse_extends_clause(lexer),
@@ -125,12 +133,15 @@ pub fn parse_stmt<'a>(
         PklToken::Module => parse_module_clause(lexer, false),
         PklToken::OpenModule => parse_module_clause(lexer, true),
 
-        PklToken::Fixed => parse_fixed(lexer),
-        PklToken::Const => parse_const(lexer),
-        PklToken::Local => parse_local(lexer),
+        PklToken::Fixed => return parse_fixed(lexer),
+        PklToken::Const => return parse_const(lexer),
+        PklToken::Local => return parse_local(lexer),
+
+        PklToken::When => parse_when(lexer, false),
+        PklToken::FunctionCall("when") => parse_when(lexer, true),
 
         PklToken::Identifier(id) | PklToken::IllegalIdentifier(id) => {
-            parse_property(lexer, Identifier(id, lexer.span()))
+            return parse_property(lexer, Identifier::new(id, lexer.span()));
         }
 
         _ => {
@@ -140,5 +151,7 @@ pub fn parse_stmt<'a>(
             )
                 .into());
         }
-    }
+    };
+
+    Ok((stmt?, false))
 }
@@ -1,11 +1,14 @@
 use super::PklStatement;
-use crate::lexer::PklToken;
+use crate::lexer::{decode_escapes, PklToken};
 use crate::parser::types::{parse_type_until, AstPklType};
-use crate::parser::utils::{parse_id, parse_id_as_str, parse_multispaces_until, parse_open_brace};
+use crate::parser::utils::{
+    parse_id, parse_id_as_str, parse_multispaces_until, parse_open_brace, take_doc_comment,
+};
 use crate::parser::Identifier;
 use crate::PklResult;
 use hashbrown::HashMap;
 use logos::{Lexer, Span};
+use std::borrow::Cow;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ClassDeclaration<'a> {
@@ -35,19 +38,37 @@ pub enum ClassKind {
 
 #[derive(Debug, Clone, Eq, Hash)]
 pub struct ClassField<'a> {
-    pub name: &'a str,
+    pub name: Cow<'a, str>,
     pub kind: FieldKind,
     span: Span,
+    doc: Option<String>,
 }
 
 impl<'a> ClassField<'a> {
+    /// Creates a field from its name as written in the source, decoding its escape sequences.
     pub fn new(name: &'a str, kind: FieldKind, span: Span) -> Self {
-        Self { name, kind, span }
+        Self {
+            name: decode_escapes(name),
+            kind,
+            span,
+            doc: None,
+        }
+    }
+
+    /// Attaches the doc comment preceding the field.
+    pub fn with_doc(mut self, doc: Option<String>) -> Self {
+        self.doc = doc;
+        self
     }
 
     pub fn span(&self) -> Span {
         self.span.to_owned()
     }
+
+    /// Returns the doc comment of the field, its lines joined with `\n`.
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
 }
 
 impl<'a> PartialEq for ClassField<'a> {
@@ -109,6 +130,7 @@ fn parse_fields<'a>(
 
     let mut key: Option<ClassField<'a>> = None;
     let mut _type: Option<AstPklType<'a>> = None;
+    let mut doc_lines = Vec::new();
 
     loop {
         let token = lexer.next();
@@ -122,11 +144,17 @@ fn parse_fields<'a>(
                 if let (Some(k), Some(t)) = (key.take(), _type.take()) {
                     hashmap.insert(k, t);
                 }
-                key = Some(ClassField::new(id, FieldKind::default(), lexer.span()))
+                key = Some(
+                    ClassField::new(id, FieldKind::default(), lexer.span())
+                        .with_doc(take_doc_comment(&mut doc_lines)),
+                )
             }
             Ok(PklToken::Hidden) if key.is_none() => {
                 let id = parse_id_as_str(lexer)?;
-                key = Some(ClassField::new(id, FieldKind::Hidden, lexer.span()))
+                key = Some(
+                    ClassField::new(id, FieldKind::Hidden, lexer.span())
+                        .with_doc(take_doc_comment(&mut doc_lines)),
+                )
             }
 
             Ok(PklToken::Colon) if key.is_some() & _type.is_none() => {
@@ -149,12 +177,12 @@ fn parse_fields<'a>(
                 break;
             }
 
+            Ok(PklToken::DocComment(line)) => doc_lines.push(line),
             Ok(PklToken::Space)
             | Ok(PklToken::NewLine)
-            | Ok(PklToken::DocComment(_))
             | Ok(PklToken::LineComment(_))
             | Ok(PklToken::MultilineComment(_)) => continue,
-            Err(e) => return Err((e.to_string(), lexer.span()).into()),
+            Err(e) => return Err((e, lexer.span()).into()),
             _ => return Err(("unexpected token here".to_owned(), lexer.span()).into()),
         }
     }
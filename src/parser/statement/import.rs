@@ -2,11 +2,12 @@ use crate::parser::statement::PklStatement;
 use crate::parser::utils::parse_simple_string;
 use crate::{lexer::PklToken, PklResult};
 use logos::{Lexer, Span};
+use std::borrow::Cow;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Import<'a> {
     pub name: &'a str,
-    pub local_name: Option<&'a str>,
+    pub local_name: Option<Cow<'a, str>>,
     pub span: Span,
 }
 
@@ -2,7 +2,7 @@ use super::{PklExpr, PklStatement};
 use crate::lexer::PklToken;
 use crate::parser::expr::object::parse_object;
 use crate::parser::expr::parse_expr;
-use crate::parser::types::{parse_type_until, AstPklType};
+use crate::parser::types::{parse_type_until_any, AstPklType};
 use crate::parser::Identifier;
 use crate::PklResult;
 use logos::{Lexer, Span};
@@ -11,7 +11,9 @@ use logos::{Lexer, Span};
 pub struct Property<'a> {
     pub name: Identifier<'a>,
     pub _type: Option<AstPklType<'a>>,
-    pub value: PklExpr<'a>,
+    /// The value of the property, `None` for a typed declaration
+    /// such as `port: Int`, whose value is set by an amending module.
+    pub value: Option<PklExpr<'a>>,
     pub span: Span,
 }
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -24,38 +26,49 @@ pub enum PropertyKind {
     ConstLocal,
 }
 
-/// Parse a token stream into a Pkl const Statement.
+/// Parse a token stream into a Pkl const Statement,
+/// along with whether the newline ending it was consumed.
 pub fn parse_property<'a>(
     lexer: &mut Lexer<'a, PklToken<'a>>,
     name: Identifier<'a>,
-) -> PklResult<PklStatement<'a>> {
+) -> PklResult<(PklStatement<'a>, bool)> {
     let start = name.1.start;
-    let (_type, value) = parse_property_expr(lexer)?;
+    let (_type, value, consumed_newline) = parse_property_expr(lexer)?;
     let end = lexer.span().end;
 
-    Ok(PklStatement::Property(Property {
+    let property = PklStatement::Property(Property {
         name,
         _type,
         value,
         span: start..end,
-    }))
+    });
+
+    Ok((property, consumed_newline))
 }
 
 /// Parse a token stream into a Pkl Expr after an identifier with a possible type.
+///
+/// A type followed by a newline rather than `=` declares the property without a value,
+/// the returned flag telling whether that terminating newline was consumed.
 pub fn parse_property_expr<'a>(
     lexer: &mut Lexer<'a, PklToken<'a>>,
-) -> PklResult<(Option<AstPklType<'a>>, PklExpr<'a>)> {
+) -> PklResult<(Option<AstPklType<'a>>, Option<PklExpr<'a>>, bool)> {
     loop {
         match lexer.next() {
             Some(Ok(PklToken::EqualSign)) => {
-                return Ok((None, parse_expr(lexer)?));
+                return Ok((None, Some(parse_expr(lexer)?), false));
             }
             Some(Ok(PklToken::Colon)) => {
-                let _type = parse_type_until(lexer, PklToken::EqualSign)?;
-                return Ok((Some(_type), parse_expr(lexer)?));
+                let (_type, until) =
+                    parse_type_until_any(lexer, &[PklToken::EqualSign, PklToken::NewLine])?;
+
+                if until == Some(PklToken::EqualSign) {
+                    return Ok((Some(_type), Some(parse_expr(lexer)?), false));
+                }
+                return Ok((Some(_type), None, until == Some(PklToken::NewLine)));
             }
             Some(Ok(PklToken::OpenBrace)) => {
-                return Ok((None, parse_object(lexer)?.into()));
+                return Ok((None, Some(parse_object(lexer)?.into()), false));
             }
             Some(Ok(PklToken::Space))
             | Some(Ok(PklToken::NewLine))
@@ -66,7 +79,7 @@ pub fn parse_property_expr<'a>(
                 continue;
             }
             Some(Err(e)) => {
-                return Err((e.to_string(), lexer.span()).into());
+                return Err((e, lexer.span()).into());
             }
             Some(_) => {
                 return Err((
@@ -103,7 +116,7 @@ pub fn parse_property_expr_without_type<'a>(
                 continue;
             }
             Some(Err(e)) => {
-                return Err((e.to_string(), lexer.span()).into());
+                return Err((e, lexer.span()).into());
             }
             Some(_) => {
                 return Err((
@@ -118,3 +131,72 @@ pub fn parse_property_expr_without_type<'a>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{error_slice, parse, parse_err},
+        Pkl, PklValue,
+    };
+    use logos::Logos;
+
+    #[test]
+    fn typed_declarations_are_set_by_amending_modules() {
+        let mut pkl = Pkl::new();
+        pkl.register_module(
+            "template",
+            "open module template\nport: Int\nhost = \"localhost\"\n",
+        );
+
+        assert!(pkl.parse("amends \"mem:template\"\nport = 8080").is_ok());
+        assert_eq!(pkl.get_value("port"), Some(PklValue::Int(8080)));
+        assert_eq!(
+            pkl.get_value("host"),
+            Some(PklValue::String("localhost".to_owned()))
+        );
+    }
+
+    #[test]
+    fn typed_declarations_left_unset_are_reported() {
+        let mut pkl = Pkl::new();
+        pkl.register_module("template", "open module template\nport: Int\n");
+
+        let error = pkl.parse("amends \"mem:template\"").err().unwrap();
+        assert_eq!(
+            error.msg(),
+            "Property `port` of type Int is declared without a value, which only open modules allow"
+        );
+
+        let error = parse_err("port: Int\n");
+        assert!(error
+            .msg()
+            .starts_with("Property `port` of type Int is declared without a value"));
+    }
+
+    #[test]
+    fn typed_declarations_consume_the_newline_ending_them() {
+        let mut lexer = PklToken::lexer(": Int\nnext = 1");
+        let (_type, value, consumed_newline) = parse_property_expr(&mut lexer).ok().unwrap();
+        assert!(_type.is_some());
+        assert_eq!(value, None);
+        assert!(consumed_newline);
+
+        let mut lexer = PklToken::lexer(": Int = 1\nnext = 1");
+        let (_, value, consumed_newline) = parse_property_expr(&mut lexer).ok().unwrap();
+        assert!(value.is_some());
+        assert!(!consumed_newline);
+
+        // the newline a declaration consumed still separates it from the next statement
+        let pkl = parse("open module m\nport: Int\nhost = \"a\"\nname: String // unset\n");
+        assert_eq!(
+            pkl.get_value("host"),
+            Some(PklValue::String("a".to_owned()))
+        );
+
+        let source = "open module m\nport: Int host = \"a\"";
+        let error = parse_err(source);
+        assert_eq!(error.msg(), "statements must be separated by a newline");
+        assert_eq!(error_slice(source, &error), "host");
+    }
+}
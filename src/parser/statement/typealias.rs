@@ -47,20 +47,20 @@ pub fn parse_typealias_name<'a>(
     while let Some(token) = lexer.next() {
         match token {
             Ok(PklToken::Identifier(id)) | Ok(PklToken::IllegalIdentifier(id)) => {
-                return Ok((Identifier(id, lexer.span()), vec![]))
+                return Ok((Identifier::new(id, lexer.span()), vec![]))
             }
             Ok(PklToken::TypeWithAttributes(fn_name)) => {
                 let start = lexer.span().start;
                 let end = start + fn_name.len();
                 let attributes = parse_attributes(lexer)?;
-                return Ok((Identifier(fn_name, start..end), attributes));
+                return Ok((Identifier::new(fn_name, start..end), attributes));
             }
             Ok(PklToken::Space)
             | Ok(PklToken::NewLine)
             | Ok(PklToken::DocComment(_))
             | Ok(PklToken::LineComment(_))
             | Ok(PklToken::MultilineComment(_)) => continue,
-            Err(e) => return Err((e.to_string(), lexer.span()).into()),
+            Err(e) => return Err((e, lexer.span()).into()),
             _ => return Err(("unexpected token here".to_owned(), lexer.span()).into()),
         }
     }
@@ -101,7 +101,7 @@ fn parse_attributes<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<Id
             Some(Ok(PklToken::OperatorMoreThan)) => {
                 break;
             }
-            Some(Err(e)) => return Err((format!("Lexer error: {:?}", e), lexer.span()).into()),
+            Some(Err(e)) => return Err((e, lexer.span()).into()),
             None => {
                 return Err((
                     "Unexpected end of input, did you mean to write ',' or '>'?".to_string(),
@@ -0,0 +1,107 @@
+use super::PklStatement;
+use crate::parser::{
+    expr::parse_parenthesized_expr,
+    parse_statements,
+    utils::{peek_non_space, peek_non_whitespace},
+};
+use crate::{lexer::PklToken, parser::expr::PklExpr, PklResult};
+use logos::{Lexer, Span};
+
+/// A conditional group of members, such as `when (cond) { ... } else { ... }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct When<'a> {
+    pub condition: PklExpr<'a>,
+    pub body: Vec<PklStatement<'a>>,
+    pub else_body: Option<Vec<PklStatement<'a>>>,
+    pub span: Span,
+}
+
+impl<'a> When<'a> {
+    pub fn modifier_not_applicable_err(&self, modifier: &str) -> String {
+        format!("Modifier `{modifier}` is not applicable to `when` statements.")
+    }
+}
+
+/// Function called after the 'when' keyword, or after `when(`
+/// when `paren_consumed` is set as both are lexed as a single token.
+pub fn parse_when<'a>(
+    lexer: &mut Lexer<'a, PklToken<'a>>,
+    paren_consumed: bool,
+) -> PklResult<PklStatement<'a>> {
+    let start = lexer.span().start;
+
+    if !paren_consumed {
+        expect_token(lexer, PklToken::OpenParen, "expected '(' after `when`")?;
+    }
+    let condition = parse_parenthesized_expr(lexer)?;
+
+    expect_token(
+        lexer,
+        PklToken::OpenBrace,
+        "expected '{' after `when` condition",
+    )?;
+    let body = parse_statements(lexer, true)?;
+
+    // `else` may be on the line following the closing brace
+    let else_body = match peek_non_whitespace(lexer) {
+        (Some(Ok(PklToken::Else)), peeked) => {
+            *lexer = peeked;
+            expect_token(lexer, PklToken::OpenBrace, "expected '{' after `else`")?;
+            Some(parse_statements(lexer, true)?)
+        }
+        _ => None,
+    };
+
+    Ok(PklStatement::When(When {
+        condition,
+        body,
+        else_body,
+        span: start..lexer.span().end,
+    }))
+}
+
+fn expect_token<'a>(
+    lexer: &mut Lexer<'a, PklToken<'a>>,
+    expected: PklToken<'a>,
+    msg: &str,
+) -> PklResult<()> {
+    match peek_non_space(lexer) {
+        (Some(Ok(token)), peeked) if token == expected => {
+            *lexer = peeked;
+            Ok(())
+        }
+        (Some(Err(e)), peeked) => Err((e, peeked.span()).into()),
+        (_, peeked) => Err((msg.to_owned(), peeked.span()).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{parse, parse_err},
+        PklValue,
+    };
+
+    #[test]
+    fn only_the_members_of_the_matching_branch_are_added() {
+        let pkl = parse("const enabled = true\nwhen (enabled) {\n  a = 1\n} else {\n  b = 2\n}");
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(1)));
+        assert_eq!(pkl.get_value("b"), None);
+
+        let pkl = parse("when (false) {\n  a = 1\n}\nc = 3");
+        assert_eq!(pkl.get_value("a"), None);
+        assert_eq!(pkl.get_value("c"), Some(PklValue::Int(3)));
+    }
+
+    #[test]
+    fn else_can_follow_the_closing_brace_on_the_next_line() {
+        let pkl = parse("when (false) {\n  a = 1\n}\n// otherwise\nelse {\n  b = 2\n}");
+        assert_eq!(pkl.get_value("b"), Some(PklValue::Int(2)));
+    }
+
+    #[test]
+    fn non_boolean_conditions_are_rejected() {
+        let error = parse_err("when (1) {\n  a = 1\n}");
+        assert!(error.msg().contains("Boolean"), "{}", error.msg());
+    }
+}
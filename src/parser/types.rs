@@ -1,6 +1,6 @@
 use super::{
     expr::{parse_expr, PklExpr},
-    PklResult,
+    is_statement_start, PklResult,
 };
 use crate::{lexer::PklToken, parser::expr::long::parse_long_expression_or};
 use logos::{Lexer, Span};
@@ -37,6 +37,19 @@ impl<'a> AstPklType<'a> {
             AstPklType::WithRequirement { span, .. } => span.to_owned(),
         }
     }
+    /// Returns the span of the first type constraint, such as `Int(isPositive)`,
+    /// found in the type, if any.
+    pub fn requirement_span(&self) -> Option<Span> {
+        match self {
+            AstPklType::Basic(_, _) | AstPklType::StringLiteral(_, _) => None,
+            AstPklType::Union(a, b) => a.requirement_span().or_else(|| b.requirement_span()),
+            AstPklType::Nullable(a) => a.requirement_span(),
+            AstPklType::WithAttributes { attributes, .. } => {
+                attributes.iter().find_map(AstPklType::requirement_span)
+            }
+            AstPklType::WithRequirement { span, .. } => Some(span.to_owned()),
+        }
+    }
     pub fn is_last_with_attributes(&self) -> bool {
         match self {
             AstPklType::Basic(_, _) => false,
@@ -100,7 +113,7 @@ pub fn parse_type<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<AstPklTy
             | Ok(PklToken::DocComment(_))
             | Ok(PklToken::LineComment(_))
             | Ok(PklToken::MultilineComment(_)) => continue,
-            Err(e) => return Err((e.to_string(), lexer.span()).into()),
+            Err(e) => return Err((e, lexer.span()).into()),
             _ => return Err(("unexpected token here".to_owned(), lexer.span()).into()),
         }
     }
@@ -112,12 +125,33 @@ pub fn parse_type_until<'a>(
     lexer: &mut Lexer<'a, PklToken<'a>>,
     until_token: PklToken<'a>,
 ) -> PklResult<AstPklType<'a>> {
+    let (_type, _) = parse_type_until_any(lexer, &[until_token])?;
+    Ok(_type)
+}
+
+/// Parses a type until one of the `until_tokens` is found.
+///
+/// A newline only ends the type when the next line does not continue it,
+/// that is does not start with `|` or another of the `until_tokens`.
+///
+/// Returns the type along with the token it stopped at,
+/// `None` if the end of input was reached.
+pub fn parse_type_until_any<'a>(
+    lexer: &mut Lexer<'a, PklToken<'a>>,
+    until_tokens: &[PklToken<'a>],
+) -> PklResult<(AstPklType<'a>, Option<PklToken<'a>>)> {
     let mut _type = parse_type(lexer)?;
 
     while let Some(token) = lexer.next() {
         match token {
-            Ok(token) if token == until_token => {
-                break;
+            Ok(PklToken::NewLine)
+                if until_tokens.contains(&PklToken::NewLine)
+                    && continues_type(lexer, until_tokens) =>
+            {
+                continue;
+            }
+            Ok(token) if until_tokens.contains(&token) => {
+                return Ok((_type, Some(token)));
             }
 
             Ok(PklToken::QuestionMark) => {
@@ -153,12 +187,41 @@ pub fn parse_type_until<'a>(
             | Ok(PklToken::DocComment(_))
             | Ok(PklToken::LineComment(_))
             | Ok(PklToken::MultilineComment(_)) => continue,
-            Err(e) => return Err((e.to_string(), lexer.span()).into()),
+            // a type ending its line is directly followed by another statement
+            Ok(token)
+                if until_tokens.contains(&PklToken::NewLine) && is_statement_start(&token) =>
+            {
+                return Err((
+                    "statements must be separated by a newline".to_owned(),
+                    lexer.span(),
+                )
+                    .into())
+            }
+            Err(e) => return Err((e, lexer.span()).into()),
             _ => return Err(("unexpected token here".to_owned(), lexer.span()).into()),
         }
     }
 
-    Ok(_type)
+    Ok((_type, None))
+}
+
+/// Checks, without consuming anything, if the next line continues
+/// the type being parsed rather than starting a new statement.
+fn continues_type<'a>(lexer: &Lexer<'a, PklToken<'a>>, until_tokens: &[PklToken<'a>]) -> bool {
+    let mut peeked = lexer.clone();
+
+    loop {
+        match peeked.next() {
+            Some(Ok(PklToken::Space))
+            | Some(Ok(PklToken::NewLine))
+            | Some(Ok(PklToken::DocComment(_)))
+            | Some(Ok(PklToken::LineComment(_)))
+            | Some(Ok(PklToken::MultilineComment(_))) => continue,
+            Some(Ok(PklToken::Union)) => return true,
+            Some(Ok(token)) => return token != PklToken::NewLine && until_tokens.contains(&token),
+            _ => return false,
+        }
+    }
 }
 
 /// Parses a type attributes
@@ -200,7 +263,7 @@ fn parse_attributes<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Vec<As
             Some(Ok(PklToken::OperatorMoreThan)) => {
                 break;
             }
-            Some(Err(e)) => return Err((format!("Lexer error: {:?}", e), lexer.span()).into()),
+            Some(Err(e)) => return Err((e, lexer.span()).into()),
             None => {
                 return Err((
                     "Unexpected end of input, did you mean to write ',' or '>'?".to_string(),
@@ -1,5 +1,5 @@
 use super::{Identifier, PklResult};
-use crate::lexer::PklToken;
+use crate::lexer::{LexingError, PklToken};
 use logos::Lexer;
 
 /// Macro to parse tokens from a lexer until one of the specified tokens is found.
@@ -30,7 +30,7 @@ macro_rules! parse_multispaces_until {
                 | Ok(PklToken::NewLine) => {
                     continue;
                 }
-                Err(e) => return Err((e.to_string(), lexer.span()).into()),
+                Err(e) => return Err((e, lexer.span()).into()),
                 _ => return Err(("unexpected token here".to_owned(), lexer.span()).into()),
             }
         }
@@ -53,7 +53,7 @@ macro_rules! parse_multispaces_until {
                     let token: PklToken<'_> = token;
                     return Ok(token);
                 }
-                Err(e) => return Err((e.to_string(), lexer.span()).into()),
+                Err(e) => return Err((e, lexer.span()).into()),
                 _ => return Err(("unexpected token here".to_owned(), lexer.span()).into()),
             }
         }
@@ -64,6 +64,66 @@ macro_rules! parse_multispaces_until {
 
 pub(super) use parse_multispaces_until;
 
+/// Returns the next token that is not a space along with
+/// the lexer advanced up to it, without advancing the given lexer.
+///
+/// Assign the returned lexer to the original one to consume the token.
+pub fn peek_non_space<'a>(
+    lexer: &Lexer<'a, PklToken<'a>>,
+) -> (
+    Option<Result<PklToken<'a>, LexingError>>,
+    Lexer<'a, PklToken<'a>>,
+) {
+    let mut peeked = lexer.clone();
+
+    loop {
+        match peeked.next() {
+            Some(Ok(PklToken::Space)) => continue,
+            token => return (token, peeked),
+        }
+    }
+}
+
+/// Returns the next token that is neither a space, a newline nor a comment,
+/// other than a doc comment, along with the lexer advanced up to it,
+/// without advancing the given lexer.
+///
+/// Assign the returned lexer to the original one to consume the token.
+pub fn peek_non_whitespace<'a>(
+    lexer: &Lexer<'a, PklToken<'a>>,
+) -> (
+    Option<Result<PklToken<'a>, LexingError>>,
+    Lexer<'a, PklToken<'a>>,
+) {
+    let mut peeked = lexer.clone();
+
+    loop {
+        match peeked.next() {
+            Some(Ok(PklToken::Space))
+            | Some(Ok(PklToken::NewLine))
+            | Some(Ok(PklToken::LineComment(_)))
+            | Some(Ok(PklToken::MultilineComment(_))) => continue,
+            token => return (token, peeked),
+        }
+    }
+}
+
+/// Joins the lines of the doc comments preceding a member, emptying `lines`.
+///
+/// Returns `None` when the member is not documented.
+pub fn take_doc_comment(lines: &mut Vec<&str>) -> Option<String> {
+    if lines.is_empty() {
+        return None;
+    }
+
+    let doc = lines
+        .drain(..)
+        .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(doc)
+}
+
 pub fn parse_any_token<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklToken<'a>> {
     parse_multispaces_until!(lexer)
 }
@@ -90,7 +150,7 @@ fn id_token<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<PklToken<'a>>
 pub fn parse_id<'a>(lexer: &mut Lexer<'a, PklToken<'a>>) -> PklResult<Identifier<'a>> {
     match id_token(lexer)? {
         PklToken::Identifier(id) | PklToken::IllegalIdentifier(id) => {
-            return Ok(Identifier(id, lexer.span()))
+            return Ok(Identifier::new(id, lexer.span()))
         }
         _ => unreachable!(),
     }
@@ -1,7 +1,8 @@
 use logos::Span;
+use std::borrow::Cow;
 
 use super::{
-    expr::{class::ClassInstance, PklExpr},
+    expr::{class::ClassInstance, string::StringFragment, PklExpr},
     ExprHash,
 };
 
@@ -19,8 +20,9 @@ pub enum AstPklValue<'a> {
 
     /// Any quoted string.
     String(&'a str, Span),
-    /// Any multiline string.
-    MultiLineString(&'a str, Span),
+    /// Any multiline string, its indentation stripped,
+    /// its escape sequences decoded and its interpolations parsed.
+    MultiLineString(Vec<StringFragment<'a>>, Span),
 
     /// An object.
     Object(ExprHash<'a>),
@@ -42,7 +44,7 @@ pub enum AstPklValue<'a> {
     ///     prop = "attribute"
     /// }
     /// ```
-    AmendingObject(&'a str, ExprHash<'a>, Span),
+    AmendingObject(Cow<'a, str>, ExprHash<'a>, Span),
 
     /// ### An amended object.
     /// Different from `AmendingObject`
@@ -64,7 +66,7 @@ impl<'a> AstPklValue<'a> {
             AstPklValue::Int(_, rng)
             | AstPklValue::Bool(_, rng)
             | AstPklValue::Float(_, rng)
-            | AstPklValue::Object((_, rng))
+            | AstPklValue::Object((_, rng, _, _))
             | AstPklValue::AmendingObject(_, _, rng)
             | AstPklValue::AmendedObject(_, _, rng)
             | AstPklValue::ClassInstance(ClassInstance(_, _, rng))
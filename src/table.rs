@@ -1,14 +1,23 @@
 use crate::{
-    errors::PklError,
+    errors::{PklError, PklWarning},
+    lexer::decode_escapes,
     parser::{
-        expr::{class::ClassInstance, fn_call::FuncCall, member_expr::ExprMember, PklExpr},
+        expr::{
+            class::ClassInstance,
+            fn_call::FuncCall,
+            lambda::Lambda,
+            member_expr::ExprMember,
+            operation::{Operation, Operator},
+            string::StringFragment,
+            PklExpr,
+        },
         statement::{
             amends::Amends, class::ClassDeclaration, extends::Extends, import::Import,
-            module::Module, property::Property, typealias::TypeAlias, PklStatement,
+            module::Module, property::Property, typealias::TypeAlias, when::When, PklStatement,
         },
         types::AstPklType,
         value::AstPklValue,
-        ExprHash, Identifier,
+        ExprHash, Identifier, ObjectKey,
     },
     PklResult,
 };
@@ -18,19 +27,40 @@ use base::{
     duration::{match_duration_methods_api, match_duration_props_api},
     float_api::{match_float_methods_api, match_float_props_api},
     int_api::{match_int_methods_api, match_int_props_api},
-    list_api::match_list_props_api,
+    list_api::{match_list_lambda_methods_api, match_list_methods_api, match_list_props_api},
+    listing_api::{
+        match_listing_methods_api, match_listing_props_api, match_mapping_methods_api,
+        match_mapping_props_api,
+    },
+    set_api::{distinct, match_set_methods_api, match_set_props_api},
     string_api::{match_string_methods_api, match_string_props_api},
 };
-use class::{generate_class_schema, ClassSchema};
-use hashbrown::HashMap;
-use import::Importer;
+use class::{generate_class_schema, unsupported_requirement_err, ClassSchema};
+pub use functions::FunctionRegistry;
+use hashbrown::{HashMap, HashSet};
+pub use import::Importer;
+use indexmap::IndexMap;
 use logos::Span;
+use operation::{match_operation, negate};
+use ordering::order_forward_references;
+pub use scope::ScopedTable;
+use std::cell::RefCell;
 use types::PklType;
+#[cfg(feature = "suggestions")]
 use utils::spelling::check_closest_word;
-use value::PklValue;
+use value::{
+    json::{write_json_object, NonFiniteFloatPolicy},
+    render::{write_interpolation, write_properties},
+    PklValue,
+};
 
 pub mod base;
+mod functions;
 mod import;
+mod operation;
+mod ordering;
+mod resource;
+mod scope;
 mod utils;
 
 pub mod class;
@@ -184,6 +214,23 @@ impl PklMember {
     }
 }
 
+/// The settings of a `Pkl` instance its tables are built with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableSettings {
+    /// When set, amending an object merges
+    /// its nested objects instead of replacing them.
+    pub deep_amend: bool,
+    /// When set, a warning is emitted for each property
+    /// whose name is not camelCase.
+    pub lint_property_names: bool,
+    /// When set, the missing and unknown fields of class instances
+    /// emit warnings instead of errors.
+    pub lenient_instances: bool,
+    /// When set, importing a module already imported without an alias,
+    /// again without an alias, is an error instead of being ignored.
+    pub deny_duplicate_imports: bool,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct PklTable {
     pub importer: Importer,
@@ -199,6 +246,31 @@ pub struct PklTable {
     amended_or_extended_module_name: Option<String>,
     is_amended: bool,
     is_extended: bool,
+
+    settings: TableSettings,
+
+    /// The warnings emitted while building the table.
+    pub warnings: Vec<PklWarning>,
+    /// The warnings emitted while evaluating expressions, which only borrow the table,
+    /// moved to `warnings` once the property being evaluated is handled.
+    deferred_warnings: RefCell<Vec<PklWarning>>,
+
+    /// The doc comments of class fields and object entries,
+    /// by dotted path such as `Server.port`.
+    pub docs: HashMap<String, String>,
+
+    /// The properties declared without a value, such as `port: Int`,
+    /// along with their type and the span they must be set from.
+    pub declarations: HashMap<String, (PklType, Span)>,
+
+    /// The functions registered from Rust, callable from the source.
+    pub functions: FunctionRegistry,
+
+    /// The uris of the modules imported without an alias.
+    imported_uris: Vec<String>,
+
+    /// The names the imported modules are bound to, such as `shapes` for `import "shapes.pkl"`.
+    pub imports: HashSet<String>,
 }
 
 impl PartialEq for PklTable {
@@ -262,6 +334,33 @@ impl PklTable {
     /// ```
     pub fn extend(&mut self, other_table: PklTable) {
         self.members.extend(other_table.members);
+        self.warnings.extend(other_table.warnings);
+        self.docs.extend(other_table.docs);
+        self.declarations.extend(other_table.declarations);
+        self.imports.extend(other_table.imports);
+    }
+
+    /// Merges another `PklTable` into this table without overwriting
+    /// members that are neither amended nor extended.
+    ///
+    /// # Arguments
+    ///
+    /// * `other_table` - The `PklTable` to merge into the current table.
+    ///
+    /// # Returns
+    ///
+    /// An `Err` containing the name of the first member defined in both tables,
+    /// in which case the current table is left untouched.
+    pub fn try_extend(&mut self, other_table: PklTable) -> Result<(), String> {
+        if let Some(name) = other_table.members.keys().find(|name| {
+            self.get(name)
+                .is_some_and(|member| !member.is_amended() && !member.is_extended())
+        }) {
+            return Err(name.to_owned());
+        }
+
+        self.extend(other_table);
+        Ok(())
     }
 
     /// Retrieves the value of a member with the given name from the context.
@@ -278,52 +377,96 @@ impl PklTable {
         self.members.get(name.as_ref())
     }
 
-    pub fn get_schema(&self, name: impl AsRef<str>) -> Option<ClassSchema> {
-        self.get(name)
-            .map(|member| member.to_owned().extract_schema())
-            .flatten()
+    /// Serializes the value members of the table to a JSON object.
+    ///
+    /// Local members are omitted and members are sorted by name,
+    /// so that the output is deterministic.
+    pub fn to_json(&self, policy: NonFiniteFloatPolicy) -> PklResult<String> {
+        let mut values = self
+            .members
+            .iter()
+            .filter_map(|(name, member)| match member {
+                PklMember::Value {
+                    value,
+                    is_local: false,
+                    ..
+                } => Some((name, value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        values.sort_by_key(|(name, _)| *name);
+
+        let mut output = String::new();
+        write_json_object(&mut output, values.into_iter(), policy)?;
+        Ok(output)
+    }
+
+    /// Renders the value members of the table as Pkl properties.
+    ///
+    /// Local members are omitted and members are sorted by name,
+    /// so that the output is deterministic.
+    pub fn render(&self) -> String {
+        let mut values = self
+            .members
+            .iter()
+            .filter_map(|(name, member)| match member {
+                PklMember::Value {
+                    value,
+                    is_local: false,
+                    ..
+                } => Some((name, value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        values.sort_by_key(|(name, _)| *name);
+
+        let mut output = String::new();
+        write_properties(&mut output, values.into_iter(), 0);
+        output
     }
-    pub fn get_value(&self, name: impl AsRef<str>) -> Option<PklValue> {
-        self.get(name)
-            .map(|member| member.to_owned().extract_value())
-            .flatten()
+
+    /// Renders the table as a module that does not depend on any other,
+    /// class instances being rendered as dynamic objects since classes are not rendered.
+    pub fn render_standalone(&self) -> String {
+        let mut standalone = self.to_owned();
+        for member in standalone.members.values_mut() {
+            if let PklMember::Value { value, .. } = member {
+                *value = std::mem::take(value).erase_classes();
+            }
+        }
+
+        standalone.render()
     }
 
+    /// Returns the names of the values, sorted so that the order is deterministic.
+    #[cfg(feature = "suggestions")]
     pub fn get_values(&self) -> Vec<&str> {
-        self.members
-            .iter()
-            .filter_map(|(k, v)| if v.is_value() { Some(k.as_str()) } else { None })
-            .collect()
+        self.sorted_names(|member| member.is_value())
     }
+    /// Returns the names of the classes, sorted so that the order is deterministic.
+    #[cfg(feature = "suggestions")]
     pub fn get_schemas(&self) -> Vec<&str> {
-        self.members
-            .iter()
-            .filter_map(|(k, v)| if v.is_class() { Some(k.as_str()) } else { None })
-            .collect()
+        self.sorted_names(|member| member.is_class())
     }
+    /// Returns the names of the amended classes, sorted so that the order is deterministic.
     pub fn get_amended_schemas(&self) -> Vec<&str> {
-        self.members
-            .iter()
-            .filter_map(|(k, v)| {
-                if v.is_class() && v.is_amended() {
-                    Some(k.as_str())
-                } else {
-                    None
-                }
-            })
-            .collect()
+        self.sorted_names(|member| member.is_class() && member.is_amended())
     }
+    /// Returns the names of the amended values, sorted so that the order is deterministic.
     pub fn get_amended_values(&self) -> Vec<&str> {
-        self.members
+        self.sorted_names(|member| member.is_value() && member.is_amended())
+    }
+
+    /// Returns the sorted names of the members matching `predicate`,
+    /// the members being stored in a map whose iteration order is unspecified.
+    fn sorted_names(&self, predicate: impl Fn(&PklMember) -> bool) -> Vec<&str> {
+        let mut names = self
+            .members
             .iter()
-            .filter_map(|(k, v)| {
-                if v.is_value() && v.is_amended() {
-                    Some(k.as_str())
-                } else {
-                    None
-                }
-            })
-            .collect()
+            .filter_map(|(k, v)| if predicate(v) { Some(k.as_str()) } else { None })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
     }
 
     pub fn import(
@@ -332,38 +475,71 @@ impl PklTable {
         local_name: Option<&str>,
         span: Span,
     ) -> PklResult<()> {
-        let imported_table = self
-            .importer
-            .import(module_uri, span.to_owned())
-            .map_err(|e| e.with_file_name(module_uri.to_owned()))?;
-
-        fn transform_map(original: HashMap<String, PklMember>) -> HashMap<String, PklValue> {
-            original
-                .into_iter()
-                .filter_map(|(key, member)| member.extract_value().map(|v| (key, v)))
-                .collect()
+        if local_name.is_none() {
+            if self.imported_uris.iter().any(|uri| uri == module_uri) {
+                if self.settings.deny_duplicate_imports {
+                    return Err((format!("Module `{module_uri}` is already imported"), span).into());
+                }
+                return Ok(());
+            }
+            self.imported_uris.push(module_uri.to_owned());
         }
 
-        let value = transform_map(imported_table.members);
-        let mut member = PklMember::value(value.into());
-        member.set_const().set_local();
+        let imported_table =
+            self.importer
+                .import(module_uri, span.to_owned(), self.settings, &self.functions)?;
+        let name = match local_name {
+            Some(local) => local,
+            None => Importer::module_id_from_uri(module_uri),
+        };
 
-        if let Some(local) = local_name {
-            self.insert(local, member);
-            return Ok(());
+        let mut values = IndexMap::new();
+        for (key, member) in imported_table.members {
+            match member {
+                // classes are kept under the module name, such as `shapes.Circle`
+                PklMember::Class { value, .. } => {
+                    let mut class = PklMember::schema(value);
+                    class.set_local();
+                    self.insert(format!("{name}.{key}"), class);
+                }
+                member => {
+                    if let Some(value) = member.extract_value() {
+                        values.insert(key, value);
+                    }
+                }
+            }
         }
 
-        let name = Importer::construct_name_from_uri(module_uri);
+        let mut member = PklMember::value(values.into());
+        member.set_const().set_local();
         self.insert(name, member);
+        self.imports.insert(name.to_owned());
 
         Ok(())
     }
 
+    /// Returns the name of the module this one amends, if any.
+    pub fn amended_module_name(&self) -> Option<&str> {
+        self.amended_or_extended_module_name
+            .as_deref()
+            .filter(|_| self.is_amended)
+    }
+
+    /// Returns the name of the module this one extends, if any.
+    pub fn extended_module_name(&self) -> Option<&str> {
+        self.amended_or_extended_module_name
+            .as_deref()
+            .filter(|_| self.is_extended)
+    }
+
     pub fn amends(&mut self, module_uri: &str, span: Span) -> PklResult<()> {
-        let amended_table = self
-            .importer
-            .amends(module_uri, span.to_owned())
-            .map_err(|e| e.with_file_name(module_uri.to_owned()))?;
+        let mut amended_table =
+            self.importer
+                .amends(module_uri, span.to_owned(), self.settings, &self.functions)?;
+        // the declarations of the amended module must be set in this one
+        for (_, declaration_span) in amended_table.declarations.values_mut() {
+            *declaration_span = span.to_owned();
+        }
 
         let amended_mod_name = Importer::construct_name_from_uri(module_uri);
 
@@ -379,10 +555,12 @@ impl PklTable {
     /// extends the current file if the
     /// other module is an open module.
     pub fn extends(&mut self, module_uri: &str, span: Span) -> PklResult<()> {
-        let extended_table = self
-            .importer
-            .extends(module_uri, span.to_owned())
-            .map_err(|e| e.with_file_name(module_uri.to_owned()))?;
+        let mut extended_table =
+            self.importer
+                .extends(module_uri, span.to_owned(), self.settings, &self.functions)?;
+        for (_, declaration_span) in extended_table.declarations.values_mut() {
+            *declaration_span = span.to_owned();
+        }
 
         if !extended_table.is_open {
             return Err((
@@ -394,13 +572,37 @@ impl PklTable {
 
         let extended_mod_name = Importer::construct_name_from_uri(module_uri);
 
+        self.is_extended = true;
         self.amended_or_extended_module_name = Some(extended_mod_name);
         self.extend(extended_table);
 
         Ok(())
     }
 
-    /// Evaluates an expression in the current context.
+    /// Evaluates an expression in the outermost scope of the table, see [`ScopedTable::evaluate`].
+    pub fn evaluate(&self, expr: PklExpr) -> PklResult<PklValue> {
+        ScopedTable::new(self).evaluate(expr)
+    }
+
+    /// Evaluates an expression in the context of a variable declaration,
+    /// in the outermost scope of the table, see [`ScopedTable::evaluate_in_variable`].
+    pub fn evaluate_in_variable(
+        &self,
+        expr: PklExpr,
+        opt_type: Option<AstPklType>,
+    ) -> PklResult<PklValue> {
+        ScopedTable::new(self).evaluate_in_variable(expr, opt_type)
+    }
+
+    /// Moves the warnings emitted while evaluating expressions to `warnings`.
+    fn flush_warnings(&mut self) {
+        let deferred = self.deferred_warnings.take();
+        self.warnings.extend(deferred);
+    }
+}
+
+impl ScopedTable<'_> {
+    /// Evaluates an expression in the current scope.
     ///
     /// # Arguments
     ///
@@ -412,21 +614,23 @@ impl PklTable {
     pub fn evaluate(&self, expr: PklExpr) -> PklResult<PklValue> {
         match expr {
             PklExpr::Identifier(Identifier(id, range)) => self
-                .get(id)
+                .get(&id)
                 .cloned()
                 .map(|v| v.extract_value())
                 .flatten()
                 .ok_or_else(|| (format!("unknown property `{}`", id), range).into()),
             PklExpr::Value(value) => self.evaluate_value(value),
-            PklExpr::MemberExpression(base_expr, indexor, range) => {
+            PklExpr::MemberExpression(base_expr, indexor, _) => {
                 let base = self.evaluate(*base_expr)?;
+                // errors point at the accessed member, not at the whole chain
+                let range = indexor.span();
 
                 match indexor {
                     ExprMember::Identifier(Identifier(property, _)) => match base {
-                        PklValue::Int(int) => match_int_props_api(int, property, range),
-                        PklValue::Float(float) => match_float_props_api(float, property, range),
+                        PklValue::Int(int) => match_int_props_api(int, &property, range),
+                        PklValue::Float(float) => match_float_props_api(float, &property, range),
                         PklValue::Object(hashmap) => {
-                            if let Some(data) = hashmap.get(property) {
+                            if let Some(data) = hashmap.get(property.as_ref()) {
                                 Ok(data.to_owned())
                             } else {
                                 Err((
@@ -436,9 +640,9 @@ impl PklTable {
                                     .into())
                             }
                         }
-                        PklValue::String(s) => match_string_props_api(&s, property, range),
+                        PklValue::String(s) => match_string_props_api(&s, &property, range),
                         PklValue::ClassInstance(_class_name, hashmap) => {
-                            if let Some(data) = hashmap.get(property) {
+                            if let Some(data) = hashmap.get(property.as_ref()) {
                                 Ok(data.to_owned())
                             } else {
                                 Err((
@@ -449,34 +653,59 @@ impl PklTable {
                             }
                         }
                         PklValue::DataSize(byte) => {
-                            match_data_size_props_api(byte, property, range)
+                            match_data_size_props_api(byte, &property, range)
                         }
                         PklValue::Duration(duration) => {
-                            match_duration_props_api(duration, property, range)
+                            match_duration_props_api(duration, &property, range)
+                        }
+                        PklValue::List(list) => match_list_props_api(list, &property, range),
+                        PklValue::Set(set) => match_set_props_api(set, &property, range),
+                        PklValue::Listing(listing) => {
+                            match_listing_props_api(listing, &property, range)
+                        }
+                        PklValue::Mapping(mapping) => {
+                            match_mapping_props_api(mapping, &property, range)
                         }
-                        PklValue::List(list) => match_list_props_api(list, property, range),
 
-                        _ => Err((
+                        _ => Err(PklError::unsupported(
                             format!("Indexing of value '{:?}' not yet supported", base),
                             range,
-                        )
-                            .into()),
+                        )),
                     },
                     ExprMember::FuncCall(FuncCall(Identifier(fn_name, _), values, _)) => {
+                        // methods taking a function are given the lambda, called on demand
+                        if let [PklExpr::Lambda(lambda)] = values.as_slice() {
+                            return match base {
+                                PklValue::List(list) => match_list_lambda_methods_api(
+                                    list,
+                                    &fn_name,
+                                    &mut |args| self.call_lambda(lambda, args),
+                                    range,
+                                ),
+                                other => Err(PklError::unsupported(
+                                    format!(
+                                        "Methods of {} taking a function are not yet supported",
+                                        other.get_type()
+                                    ),
+                                    range,
+                                )),
+                            };
+                        }
+
                         // here are method calls
                         let args = self.evaluate_fn_args(values)?;
 
                         match base {
                             PklValue::Bool(bool) => {
-                                match_bool_methods_api(bool, fn_name, args, range)
+                                match_bool_methods_api(bool, &fn_name, args, range)
                             }
-                            PklValue::Int(int) => match_int_methods_api(int, fn_name, args, range),
+                            PklValue::Int(int) => match_int_methods_api(int, &fn_name, args, range),
                             PklValue::Float(float) => {
-                                match_float_methods_api(float, fn_name, args, range)
+                                match_float_methods_api(float, &fn_name, args, range)
                             }
                             PklValue::Object(hashmap) => {
                                 // need to allow functions as fields of objects
-                                if let Some(data) = hashmap.get(fn_name) {
+                                if let Some(data) = hashmap.get(fn_name.as_ref()) {
                                     Ok(data.to_owned())
                                 } else {
                                     Err((
@@ -488,10 +717,10 @@ impl PklTable {
                             }
                             PklValue::String(s) => {
                                 // we should directly use s not &s
-                                match_string_methods_api(&s, fn_name, args, range)
+                                match_string_methods_api(&s, &fn_name, args, range)
                             }
                             PklValue::ClassInstance(_class_name, hashmap) => {
-                                if let Some(data) = hashmap.get(fn_name) {
+                                if let Some(data) = hashmap.get(fn_name.as_ref()) {
                                     Ok(data.to_owned())
                                 } else {
                                     Err((
@@ -502,29 +731,116 @@ impl PklTable {
                                 }
                             }
                             PklValue::DataSize(byte) => {
-                                match_data_size_methods_api(byte, fn_name, args, range)
+                                match_data_size_methods_api(byte, &fn_name, args, range)
                             }
                             PklValue::Duration(duration) => {
-                                match_duration_methods_api(duration, fn_name, args, range)
+                                match_duration_methods_api(duration, &fn_name, args, range)
+                            }
+                            PklValue::List(list) => {
+                                match_list_methods_api(list, &fn_name, args, range)
+                            }
+                            PklValue::Set(set) => match_set_methods_api(set, &fn_name, args, range),
+                            PklValue::Listing(listing) => {
+                                match_listing_methods_api(listing, &fn_name, args, range)
+                            }
+                            PklValue::Mapping(mapping) => {
+                                match_mapping_methods_api(mapping, &fn_name, args, range)
                             }
-                            PklValue::List(list) => match_list_props_api(list, fn_name, range),
 
-                            _ => Err((
+                            _ => Err(PklError::unsupported(
                                 format!("Indexing of value '{:?}' not yet supported", base),
                                 range,
-                            )
-                                .into()),
+                            )),
                         }
                     }
                 }
             }
-            PklExpr::FuncCall(FuncCall(Identifier(name, _), args, _span)) => {
+            PklExpr::FuncCall(FuncCall(Identifier(name, name_span), args, span)) => {
                 // all function calls
-                match name {
+                match name.as_ref() {
                     "List" => self.evaluate_list(args),
-                    _ => todo!(),
+                    "Set" => self.evaluate_set(args),
+                    "read" => self.evaluate_read(args, span),
+                    _ if self.function(&name).is_some() => {
+                        self.evaluate_registered_fn(&name, args, span)
+                    }
+                    _ => {
+                        // the span of the name includes the open parenthesis
+                        let name_span = name_span.start..name_span.end - 1;
+                        Err((format!("unknown function `{}`", name), name_span).into())
+                    }
                 }
             }
+            PklExpr::Operation(operation) => self.evaluate_operation(operation),
+            PklExpr::Parenthesized(expr, _) => self.evaluate(*expr),
+            PklExpr::Spread(_, span) => Err((
+                "Spread expressions are only allowed in `List(...)` and `Set(...)` arguments"
+                    .to_owned(),
+                span,
+            )
+                .into()),
+            PklExpr::Not(expr, _) => {
+                let operand_span = expr.span();
+                match self.evaluate(*expr)? {
+                    PklValue::Bool(b) => Ok(PklValue::Bool(!b)),
+                    value => Err(PklError::type_mismatch(
+                        format!(
+                            "Operator `!` cannot be applied to an operand of type {}",
+                            value.describe_type()
+                        ),
+                        operand_span,
+                    )),
+                }
+            }
+            PklExpr::Negate(expr, span) => negate(self.evaluate(*expr)?, span),
+            PklExpr::NonNull(expr, span) => match self.evaluate(*expr)? {
+                PklValue::Null => {
+                    Err(("Expected a non-null value, found `null`".to_owned(), span).into())
+                }
+                value => Ok(value),
+            },
+            PklExpr::Lambda(lambda) => Err(PklError::unsupported(
+                "Lambdas are only supported as the argument of a List method taking a function",
+                lambda.span(),
+            )),
+        }
+    }
+
+    /// Evaluates a binary operation.
+    ///
+    /// `&&` and `||` short-circuit, their right operand being only evaluated when needed,
+    /// an evaluated operand that is not a boolean is reported on its own span.
+    fn evaluate_operation(
+        &self,
+        Operation(left, op, right, span): Operation,
+    ) -> PklResult<PklValue> {
+        match op {
+            Operator::And | Operator::Or => {
+                let left_span = left.span();
+                let left = match self.evaluate(*left)? {
+                    PklValue::Bool(b) => b,
+                    other => return Err(non_boolean_operand_err(op, &other, left_span)),
+                };
+
+                if (op == Operator::And && !left) || (op == Operator::Or && left) {
+                    return Ok(left.into());
+                }
+
+                let right_span = right.span();
+                match self.evaluate(*right)? {
+                    PklValue::Bool(b) => Ok(b.into()),
+                    other => Err(non_boolean_operand_err(op, &other, right_span)),
+                }
+            }
+            Operator::NullCoalescing => match self.evaluate(*left)? {
+                PklValue::Null => self.evaluate(*right),
+                value => Ok(value),
+            },
+            _ => {
+                let left = self.evaluate(*left)?;
+                let right = self.evaluate(*right)?;
+                match_operation(left, op, right, span)
+            }
         }
     }
 
@@ -548,43 +864,46 @@ impl PklTable {
                 AstPklValue::ClassInstance(ClassInstance(a, b, span)) => match (a, opt_type) {
                     (Some(id), Some(_type)) => match _type {
                         AstPklType::Basic(ref value, _) if value == &id.0 => self
-                            .evaluate_class_instance(Some(Identifier(value, b.1.to_owned())), b)
+                            .evaluate_class_instance(
+                                Some(Identifier::new(value, b.1.to_owned())),
+                                b,
+                            )
                             .map(PklValue::into),
+                        AstPklType::WithAttributes { name, .. } if name == id.0 => {
+                            self.evaluate_class_instance(Some(id), b)
+                        }
                         AstPklType::Basic(value, type_span) => Err((
                             format!("Type '{value}' and '{}' do not match.", id.0),
                             type_span,
                         )
                             .into()),
-                        AstPklType::StringLiteral(_, _) => todo!(),
-                        AstPklType::Union(_, _) => todo!(),
-                        AstPklType::Nullable(_) => todo!(),
-                        AstPklType::WithAttributes {
-                            name, attributes, ..
-                        } => todo!(),
-                        AstPklType::WithRequirement {
-                            base_type,
-                            requirements,
-                            ..
-                        } => todo!(),
+                        _type => Err(PklError::unsupported(
+                            "Class instances can only be annotated with a class name for now",
+                            _type.span(),
+                        )),
                     },
                     (Some(id), None) => self
                         .evaluate_class_instance(Some(id), b)
                         .map(PklValue::into),
                     (None, Some(_type)) => match _type {
                         AstPklType::Basic(ref value, _) => self
-                            .evaluate_class_instance(Some(Identifier(value, b.1.to_owned())), b)
+                            .evaluate_class_instance(
+                                Some(Identifier::new(value, b.1.to_owned())),
+                                b,
+                            )
                             .map(PklValue::into),
-                        AstPklType::StringLiteral(_, _) => todo!(),
-                        AstPklType::Union(_, _) => todo!(),
-                        AstPklType::Nullable(_) => todo!(),
-                        AstPklType::WithAttributes {
-                            name, attributes, ..
-                        } => todo!(),
-                        AstPklType::WithRequirement {
-                            base_type,
-                            requirements,
-                            ..
-                        } => todo!(),
+                        AstPklType::WithAttributes { name, .. }
+                            if name == "Listing" || name == "Mapping" =>
+                        {
+                            self.evaluate_class_instance(
+                                Some(Identifier::new(name, b.1.to_owned())),
+                                b,
+                            )
+                        }
+                        _type => Err(PklError::unsupported(
+                            "Class instances can only be annotated with a class name for now",
+                            _type.span(),
+                        )),
                     },
                     (None, None) => Err((
                         "Unknown class instance, add the name of the class!".to_owned(),
@@ -613,31 +932,199 @@ impl PklTable {
             AstPklValue::Float(f, _) => PklValue::Float(f),
             AstPklValue::Int(i, _) => PklValue::Int(i),
             AstPklValue::Null(_) => PklValue::Null,
-            AstPklValue::String(s, _) | AstPklValue::MultiLineString(s, _) => {
-                PklValue::String(s.to_owned())
-            }
+            AstPklValue::String(s, _) => PklValue::String(decode_escapes(s).into_owned()),
+            AstPklValue::MultiLineString(fragments, _) => self.evaluate_string(fragments)?,
             AstPklValue::List(values, _) => self.evaluate_list(values)?,
             AstPklValue::Object(o) => self.evaluate_object(o)?,
             AstPklValue::ClassInstance(ClassInstance(a, b, _)) => {
                 self.evaluate_class_instance(a, b)?
             }
             AstPklValue::AmendedObject(a, b, _) => self.evaluate_amended_object(*a, b)?,
-            AstPklValue::AmendingObject(a, b, span) => self.evaluate_amending_object(a, b, span)?,
+            AstPklValue::AmendingObject(a, b, span) => {
+                self.evaluate_amending_object(&a, b, span)?
+            }
         };
 
         Ok(result)
     }
 
+    /// Calls a lambda with the given arguments, its parameters being bound in a child scope.
+    fn call_lambda(&self, lambda: &Lambda, args: Vec<PklValue>) -> PklResult<PklValue> {
+        let Lambda(parameters, body, span) = lambda;
+
+        if parameters.len() != args.len() {
+            return Err((
+                format!(
+                    "Lambda expects {} argument(s), but was called with {}",
+                    parameters.len(),
+                    args.len()
+                ),
+                span.to_owned(),
+            )
+                .into());
+        }
+
+        let mut scope = self.child();
+        for (Identifier(name, _), arg) in parameters.iter().zip(args) {
+            scope.bind(name.as_ref(), arg);
+        }
+
+        scope.evaluate(body.as_ref().to_owned())
+    }
+
+    /// Evaluates the fragments of a string, interpolated values being converted to strings.
+    fn evaluate_string(&self, fragments: Vec<StringFragment>) -> PklResult<PklValue> {
+        let mut s = String::new();
+
+        for fragment in fragments {
+            match fragment {
+                StringFragment::Text(text) => s.push_str(&text),
+                StringFragment::Interpolation(expr) => {
+                    write_interpolation(&mut s, &self.evaluate(*expr)?)
+                }
+            }
+        }
+
+        Ok(PklValue::String(s))
+    }
+
     fn evaluate_object(&self, o: ExprHash) -> PklResult<PklValue> {
-        let new_hash: Result<HashMap<_, _>, _> =
-            o.0.into_iter()
-                .map(|(name, expr)| {
-                    let evaluated_expr = self.evaluate(expr)?;
-                    Ok((name.into(), evaluated_expr))
-                })
-                .collect();
+        self.evaluate_object_fields(IndexMap::new(), o, None)
+            .map(PklValue::Object)
+    }
+
+    /// Evaluates the elements of a listing body, appended to the already existing ones.
+    fn evaluate_listing(&self, mut elements: Vec<PklValue>, o: ExprHash) -> PklResult<PklValue> {
+        match o.0.first() {
+            Some((ObjectKey::Field(name), expr)) => {
+                return Err((
+                    format!(
+                        "Cannot declare property `{name}` in a Listing, only elements are allowed"
+                    ),
+                    expr.span(),
+                )
+                    .into())
+            }
+            Some((ObjectKey::Entry(key), _)) => {
+                return Err((
+                    "Cannot declare an entry in a Listing, only elements are allowed".to_owned(),
+                    key.span(),
+                )
+                    .into())
+            }
+            None => {}
+        }
+
+        for expr in o.2 {
+            elements.push(self.evaluate(expr)?);
+        }
+
+        Ok(PklValue::Listing(elements))
+    }
+
+    /// Evaluates the entries of a mapping body, an overridden entry keeps
+    /// its position while a new one is appended.
+    fn evaluate_mapping(
+        &self,
+        mut entries: IndexMap<String, PklValue>,
+        o: ExprHash,
+    ) -> PklResult<PklValue> {
+        if let Some(element) = o.2.first() {
+            return Err((
+                "Cannot declare an element in a Mapping, only entries are allowed".to_owned(),
+                element.span(),
+            )
+                .into());
+        }
+
+        for (key, expr) in o.0 {
+            let key = match key {
+                ObjectKey::Field(name) => name.into_owned(),
+                ObjectKey::Entry(key) => self.evaluate_entry_key(key)?,
+            };
+            entries.insert(key, self.evaluate(expr)?);
+        }
 
-        new_hash.map(PklValue::Object)
+        Ok(PklValue::Mapping(entries))
+    }
+
+    /// Evaluates the key of an `[key] = value` entry, which must be a string.
+    fn evaluate_entry_key(&self, key: PklExpr) -> PklResult<String> {
+        let span = key.span();
+
+        match self.evaluate(key)? {
+            PklValue::String(key) => Ok(key),
+            other => Err(PklError::type_mismatch(
+                format!(
+                    "Entry keys are expected to be of type String, but found {}",
+                    other.describe_type()
+                ),
+                span,
+            )),
+        }
+    }
+
+    /// Evaluates the fields of an object in their declaration order on top of
+    /// already existing fields, each field being visible to the ones declared after it.
+    ///
+    /// When amending a class instance, its class name and schema are given
+    /// so that the fields are checked against their declared types.
+    fn evaluate_object_fields(
+        &self,
+        mut fields: IndexMap<String, PklValue>,
+        o: ExprHash,
+        schema: Option<(&str, &ClassSchema)>,
+    ) -> PklResult<IndexMap<String, PklValue>> {
+        if let Some(element) = o.2.first() {
+            return Err(PklError::unsupported(
+                "Elements of objects are not yet supported, use a Listing",
+                element.span(),
+            ));
+        }
+
+        let mut scope = self.child();
+        for (name, value) in &fields {
+            scope.bind(name, value.to_owned());
+        }
+
+        // the key of an entry sees the fields declared before it
+        for (key, expr) in o.0 {
+            let name = match key {
+                ObjectKey::Field(name) => name.into_owned(),
+                ObjectKey::Entry(key) => scope.evaluate_entry_key(key)?,
+            };
+            self.evaluate_object_field(&mut scope, &mut fields, name, expr, schema)?;
+        }
+
+        Ok(fields)
+    }
+
+    /// Evaluates a field of an object in `scope` and binds it there
+    /// for the fields declared after it.
+    fn evaluate_object_field(
+        &self,
+        scope: &mut ScopedTable,
+        fields: &mut IndexMap<String, PklValue>,
+        name: String,
+        expr: PklExpr,
+        schema: Option<(&str, &ClassSchema)>,
+    ) -> PklResult<()> {
+        let expr_span = expr.span();
+        let mut evaluated_expr = scope.evaluate(expr)?;
+
+        if let Some((class_name, schema)) = schema {
+            check_instance_field(class_name, schema, &name, &evaluated_expr, expr_span)?;
+        }
+        if self.settings().deep_amend {
+            if let Some(previous) = fields.get(&name) {
+                evaluated_expr = previous.to_owned().deep_merge(evaluated_expr);
+            }
+        }
+
+        scope.bind(name.as_str(), evaluated_expr.to_owned());
+        // an overridden field keeps its position, a new one is appended
+        fields.insert(name, evaluated_expr);
+        Ok(())
     }
 
     fn evaluate_fn_args(&self, values: Vec<PklExpr>) -> PklResult<Vec<PklValue>> {
@@ -652,10 +1139,83 @@ impl PklTable {
         new_hash
     }
 
+    /// Evaluates a `read(...)` call, reading the YAML or TOML resource
+    /// it is given into a value, or a `file:` or `env:` resource into a `Resource` object.
+    fn evaluate_read(&self, args: Vec<PklExpr>, span: Span) -> PklResult<PklValue> {
+        let args = self.evaluate_fn_args(args)?;
+
+        match args.as_slice() {
+            [PklValue::String(uri)] => resource::read_resource(uri, span),
+            [arg] => Err(PklError::type_mismatch(
+                format!(
+                    "Function 'read' expects argument at index 0 to be of type String, but found {}",
+                    arg.describe_type()
+                ),
+                span,
+            )),
+            _ => Err((
+                "Function 'read' expects exactly 1 argument".to_owned(),
+                span,
+            )
+                .into()),
+        }
+    }
+
+    /// Calls a function registered from Rust with the evaluated arguments,
+    /// the errors it returns without a span being reported on the call.
+    fn evaluate_registered_fn(
+        &self,
+        name: &str,
+        args: Vec<PklExpr>,
+        span: Span,
+    ) -> PklResult<PklValue> {
+        let args = args
+            .into_iter()
+            .map(|arg| self.evaluate(arg))
+            .collect::<PklResult<Vec<_>>>()?;
+
+        let f = self.function(name).expect("function to be registered");
+        f(&args).map_err(|e| match e {
+            PklError::WithoutContext(msg, _) => (msg, span).into(),
+            e => e,
+        })
+    }
+
+    /// Evaluates the arguments of a `List(...)` call,
+    /// spread arguments being flattened into the list.
     fn evaluate_list(&self, values: Vec<PklExpr>) -> PklResult<PklValue> {
-        let new_hash = self.evaluate_fn_args(values);
+        self.evaluate_elements(values).map(PklValue::List)
+    }
 
-        new_hash.map(PklValue::List)
+    /// Evaluates the arguments of a `Set(...)` call like those of a `List(...)` call,
+    /// elements equal to a previous one being dropped.
+    fn evaluate_set(&self, values: Vec<PklExpr>) -> PklResult<PklValue> {
+        let elements = self.evaluate_elements(values)?;
+        Ok(PklValue::Set(distinct(elements)))
+    }
+
+    fn evaluate_elements(&self, values: Vec<PklExpr>) -> PklResult<Vec<PklValue>> {
+        let mut list = Vec::with_capacity(values.len());
+
+        for expr in values {
+            match expr {
+                PklExpr::Spread(expr, span) => match self.evaluate(*expr)? {
+                    PklValue::List(elements) | PklValue::Set(elements) => list.extend(elements),
+                    other => {
+                        return Err(PklError::type_mismatch(
+                            format!(
+                                "Cannot spread a value of type {}, expected a List or a Set",
+                                other.describe_type()
+                            ),
+                            span,
+                        ))
+                    }
+                },
+                expr => list.push(self.evaluate(expr)?),
+            }
+        }
+
+        Ok(list)
     }
 
     /// Function should only be called when not in a variable declaration
@@ -674,79 +1234,189 @@ impl PklTable {
                 .into()),
         };
 
-        let new_hash: Result<HashMap<_, _>, PklError> =
-            b.0.into_iter()
-                .map(|(name, expr)| {
-                    let evaluated_expr = self.evaluate(expr)?;
-                    Ok((name.into(), evaluated_expr))
-                })
-                .collect();
+        match a.value() {
+            "Listing" => return self.evaluate_listing(Vec::new(), b),
+            "Mapping" => return self.evaluate_mapping(IndexMap::new(), b),
+            _ => {}
+        }
+        if let Some(element) = b.2.first() {
+            return Err((
+                format!("Instances of {} cannot have elements", a.0),
+                element.span(),
+            )
+                .into());
+        }
+        let mut fields = Vec::with_capacity(b.0.len());
+        for (key, expr) in b.0 {
+            match key {
+                ObjectKey::Field(name) => fields.push((name, expr)),
+                ObjectKey::Entry(key) => {
+                    return Err((
+                        format!("Instances of {} cannot have entries", a.0),
+                        key.span(),
+                    )
+                        .into())
+                }
+            }
+        }
 
-        let schema = match self.get_schema(a.0) {
+        let schema = match self.get_schema(a.value()) {
             Some(schema) => schema,
             None => return Err((format!("Unknown class '{}'", a.0), a.1).into()),
         };
 
-        let found_schema = new_hash?;
+        // each field sees the ones declared before it,
+        // and `this` bound to the instance built so far
+        let mut scope = self.child();
+        let mut found_schema = IndexMap::new();
+        for (name, expr) in fields {
+            let evaluated_expr = scope.evaluate(expr)?;
+
+            scope.bind(name.as_ref(), evaluated_expr.to_owned());
+            found_schema.insert(name.into_owned(), evaluated_expr);
+            scope.bind(
+                "this",
+                PklValue::ClassInstance(a.0.to_string(), found_schema.to_owned()),
+            );
+        }
+
+        // sorted so that omitted fields are appended in a deterministic order
+        let mut schema_keys = schema.keys().collect::<Vec<_>>();
+        schema_keys.sort();
 
-        for k in schema.keys() {
+        for k in schema_keys {
             if !found_schema.contains_key(k) {
-                return Err((format!("Missing key '{k}' in instance of {}", a.0), b.1).into());
+                // an omitted nullable field defaults to null
+                if schema[k].can_be_nullable() {
+                    found_schema.insert(k.to_owned(), PklValue::Null);
+                    continue;
+                }
+
+                let message = format!("Missing key '{k}' in instance of {}", a.0);
+                self.error_or_warn(message, b.1.to_owned())?;
             }
         }
         for k in found_schema.keys() {
             if !schema.contains_key(k) {
-                return Err((format!("Unknown key '{k}' in instance of {}", a.0), b.1).into());
+                let message = format!("Unknown key '{k}' in instance of {}", a.0);
+                self.error_or_warn(message, b.1.to_owned())?;
             }
         }
 
         // Todo: Check if the types of the values are correct in the found_schema
         for (k, v) in &found_schema {
-            let _type = schema.get(k).unwrap();
+            // unknown keys are only kept in lenient mode
+            let Some(_type) = schema.get(k) else {
+                continue;
+            };
             if !v.is_instance_of(_type) {
-                return Err((
+                return Err(PklError::type_mismatch(
                     format!(
-                        "Invalid type for key '{k}', not an instance of '{:?}'",
-                        _type
+                        "Invalid type for key '{k}', expected '{}' but found '{}'",
+                        _type,
+                        v.describe_type()
                     ),
                     b.1,
-                )
-                    .into());
+                ));
             }
         }
 
         Ok(PklValue::ClassInstance(a.0.into(), found_schema))
     }
 
-    fn evaluate_amending_object(&self, a: &str, b: ExprHash, span: Span) -> PklResult<PklValue> {
-        let other_object = match self.get_value(a) {
-            Some(PklValue::Object(hash)) => hash,
-            _ => return Err((format!("Unknown object `{}`", a), span).into()),
-        };
-
-        let mut new_hash = other_object.clone();
-        for (name, expr) in b.0 {
-            new_hash.insert(name.into(), self.evaluate(expr)?);
+    /// Returns the error, or only emits it as a warning
+    /// when class instances are lenient.
+    fn error_or_warn(&self, message: String, span: Span) -> PklResult<()> {
+        if !self.settings().lenient_instances {
+            return Err((message, span).into());
         }
 
-        Ok(PklValue::Object(new_hash))
+        self.defer_warning(PklWarning::new(message, span));
+        Ok(())
+    }
+
+    fn evaluate_amending_object(&self, a: &str, b: ExprHash, span: Span) -> PklResult<PklValue> {
+        match self.get_value(a) {
+            Some(PklValue::Object(hash)) => self
+                .evaluate_object_fields(hash, b, None)
+                .map(PklValue::Object),
+            Some(PklValue::ClassInstance(class_name, hash)) => {
+                self.evaluate_amended_instance(class_name, hash, b)
+            }
+            Some(PklValue::Listing(elements)) => self.evaluate_listing(elements, b),
+            Some(PklValue::Mapping(entries)) => self.evaluate_mapping(entries, b),
+            _ => Err((format!("Unknown object `{}`", a), span).into()),
+        }
     }
 
     fn evaluate_amended_object(&self, a: AstPklValue, b: ExprHash) -> PklResult<PklValue> {
-        let first_object = match self.evaluate_value(a)? {
-            PklValue::Object(o) => o,
+        match self.evaluate_value(a)? {
+            PklValue::Object(o) => self
+                .evaluate_object_fields(o, b, None)
+                .map(PklValue::Object),
+            PklValue::ClassInstance(class_name, hash) => {
+                self.evaluate_amended_instance(class_name, hash, b)
+            }
+            PklValue::Listing(elements) => self.evaluate_listing(elements, b),
+            PklValue::Mapping(entries) => self.evaluate_mapping(entries, b),
             _ => unreachable!("should not be reached due to the parser work"),
+        }
+    }
+
+    /// Amends a class instance, the overriding fields
+    /// must be declared by the class with a matching type.
+    fn evaluate_amended_instance(
+        &self,
+        class_name: String,
+        fields: IndexMap<String, PklValue>,
+        b: ExprHash,
+    ) -> PklResult<PklValue> {
+        let fields = match self.get_schema(&class_name) {
+            Some(schema) => self.evaluate_object_fields(fields, b, Some((&class_name, &schema)))?,
+            None => self.evaluate_object_fields(fields, b, None)?,
         };
 
-        let mut new_hash = first_object;
-        for (name, expr) in b.0 {
-            new_hash.insert(name.into(), self.evaluate(expr)?);
-        }
+        Ok(PklValue::ClassInstance(class_name, fields))
+    }
+}
 
-        Ok(PklValue::Object(new_hash))
+/// Checks that a field of an instance of `class_name` is declared in its schema
+/// and that its value corresponds to the declared type.
+fn check_instance_field(
+    class_name: &str,
+    schema: &ClassSchema,
+    name: &str,
+    value: &PklValue,
+    span: Span,
+) -> PklResult<()> {
+    match schema.get(name) {
+        None => Err((
+            format!("Unknown key '{name}' in instance of {class_name}"),
+            span,
+        )
+            .into()),
+        Some(_type) if !value.is_instance_of(_type) => Err(PklError::type_mismatch(
+            format!(
+                "Invalid type for key '{name}', expected '{}' but found '{}'",
+                _type,
+                value.describe_type()
+            ),
+            span,
+        )),
+        Some(_) => Ok(()),
     }
 }
 
+fn non_boolean_operand_err(op: Operator, value: &PklValue, span: Span) -> PklError {
+    PklError::type_mismatch(
+        format!(
+            "expected Boolean operand for operator `{op}`, found {}",
+            value.describe_type()
+        ),
+        span,
+    )
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct StatementBuilder {
     pub fixed_found: bool,
@@ -762,8 +1432,47 @@ impl StatementBuilder {
     }
 }
 
-pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
-    let mut table = PklTable::default();
+pub fn ast_to_table(
+    ast: Vec<PklStatement>,
+    settings: TableSettings,
+    importer: Importer,
+    functions: FunctionRegistry,
+) -> PklResult<PklTable> {
+    build_table(ast, settings, importer, functions, None)
+}
+
+/// Builds a table the same way `ast_to_table` does, except that
+/// the errors of properties do not stop the build, all the errors being returned.
+pub fn validate_ast(
+    ast: Vec<PklStatement>,
+    settings: TableSettings,
+    importer: Importer,
+    functions: FunctionRegistry,
+) -> Vec<PklError> {
+    let mut errors = Vec::new();
+
+    if let Err(e) = build_table(ast, settings, importer, functions, Some(&mut errors)) {
+        errors.push(e);
+    }
+
+    errors
+}
+
+/// Builds a table from the statements, the errors of properties
+/// being collected in `property_errors` when given instead of being returned.
+fn build_table(
+    ast: Vec<PklStatement>,
+    settings: TableSettings,
+    importer: Importer,
+    functions: FunctionRegistry,
+    mut property_errors: Option<&mut Vec<PklError>>,
+) -> PklResult<PklTable> {
+    let mut table = PklTable {
+        importer,
+        settings,
+        functions,
+        ..Default::default()
+    };
 
     // if encountered a body statement
     // == no more import stmt allowed
@@ -775,7 +1484,14 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
 
     let mut stmt_builder = StatementBuilder::default();
 
-    for statement in ast {
+    // statements are popped from the end, the members
+    // of a `when` branch being pushed back in their place
+    let mut statements = order_forward_references(ast)?
+        .into_iter()
+        .rev()
+        .collect::<Vec<_>>();
+
+    while let Some(statement) = statements.pop() {
         match statement {
             PklStatement::ModuleClause(Module {
                 full_name,
@@ -793,7 +1509,7 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
                         .into());
                 }
 
-                table.module_name = Some(full_name.0.to_owned());
+                table.module_name = Some(full_name.0.into_owned());
                 table.is_open = is_open;
                 module_clause_found = true;
             }
@@ -853,7 +1569,7 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
                         .into());
                 }
 
-                table.import(name, local_name, span)?;
+                table.import(name, local_name.as_deref(), span)?;
                 import_found = true;
             }
             PklStatement::TypeAlias(TypeAlias { .. }) => {
@@ -865,12 +1581,38 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
 
             PklStatement::Property(property) => {
                 in_body = true;
-                handle_property(&mut table, property, stmt_builder)?;
+                let result = handle_property(&mut table, property, stmt_builder);
+                collect_property_error(result, &mut property_errors)?;
             }
             PklStatement::Class(declaration) => {
                 in_body = true;
                 handle_class(&mut table, declaration)?;
             }
+            PklStatement::When(When {
+                condition,
+                body,
+                else_body,
+                ..
+            }) => {
+                in_body = true;
+
+                let condition_span = condition.span();
+                let branch = match table.evaluate(condition)? {
+                    PklValue::Bool(true) => body,
+                    PklValue::Bool(false) => else_body.unwrap_or_default(),
+                    other => {
+                        return Err(PklError::type_mismatch(
+                            format!(
+                                "Condition of `when` must be of type Boolean, found {}",
+                                other.get_type()
+                            ),
+                            condition_span,
+                        ))
+                    }
+                };
+
+                statements.extend(branch.into_iter().rev());
+            }
 
             // there three prefixes below can be before a Class,
             // a TypeAlias, a Property or a function
@@ -886,11 +1628,31 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
                 // and do the same for const and fixed stmts
 
                 match *stmt {
-                    PklStatement::Property(prop) => handle_property(&mut table, prop, stmt_builder),
-                    PklStatement::Class(_) => todo!(),
-                    PklStatement::TypeAlias(_) => todo!(),
-                    PklStatement::Const(_, _) => todo!(),
-                    PklStatement::Local(_, span) => todo!(),
+                    PklStatement::Property(prop) => {
+                        let result = handle_property(&mut table, prop, stmt_builder);
+                        collect_property_error(result, &mut property_errors)?
+                    }
+                    PklStatement::Class(stmt) => {
+                        return Err(PklError::unsupported(
+                            "Local classes are not yet supported",
+                            stmt.span,
+                        ))
+                    }
+                    PklStatement::TypeAlias(stmt) => {
+                        return Err(PklError::unsupported(
+                            "Local typealiases are not yet supported",
+                            stmt.span,
+                        ))
+                    }
+                    PklStatement::Const(_, span) => {
+                        return Err(PklError::unsupported(
+                            "Combining the `local` and `const` modifiers is not yet supported",
+                            span,
+                        ))
+                    }
+                    PklStatement::Local(_, span) => {
+                        return Err(("Duplicate modifier `local`".to_owned(), span).into())
+                    }
 
                     PklStatement::Fixed(_, span) => {
                         return Err((
@@ -911,17 +1673,30 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
                     PklStatement::Import(stmt) => {
                         return Err((stmt.not_allowed_here_err(), stmt.span).into())
                     }
+                    PklStatement::When(stmt) => {
+                        return Err((stmt.modifier_not_applicable_err("local"), stmt.span).into())
+                    }
                 };
             }
 
             PklStatement::Const(stmt, span) => {
                 in_body = true;
+                stmt_builder.const_found = true;
 
                 match *stmt {
-                    PklStatement::Property(_) => todo!(),
-                    PklStatement::Const(_, _) => todo!(),
-                    PklStatement::Fixed(_, span) => todo!(),
-                    PklStatement::Local(_, span) => todo!(),
+                    PklStatement::Property(prop) => {
+                        let result = handle_property(&mut table, prop, stmt_builder);
+                        collect_property_error(result, &mut property_errors)?
+                    }
+                    PklStatement::Const(_, span) => {
+                        return Err(("Duplicate modifier `const`".to_owned(), span).into())
+                    }
+                    PklStatement::Fixed(_, span) | PklStatement::Local(_, span) => {
+                        return Err(PklError::unsupported(
+                            "Combining the `const` modifier with other modifiers is not yet supported",
+                            span,
+                        ))
+                    }
 
                     PklStatement::Class(stmt) => {
                         return Err((stmt.modifier_not_applicable_err("const"), stmt.span).into())
@@ -941,15 +1716,27 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
                     PklStatement::Import(stmt) => {
                         return Err((stmt.not_allowed_here_err(), stmt.span).into())
                     }
+                    PklStatement::When(stmt) => {
+                        return Err((stmt.modifier_not_applicable_err("const"), stmt.span).into())
+                    }
                 }
             }
             PklStatement::Fixed(stmt, span) => {
                 in_body = true;
+                stmt_builder.fixed_found = true;
 
                 match *stmt {
-                    PklStatement::Property(_) => todo!(),
+                    PklStatement::Property(prop) => {
+                        let result = handle_property(&mut table, prop, stmt_builder);
+                        collect_property_error(result, &mut property_errors)?
+                    }
 
-                    PklStatement::Const(_, _) => todo!(),
+                    PklStatement::Const(_, span) => {
+                        return Err(PklError::unsupported(
+                            "Combining the `fixed` and `const` modifiers is not yet supported",
+                            span,
+                        ))
+                    }
 
                     PklStatement::Class(stmt) => {
                         return Err((stmt.modifier_not_applicable_err("fixed"), stmt.span).into())
@@ -957,7 +1744,9 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
                     PklStatement::TypeAlias(stmt) => {
                         return Err((stmt.modifier_not_applicable_err("fixed"), stmt.span).into())
                     }
-                    PklStatement::Fixed(_, span) => todo!(),
+                    PklStatement::Fixed(_, span) => {
+                        return Err(("Duplicate modifier `fixed`".to_owned(), span).into())
+                    }
                     PklStatement::Local(_, span) => {
                         return Err((
                             format!("Modifier `fixed` is redundant here; just use `local`."),
@@ -978,15 +1767,50 @@ pub fn ast_to_table(ast: Vec<PklStatement>) -> PklResult<PklTable> {
                     PklStatement::Import(stmt) => {
                         return Err((stmt.not_allowed_here_err(), stmt.span).into())
                     }
+                    PklStatement::When(stmt) => {
+                        return Err((stmt.modifier_not_applicable_err("fixed"), stmt.span).into())
+                    }
                 }
             }
         }
         stmt_builder.reset();
     }
 
+    // only open modules can leave declared properties unset
+    if !table.is_open {
+        let unset = table
+            .declarations
+            .iter()
+            .min_by_key(|(name, _)| name.as_str());
+
+        if let Some((name, (_type, span))) = unset {
+            return Err((
+                format!(
+                    "Property `{name}` of type {_type} is declared without a value, which only open modules allow"
+                ),
+                span.to_owned(),
+            )
+                .into());
+        }
+    }
+
     Ok(table)
 }
 
+/// Records the error of a property when errors are collected, otherwise returns it.
+fn collect_property_error(
+    result: PklResult<()>,
+    property_errors: &mut Option<&mut Vec<PklError>>,
+) -> PklResult<()> {
+    match (result, property_errors) {
+        (Err(e), Some(errors)) => {
+            errors.push(e);
+            Ok(())
+        }
+        (result, _) => result,
+    }
+}
+
 fn handle_property(
     table: &mut PklTable,
     Property {
@@ -997,29 +1821,46 @@ fn handle_property(
     }: Property,
     stmt_builder: StatementBuilder,
 ) -> PklResult<()> {
-    let evaluated_value = table.evaluate_in_variable(value, _type.clone())?;
+    let Some(value) = value else {
+        return handle_declaration(table, name, _type, span, stmt_builder);
+    };
+
+    collect_entry_docs(&mut table.docs, name.value(), &value);
+    let evaluated_value = table.evaluate_in_variable(value, _type.clone());
+    table.flush_warnings();
+    let evaluated_value = evaluated_value?;
+
+    if table.settings.lint_property_names && !is_camel_case(name.value()) {
+        table.warnings.push(PklWarning::new(
+            format!("Property name `{}` is not camelCase", name.0),
+            name.1.to_owned(),
+        ));
+    }
 
-    // checks for spelling errors
-    let vars = table
-        .get_values()
-        .into_iter()
-        .filter(|x| *x != name.0)
-        .collect::<Vec<&str>>();
+    // checks for spelling errors, skipped without the `suggestions` feature
+    #[cfg(feature = "suggestions")]
+    {
+        let vars = table
+            .get_values()
+            .into_iter()
+            .filter(|x| *x != name.value())
+            .collect::<Vec<&str>>();
 
-    if !vars.is_empty() && name.0.len() > 2 {
-        match check_closest_word(name.0, vars.as_slice(), 1) {
-            Some(closest) => {
-                return Err((
-                    format!(
-                        "Did you mean to write '{}' instead of '{}'?",
-                        closest, name.0
-                    ),
-                    name.1,
-                )
-                    .into())
-            }
-            None => (),
-        };
+        if !vars.is_empty() && name.0.len() > 2 {
+            match check_closest_word(name.value(), vars.as_slice(), 1) {
+                Some(closest) => {
+                    return Err((
+                        format!(
+                            "Did you mean to write '{}' instead of '{}'?",
+                            closest, name.0
+                        ),
+                        name.1,
+                    )
+                        .into())
+                }
+                None => (),
+            };
+        }
     }
 
     // checks if user creates variables
@@ -1028,7 +1869,10 @@ fn handle_property(
         let amended_mod_name = table.amended_or_extended_module_name.as_ref().unwrap();
         let amended_values = table.get_amended_values();
 
-        if !stmt_builder.local_found && !amended_values.contains(&name.0) {
+        if !stmt_builder.local_found
+            && !amended_values.contains(&name.value())
+            && !table.declarations.contains_key(name.value())
+        {
             return Err((
                 format!(
                     "Cannot find property `{}` in module `{}`",
@@ -1042,17 +1886,36 @@ fn handle_property(
 
     // checks if type corresponds to value
     if let Some(_type) = _type {
+        if let Some(span) = _type.requirement_span() {
+            return Err(unsupported_requirement_err(span));
+        }
         let span = _type.span();
         let true_type: PklType = _type.into();
         if !evaluated_value.is_instance_of(&true_type) {
-            return Err((
+            return Err(PklError::type_mismatch(
                 format!(
-                    "Type '{}' does not correspond to the value of '{}'",
-                    true_type, name.0
+                    "Type '{}' does not correspond to the value of '{}', found '{}'",
+                    true_type,
+                    name.0,
+                    evaluated_value.describe_type()
                 ),
                 span,
-            )
-                .into());
+            ));
+        }
+    }
+
+    // checks the value of a declared property against its declared type
+    if let Some((declared_type, _)) = table.declarations.remove(name.value()) {
+        if !evaluated_value.is_instance_of(&declared_type) {
+            return Err(PklError::type_mismatch(
+                format!(
+                    "Type '{}' does not correspond to the value of '{}', found '{}'",
+                    declared_type,
+                    name.0,
+                    evaluated_value.describe_type()
+                ),
+                name.1,
+            ));
         }
     }
 
@@ -1063,7 +1926,7 @@ fn handle_property(
     // otherwise throws an Error
     let mut member = PklMember::value(evaluated_value);
     member.set_stmt_builder(stmt_builder);
-    if let Some(prev_member) = table.insert(name.0, member) {
+    if let Some(prev_member) = table.insert(name.value(), member) {
         if !prev_member.is_amended() && !prev_member.is_extended() {
             return Err((
                 format!("Duplicate definition of member `{}`", name.0),
@@ -1102,30 +1965,112 @@ fn handle_property(
     Ok(())
 }
 
-fn handle_class(table: &mut PklTable, declaration: ClassDeclaration) -> PklResult<()> {
-    let (name, schema) = generate_class_schema(declaration);
+/// Records a property declared with a type but without a value, such as `port: Int`.
+fn handle_declaration(
+    table: &mut PklTable,
+    name: Identifier,
+    _type: Option<AstPklType>,
+    span: Span,
+    stmt_builder: StatementBuilder,
+) -> PklResult<()> {
+    let Some(_type) = _type else {
+        return Err((format!("Property `{}` must have a value", name.0), span).into());
+    };
+
+    if stmt_builder.local_found || stmt_builder.const_found || stmt_builder.fixed_found {
+        return Err((
+            format!(
+                "Property `{}` must have a value, as it has a modifier",
+                name.0
+            ),
+            span,
+        )
+            .into());
+    }
+    if let Some(span) = _type.requirement_span() {
+        return Err(unsupported_requirement_err(span));
+    }
+    if table.get(name.value()).is_some() || table.declarations.contains_key(name.value()) {
+        return Err((
+            format!("Duplicate definition of member `{}`", name.0),
+            name.1,
+        )
+            .into());
+    }
 
-    // checks for spelling errors
-    let vars = table
-        .get_schemas()
-        .into_iter()
-        .filter(|x| *x != name.0)
-        .collect::<Vec<&str>>();
+    table
+        .declarations
+        .insert(name.0.into_owned(), (_type.into(), span));
 
-    if !vars.is_empty() && name.0.len() > 2 {
-        match check_closest_word(name.0, vars.as_slice(), 1) {
-            Some(closest) => {
-                return Err((
-                    format!(
-                        "Did you mean to write '{}' instead of '{}'?",
-                        closest, name.0
-                    ),
-                    name.1,
-                )
-                    .into())
+    Ok(())
+}
+
+/// Collects the doc comments of the entries of the object `expr` evaluates to, if any,
+/// and of the entries of its nested objects, under `path`.
+fn collect_entry_docs(docs: &mut HashMap<String, String>, path: &str, expr: &PklExpr) {
+    let PklExpr::Value(value) = expr else {
+        return;
+    };
+
+    let (members, _, _, entry_docs) = match value {
+        AstPklValue::Object(object)
+        | AstPklValue::AmendingObject(_, object, _)
+        | AstPklValue::ClassInstance(ClassInstance(_, object, _)) => object,
+        AstPklValue::AmendedObject(amended, object, _) => {
+            collect_entry_docs(docs, path, &PklExpr::Value(*amended.to_owned()));
+            object
+        }
+        _ => return,
+    };
+
+    for (name, doc) in entry_docs {
+        docs.insert(format!("{path}.{name}"), doc.to_owned());
+    }
+    for (key, member) in members {
+        match key {
+            ObjectKey::Field(name) => collect_entry_docs(docs, &format!("{path}.{name}"), member),
+            ObjectKey::Entry(PklExpr::Value(AstPklValue::String(key, _))) => {
+                collect_entry_docs(docs, &format!("{path}.{key}"), member)
             }
-            None => (),
-        };
+            ObjectKey::Entry(_) => {}
+        }
+    }
+}
+
+fn handle_class(table: &mut PklTable, declaration: ClassDeclaration) -> PklResult<()> {
+    for field in declaration.fields.keys() {
+        if let Some(doc) = field.doc() {
+            let path = format!("{}.{}", declaration.name.0, field.name);
+            table.docs.insert(path, doc.to_owned());
+        }
+    }
+
+    let (name, schema) = generate_class_schema(declaration)?;
+
+    // checks for spelling errors, skipped without the `suggestions` feature
+    #[cfg(feature = "suggestions")]
+    {
+        let vars = table
+            .get_schemas()
+            .into_iter()
+            .filter(|x| *x != name.value())
+            .collect::<Vec<&str>>();
+
+        if !vars.is_empty() && name.0.len() > 2 {
+            match check_closest_word(name.value(), vars.as_slice(), 1) {
+                Some(closest) => {
+                    return Err((
+                        format!(
+                            "Did you mean to write '{}' instead of '{}'?",
+                            closest, name.0
+                        ),
+                        name.1,
+                    )
+                        .into())
+                }
+                None => (),
+            };
+        }
     }
 
     // checks if adding variables to amending module
@@ -1134,7 +2079,7 @@ fn handle_class(table: &mut PklTable, declaration: ClassDeclaration) -> PklResul
         let amended_mod_name = table.amended_or_extended_module_name.as_ref().unwrap();
         let amended_schemas = table.get_amended_schemas();
 
-        if !amended_schemas.contains(&name.0) {
+        if !amended_schemas.contains(&name.value()) {
             return Err((
                 format!(
                     "Cannot find property `{}` in module `{}`",
@@ -1151,7 +2096,7 @@ fn handle_class(table: &mut PklTable, declaration: ClassDeclaration) -> PklResul
     // if schema is amended/extended then allows
     // assignment in new module
     // otherwise throws an Error
-    if let Some(prev_member) = table.insert(name.0, PklMember::schema(schema)) {
+    if let Some(prev_member) = table.insert(name.value(), PklMember::schema(schema)) {
         if !prev_member.is_amended() && !prev_member.is_extended() {
             return Err((
                 format!("Duplicate definition of member `{}`", name.0),
@@ -1189,3 +2134,232 @@ fn handle_class(table: &mut PklTable, declaration: ClassDeclaration) -> PklResul
 
     Ok(())
 }
+
+/// Whether a name is camelCase, such as `myProp` or `http2Port`.
+fn is_camel_case(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|c| c.is_ascii_lowercase()) && chars.all(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{error_slice, eval, eval_err, parse, parse_err},
+        Pkl, PklValue,
+    };
+
+    #[test]
+    fn object_fields_see_the_fields_declared_before_them() {
+        let pkl = parse("x {\n  a = 1\n  b = a + 1\n}");
+        assert_eq!(pkl.get_path("x.b"), Some(PklValue::Int(2)));
+    }
+
+    #[test]
+    fn object_fields_cannot_reference_the_fields_declared_after_them() {
+        let source = "x {\n  b = a + 1\n  a = 1\n}";
+        let error = parse_err(source);
+
+        assert_eq!(error.msg(), "unknown property `a`");
+        assert_eq!(error.span(), Some(10..11));
+    }
+
+    #[test]
+    fn members_of_call_results_can_be_accessed() {
+        assert_eq!(eval("List(1, 2, 3).first"), PklValue::Int(1));
+        assert_eq!(eval("List(1, 2, 3).length"), PklValue::Int(3));
+
+        let mut pkl = Pkl::new();
+        pkl.register_fn("person", |_| {
+            Ok(PklValue::Object(
+                [("name".to_owned(), PklValue::String("x".to_owned()))]
+                    .into_iter()
+                    .collect(),
+            ))
+        });
+        assert!(pkl.parse("name = person().name").is_ok());
+        assert_eq!(
+            pkl.get_value("name"),
+            Some(PklValue::String("x".to_owned()))
+        );
+    }
+
+    #[test]
+    fn unknown_functions_are_reported_on_their_name() {
+        let source = "missing(1)";
+        let error = eval_err(source);
+
+        assert_eq!(error.msg(), "unknown function `missing`");
+        assert_eq!(error_slice(source, &error), "missing");
+    }
+
+    #[test]
+    fn lists_can_be_spread_in_list_arguments() {
+        let pkl = parse("xs = List(1, 2, 3)\nys = List(...xs, 4)");
+        assert_eq!(
+            pkl.get_value("ys"),
+            Some(PklValue::List((1..=4).map(PklValue::Int).collect()))
+        );
+    }
+
+    #[test]
+    fn spreading_a_non_list_is_reported_on_the_spread() {
+        let source = "List(...1, 2)";
+        let error = eval_err(source);
+
+        assert_eq!(
+            error.msg(),
+            "Cannot spread a value of type Int, expected a List or a Set"
+        );
+        assert_eq!(error_slice(source, &error), "...1");
+    }
+
+    #[test]
+    fn amending_keeps_the_order_of_the_fields() {
+        let pkl =
+            parse("base {\n  a = 1\n  b = 1\n  c = 1\n}\nderived = (base) {\n  b = 2\n  d = 3\n}");
+
+        let Some(PklValue::Object(fields)) = pkl.get_value("derived") else {
+            panic!("expected an object");
+        };
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+        assert_eq!(fields["b"], PklValue::Int(2));
+    }
+
+    #[test]
+    fn amended_instance_fields_are_checked_against_their_class() {
+        let class = "class Server {\n  port: Int\n}\nbase = new Server { port = 80 }\n";
+
+        let pkl = parse(&format!("{class}derived = (base) {{\n  port = 8080\n}}"));
+        assert_eq!(pkl.get_path("derived.port"), Some(PklValue::Int(8080)));
+
+        let source = format!("{class}derived = (base) {{\n  port = \"80\"\n}}");
+        let error = parse_err(&source);
+        assert_eq!(error.code(), Some("pkl_fast::type_mismatch"));
+        assert_eq!(
+            error.msg(),
+            "Invalid type for key 'port', expected 'Int' but found 'String'"
+        );
+        assert_eq!(error_slice(&source, &error), "\"80\"");
+
+        let error = parse_err(&format!("{class}derived = (base) {{\n  host = \"x\"\n}}"));
+        assert_eq!(error.msg(), "Unknown key 'host' in instance of Server");
+    }
+
+    #[test]
+    fn omitted_nullable_class_fields_default_to_null() {
+        let class = "class Server {\n  host: String\n  port: Int?\n}\n";
+
+        let pkl = parse(&format!("{class}s = new Server {{ host = \"x\" }}"));
+        assert_eq!(pkl.get_path("s.port"), Some(PklValue::Null));
+
+        let error = parse_err(&format!("{class}s = new Server {{ port = 1 }}"));
+        assert_eq!(error.msg(), "Missing key 'host' in instance of Server");
+    }
+
+    #[cfg(feature = "suggestions")]
+    #[test]
+    fn close_declaration_names_are_reported_with_a_suggestion() {
+        let source = "port = 1\npart = 2";
+        let error = parse_err(source);
+        assert_eq!(
+            error.msg(),
+            "Did you mean to write 'port' instead of 'part'?"
+        );
+        assert_eq!(error_slice(source, &error), "part");
+
+        let error = parse_err("class Server {}\nclass Servar {}");
+        assert_eq!(
+            error.msg(),
+            "Did you mean to write 'Server' instead of 'Servar'?"
+        );
+    }
+
+    #[cfg(not(feature = "suggestions"))]
+    #[test]
+    fn close_declaration_names_are_accepted_without_the_suggestions_feature() {
+        let pkl = parse("port = 1\npart = 2");
+        assert_eq!(pkl.get_value("part"), Some(PklValue::Int(2)));
+    }
+
+    #[test]
+    fn logical_not_negates_booleans_only() {
+        assert_eq!(eval("!true == false"), PklValue::Bool(true));
+        assert_eq!(eval("!(1 == 2) == true"), PklValue::Bool(true));
+        assert_eq!(eval("!false && true"), PklValue::Bool(true));
+
+        let source = "!1";
+        let error = eval_err(source);
+        assert_eq!(
+            error.msg(),
+            "Operator `!` cannot be applied to an operand of type Int"
+        );
+        assert_eq!(error_slice(source, &error), "1");
+
+        // `!!` is the non-null assertion, not a double negation
+        assert_eq!(eval("1!!"), PklValue::Int(1));
+    }
+
+    #[test]
+    fn member_names_are_listed_sorted() {
+        let mut pkl = Pkl::new();
+        pkl.register_module("base", "zeta = 1\nalpha = 2\nmid = 3");
+        if let Err(e) = pkl.parse("amends \"mem:base\"") {
+            panic!("{}", e.msg());
+        }
+
+        let amended = pkl.table.get_amended_values();
+        assert_eq!(amended, ["alpha", "mid", "zeta"]);
+        assert_eq!(pkl.table.get_amended_values(), amended);
+    }
+
+    #[cfg(feature = "suggestions")]
+    #[test]
+    fn values_and_schemas_are_listed_sorted() {
+        let pkl = parse(
+            "class Zebra {\n  a: Int\n}\nclass Ant {\n  a: Int\n}\nyellow = 1\nblue = 2\nred = 3",
+        );
+
+        assert_eq!(pkl.table.get_values(), ["blue", "red", "yellow"]);
+        assert_eq!(pkl.table.get_schemas(), ["Ant", "Zebra"]);
+        assert_eq!(pkl.table.get_values(), pkl.table.get_values());
+    }
+
+    #[test]
+    fn class_instance_fields_see_the_fields_declared_before_them() {
+        let class = "class P {\n  a: Int\n  b: Int\n}\n";
+
+        let pkl = parse(&format!("{class}p = new P {{\n  a = 1\n  b = a + 1\n}}\n"));
+        assert_eq!(pkl.get_path("p.b"), Some(PklValue::Int(2)));
+
+        let pkl = parse(&format!(
+            "{class}p = new P {{\n  a = 1\n  b = this.a + 2\n}}\n"
+        ));
+        assert_eq!(pkl.get_path("p.b"), Some(PklValue::Int(3)));
+
+        let source = format!("{class}p = new P {{\n  b = a + 1\n  a = 1\n}}\n");
+        let error = parse_err(&source);
+        assert_eq!(error.msg(), "unknown property `a`");
+        assert_eq!(error_slice(&source, &error), "a");
+    }
+
+    #[test]
+    fn escaped_names_are_decoded_once_parsed() {
+        let source = "open module m\n`a\\u{20}b`: Int\n`a b` = \"s\"";
+        let e = parse_err(source);
+        assert!(
+            e.msg()
+                .contains("does not correspond to the value of 'a b'"),
+            "{}",
+            e.msg()
+        );
+        assert_eq!(error_slice(source, &e), "`a b`");
+
+        let pkl = parse("open module m\n`a\\u{20}b`: Int\n`a b` = 1\nc = `a\\u{20}b` + 1");
+        assert_eq!(pkl.get_value("a b"), Some(PklValue::Int(1)));
+        assert_eq!(pkl.get_value("c"), Some(PklValue::Int(2)));
+
+        let pkl = parse("o {\n  `x\\u{79}` = 1\n}\nclass C {\n  `f\\u{6f}o`: Int\n}\nc = new C { foo = 2 }\nd = o.xy + c.`f\\u{6f}o`");
+        assert_eq!(pkl.get_value("d"), Some(PklValue::Int(3)));
+    }
+}
@@ -6,4 +6,6 @@ pub mod duration;
 pub mod float_api;
 pub mod int_api;
 pub mod list_api;
+pub mod listing_api;
+pub mod set_api;
 pub mod string_api;
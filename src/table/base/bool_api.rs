@@ -1,4 +1,4 @@
-use crate::{generate_method, PklResult, PklValue};
+use crate::{generate_method, PklError, PklResult, PklValue};
 use std::ops::Range;
 
 /// Based on v0.26.0
@@ -45,10 +45,10 @@ pub fn match_bool_methods_api(
                 range
             )
         }
-        _ => Err((
+        _ => Err(PklError::UnknownMember(
             format!("Boolean does not possess {} method", fn_name),
             range,
-        )
-            .into()),
+            None,
+        )),
     }
 }
@@ -2,7 +2,7 @@
 //     "b", "kb", "mb", "gb", "tb", "pb", "kib", "mib", "gib", "tib", "pib",
 // ];
 
-use crate::{generate_method, PklResult, PklValue};
+use crate::{generate_method, PklError, PklResult, PklValue};
 use std::fmt;
 use std::ops::Range;
 
@@ -33,11 +33,11 @@ pub fn match_data_size_props_api(
             }))
         }
         _ => {
-            return Err((
+            return Err(PklError::UnknownMember(
                 format!("DataSize does not possess {} property", property),
                 range,
-            )
-                .into())
+                None,
+            ))
         }
     }
 }
@@ -99,11 +99,11 @@ pub fn match_data_size_methods_api(
             )
         }
         _ => {
-            return Err((
+            return Err(PklError::UnknownMember(
                 format!("DataSize does not possess {} method", property),
                 range,
-            )
-                .into())
+                None,
+            ))
         }
     }
 }
@@ -206,8 +206,9 @@ impl Byte {
     /// # Returns
     /// Returns a new `Byte` representing the size in bytes.
     pub fn from_float_and_unit(value: f64, unit: Unit) -> Self {
-        let is_negative = value.is_sign_negative();
         let bytes = calculate_bytes(value, unit);
+        // a zero size is never negative, whatever the sign of `-0.0`
+        let is_negative = value.is_sign_negative() && bytes != 0;
         Byte {
             bytes,
             initial_value: Box::new(PklValue::Float(value)),
@@ -226,8 +227,8 @@ impl Byte {
     /// # Returns
     /// Returns a new `Byte` representing the size in bytes.
     pub fn from_int_and_unit(value: i64, unit: Unit) -> Self {
-        let is_negative = value.is_negative();
         let bytes = calculate_bytes(value as f64, unit);
+        let is_negative = value.is_negative() && bytes != 0;
         Byte {
             bytes,
             initial_value: Box::new(PklValue::Int(value)),
@@ -237,50 +238,79 @@ impl Byte {
         }
     }
 
+    /// Returns the value of the data size, expressed in its current unit.
+    pub fn value(&self) -> &PklValue {
+        &self.initial_value
+    }
+
+    /// Converts the data size to the given unit,
+    /// the value is recomputed so that the number of bytes is preserved.
     pub fn to_unit(&mut self, unit: Unit) -> &mut Self {
+        let factor = unit_factor(unit);
+        let value = if self.bytes % factor == 0 {
+            PklValue::Int(self.bytes / factor)
+        } else {
+            PklValue::Float(self.bytes as f64 / factor as f64)
+        };
+
+        *self.initial_value = value;
         self.unit = unit;
         self
     }
+
+    /// Returns the data size with its sign flipped, expressed in the same unit.
+    pub fn negated(&self) -> Self {
+        match self.value() {
+            PklValue::Int(i) => match i.checked_neg() {
+                Some(i) => Self::from_int_and_unit(i, self.unit),
+                None => Self::from_float_and_unit(-(*i as f64), self.unit),
+            },
+            PklValue::Float(f) => Self::from_float_and_unit(-f, self.unit),
+            _ => unreachable!("a data size is created from a number"),
+        }
+    }
+
     pub fn to_binary_unit(&mut self) -> &mut Self {
         match self.unit {
-            Unit::KB => self.unit = Unit::KiB,
-            Unit::MB => self.unit = Unit::MiB,
-            Unit::GB => self.unit = Unit::GiB,
-            Unit::TB => self.unit = Unit::TiB,
-            Unit::PB => self.unit = Unit::PiB,
-            _ => (),
+            Unit::KB => self.to_unit(Unit::KiB),
+            Unit::MB => self.to_unit(Unit::MiB),
+            Unit::GB => self.to_unit(Unit::GiB),
+            Unit::TB => self.to_unit(Unit::TiB),
+            Unit::PB => self.to_unit(Unit::PiB),
+            _ => self,
         }
-        self
     }
     pub fn to_decimal_unit(&mut self) -> &mut Self {
         match self.unit {
-            Unit::KiB => self.unit = Unit::KB,
-            Unit::MiB => self.unit = Unit::MB,
-            Unit::GiB => self.unit = Unit::GB,
-            Unit::TiB => self.unit = Unit::TB,
-            Unit::PiB => self.unit = Unit::PB,
-            _ => (),
+            Unit::KiB => self.to_unit(Unit::KB),
+            Unit::MiB => self.to_unit(Unit::MB),
+            Unit::GiB => self.to_unit(Unit::GB),
+            Unit::TiB => self.to_unit(Unit::TB),
+            Unit::PiB => self.to_unit(Unit::PB),
+            _ => self,
         }
-        self
     }
 }
 
-fn calculate_bytes(value: f64, unit: Unit) -> i64 {
-    let bytes = match unit {
-        Unit::B => value,
-        Unit::KB => value * 1_000.0,
-        Unit::MB => value * 1_000_000.0,
-        Unit::GB => value * 1_000_000_000.0,
-        Unit::TB => value * 1_000_000_000_000.0,
-        Unit::PB => value * 1_000_000_000_000_000.0,
-        Unit::KiB => value * 1_024.0,
-        Unit::MiB => value * 1_024.0 * 1_024.0,
-        Unit::GiB => value * 1_024.0 * 1_024.0 * 1_024.0,
-        Unit::TiB => value * 1_024.0 * 1_024.0 * 1_024.0 * 1_024.0,
-        Unit::PiB => value * 1_024.0 * 1_024.0 * 1_024.0 * 1_024.0 * 1_024.0,
-    };
+/// The number of bytes in one `unit`.
+fn unit_factor(unit: Unit) -> i64 {
+    match unit {
+        Unit::B => 1,
+        Unit::KB => 1_000,
+        Unit::MB => 1_000_000,
+        Unit::GB => 1_000_000_000,
+        Unit::TB => 1_000_000_000_000,
+        Unit::PB => 1_000_000_000_000_000,
+        Unit::KiB => 1 << 10,
+        Unit::MiB => 1 << 20,
+        Unit::GiB => 1 << 30,
+        Unit::TiB => 1 << 40,
+        Unit::PiB => 1 << 50,
+    }
+}
 
-    bytes as i64
+fn calculate_bytes(value: f64, unit: Unit) -> i64 {
+    (value * unit_factor(unit) as f64) as i64
 }
 
 impl fmt::Display for Unit {
@@ -301,3 +331,41 @@ impl fmt::Display for Unit {
         write!(f, "{}", unit_str)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_utils::eval, PklValue};
+
+    #[test]
+    fn zero_data_sizes_are_positive_whatever_the_sign_of_zero() {
+        assert_eq!(eval("(-0.0).mb.isPositive"), PklValue::Bool(true));
+        assert_eq!(eval("(-(0.mb)).isPositive"), PklValue::Bool(true));
+        assert_eq!(eval("(-1.0).mb.isPositive"), PklValue::Bool(false));
+    }
+
+    #[test]
+    fn unit_conversions_preserve_the_byte_count() {
+        assert_eq!(
+            eval("1024.kib.toDecimalUnit() == 1024.kib"),
+            PklValue::Bool(true)
+        );
+        assert_eq!(
+            eval("1024.kib.toDecimalUnit().unit"),
+            PklValue::String("kb".to_owned())
+        );
+        assert_eq!(
+            eval("1024.kib.toDecimalUnit().value"),
+            PklValue::Float(1048.576)
+        );
+
+        assert_eq!(
+            eval("1000.kb.toBinaryUnit().unit"),
+            PklValue::String("kib".to_owned())
+        );
+        assert_eq!(
+            eval("1000.kb.toBinaryUnit().value"),
+            PklValue::Float(976.5625)
+        );
+        assert_eq!(eval("1000.kb.toDecimalUnit().value"), PklValue::Int(1000));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{generate_method, PklResult, PklValue};
+use crate::{generate_method, PklError, PklResult, PklValue};
 use std::fmt;
 use std::{ops::Range, time::Duration as StdDuration};
 
@@ -20,11 +20,11 @@ pub fn match_duration_props_api(
         "isPositive" => return Ok(PklValue::Bool(!duration.is_negative)),
         "isoString" => return Ok(PklValue::String(duration.to_iso_string())),
         _ => {
-            return Err((
+            return Err(PklError::UnknownMember(
                 format!("Duration does not possess {} property", property),
                 range,
-            )
-                .into())
+                None,
+            ))
         }
     }
 }
@@ -64,11 +64,11 @@ pub fn match_duration_methods_api(
             )
         }
         _ => {
-            return Err((
+            return Err(PklError::UnknownMember(
                 format!("Duration does not possess {} method", property),
                 range,
-            )
-                .into())
+                None,
+            ))
         }
     }
 }
@@ -108,7 +108,6 @@ pub struct Duration {
     pub unit: Unit,
     pub is_negative: bool,
     initial_value: Box<PklValue>,
-    #[allow(dead_code)]
     initial_unit: Unit,
 }
 
@@ -157,22 +156,15 @@ impl Duration {
         let is_negative = value.is_sign_negative();
         let value = if is_negative { value.abs() } else { value };
 
-        let duration = match unit {
-            Unit::NS => StdDuration::from_secs_f64(value * 10e-9),
-            Unit::US => StdDuration::from_secs_f64(value * 10e-6),
-            Unit::MS => StdDuration::from_secs_f64(value * 10e-3),
-            Unit::S => StdDuration::from_secs_f64(value),
-            Unit::MIN => StdDuration::from_secs_f64(value * 60.0),
-            Unit::H => StdDuration::from_secs_f64(value * 60.0 * 60.0),
-            Unit::D => StdDuration::from_secs_f64(value * 60.0 * 60.0 * 24.0),
-        };
+        let duration = StdDuration::from_secs_f64(value * unit_nanos(unit) as f64 / 1e9);
 
         Self {
             duration,
             unit,
             initial_unit: unit,
             initial_value,
-            is_negative,
+            // a zero duration is never negative, whatever the sign of `-0.0`
+            is_negative: is_negative && !duration.is_zero(),
         }
     }
 
@@ -222,29 +214,70 @@ impl Duration {
             value as f64
         };
 
-        let duration = match unit {
-            Unit::NS => StdDuration::from_secs_f64(value * 10e-9),
-            Unit::US => StdDuration::from_secs_f64(value * 10e-6),
-            Unit::MS => StdDuration::from_secs_f64(value * 10e-3),
-            Unit::S => StdDuration::from_secs_f64(value),
-            Unit::MIN => StdDuration::from_secs_f64(value * 60.0),
-            Unit::H => StdDuration::from_secs_f64(value * 60.0 * 60.0),
-            Unit::D => StdDuration::from_secs_f64(value * 60.0 * 60.0 * 24.0),
-        };
+        let duration = StdDuration::from_secs_f64(value * unit_nanos(unit) as f64 / 1e9);
 
         Self {
             duration,
             unit,
             initial_unit: unit,
             initial_value,
-            is_negative,
+            is_negative: is_negative && !duration.is_zero(),
         }
     }
 
+    /// Returns the value of the duration, expressed in its current unit.
+    pub fn value(&self) -> &PklValue {
+        &self.initial_value
+    }
+
+    /// Converts the duration to the given unit,
+    /// the value is recomputed so that the length of the duration is preserved.
     pub fn to_unit(&mut self, unit: Unit) -> &mut Self {
+        let (from, to) = (unit_nanos(self.initial_unit), unit_nanos(unit));
+        let value = match *self.initial_value {
+            PklValue::Int(i) => {
+                let nanos = i as i128 * from;
+                match i64::try_from(nanos / to) {
+                    Ok(value) if nanos % to == 0 => PklValue::Int(value),
+                    _ => PklValue::Float(nanos as f64 / to as f64),
+                }
+            }
+            PklValue::Float(f) => PklValue::Float(f * from as f64 / to as f64),
+            _ => unreachable!("a duration is created from a number"),
+        };
+
+        *self.initial_value = value;
+        self.initial_unit = unit;
         self.unit = unit;
         self
     }
+
+    /// Returns the duration with its sign flipped, expressed in the same unit.
+    pub fn negated(&self) -> Self {
+        let mut negated = match self.value() {
+            PklValue::Int(i) => match i.checked_neg() {
+                Some(i) => Self::from_int_and_unit(i, self.initial_unit),
+                None => Self::from_float_and_unit(-(*i as f64), self.initial_unit),
+            },
+            PklValue::Float(f) => Self::from_float_and_unit(-f, self.initial_unit),
+            _ => unreachable!("a duration is created from a number"),
+        };
+        negated.unit = self.unit;
+        negated
+    }
+}
+
+/// Returns the number of nanoseconds in one `unit`.
+fn unit_nanos(unit: Unit) -> i128 {
+    match unit {
+        Unit::NS => 1,
+        Unit::US => 1_000,
+        Unit::MS => 1_000_000,
+        Unit::S => 1_000_000_000,
+        Unit::MIN => 60_000_000_000,
+        Unit::H => 3_600_000_000_000,
+        Unit::D => 86_400_000_000_000,
+    }
 }
 
 impl fmt::Display for Unit {
@@ -261,3 +294,32 @@ impl fmt::Display for Unit {
         write!(f, "{}", unit_str)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{eval, parse},
+        PklValue,
+    };
+
+    #[test]
+    fn zero_durations_are_positive_whatever_the_sign_of_zero() {
+        assert_eq!(eval("(-0.0).s.isPositive"), PklValue::Bool(true));
+        assert_eq!(eval("(-(0.s)).isPositive"), PklValue::Bool(true));
+        assert_eq!(eval("(-1.0).s.isPositive"), PklValue::Bool(false));
+    }
+
+    #[test]
+    fn converted_durations_keep_their_length() {
+        assert_eq!(eval("5.min.toUnit(\"s\").value"), PklValue::Int(300));
+        assert_eq!(eval("90.s.toUnit(\"min\").value"), PklValue::Float(1.5));
+        assert_eq!(eval("1500.ms.toUnit(\"s\") == 1.5.s"), PklValue::Bool(true));
+        assert_eq!(eval("-(2.h.toUnit(\"min\")).value"), PklValue::Int(-120));
+    }
+
+    #[test]
+    fn converted_durations_render_in_their_new_unit() {
+        let pkl = parse("x = 5.min.toUnit(\"s\")");
+        assert_eq!(pkl.to_string(), "x = 300.s\n");
+    }
+}
@@ -2,7 +2,9 @@ use super::{
     data_size,
     duration::{self, Duration},
 };
-use crate::{generate_method, values::Byte, PklResult, PklValue};
+use crate::{
+    generate_method, table::value::render::write_float, values::Byte, PklError, PklResult, PklValue,
+};
 use std::ops::Range;
 
 /// Based on v0.26.0
@@ -12,12 +14,20 @@ pub fn match_float_props_api(
     range: Range<usize>,
 ) -> PklResult<PklValue> {
     if let Some(unit) = duration::Unit::from_str(property) {
+        if !float.is_finite() {
+            return Err((non_finite_conversion_msg(float, "Duration"), range).into());
+        }
+
         return Ok(PklValue::Duration(Duration::from_float_and_unit(
             float, unit,
         )));
     }
 
     if let Some(unit) = data_size::Unit::from_str(property) {
+        if !float.is_finite() {
+            return Err((non_finite_conversion_msg(float, "DataSize"), range).into());
+        }
+
         return Ok(PklValue::DataSize(Byte::from_float_and_unit(float, unit)));
     }
 
@@ -53,11 +63,11 @@ pub fn match_float_props_api(
                 .into())
         }
         _ => {
-            return Err((
+            return Err(PklError::UnknownMember(
                 format!("Float does not possess {} property", property),
                 range,
-            )
-                .into())
+                None,
+            ))
         }
     }
 }
@@ -73,7 +83,11 @@ pub fn match_float_methods_api(
         "toString" => {
             generate_method!(
                 "toString", &args;
-                Ok(float.to_string().into());
+                {
+                    let mut output = String::new();
+                    write_float(&mut output, float);
+                    Ok(output.into())
+                };
                 range
             )
         }
@@ -133,6 +147,10 @@ pub fn match_float_methods_api(
                 0: String;
                 |duration_unit: String|
                     {
+                        if !float.is_finite() {
+                            return Err((non_finite_conversion_msg(float, "Duration"), range))
+                        }
+
                         if let Some(unit) = duration::Unit::from_str(&duration_unit) {
                             return Ok(Duration::from_float_and_unit(float, unit).into())
                         }
@@ -149,6 +167,10 @@ pub fn match_float_methods_api(
                 0: String;
                 |datasize_unit: String|
                     {
+                        if !float.is_finite() {
+                            return Err((non_finite_conversion_msg(float, "DataSize"), range))
+                        }
+
                         if let Some(unit) = data_size::Unit::from_str(&datasize_unit) {
                             return Ok(Byte::from_float_and_unit(float, unit).into())
                         }
@@ -159,6 +181,7 @@ pub fn match_float_methods_api(
                 range
             )
         }
+        // both bounds are inclusive
         "isBetween" => {
             generate_method!(
                 "isBetween", &args;
@@ -171,15 +194,96 @@ pub fn match_float_methods_api(
                 range
             )
         }
+        // both bounds are exclusive
+        "isBetweenExclusive" => {
+            generate_method!(
+                "isBetweenExclusive", &args;
+                Numbers: 2;
+                |[start, exclusive_end]: [f64; 2]|
+                    {
+                        Ok((float > start && float < exclusive_end).into())
+                    }
+                ;
+                range
+            )
+        }
         _ => {
-            return Err((
-                format!(
-                    "String does not possess {} method (or method not yet implemented)",
-                    fn_name
-                ),
+            return Err(PklError::UnknownMember(
+                format!("Float does not possess {} method", fn_name),
                 range,
-            )
-                .into())
+                None,
+            ))
         }
     }
 }
+
+fn non_finite_conversion_msg(float: f64, type_name: &str) -> String {
+    format!(
+        "Cannot convert {} to {}, float must be finite",
+        float, type_name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{eval, eval_err},
+        PklValue,
+    };
+
+    #[test]
+    fn non_finite_floats_cannot_be_converted() {
+        for expr in [
+            r#"Infinity.toDataSize("mb")"#,
+            r#"NaN.toDuration("s")"#,
+            "Infinity.ms",
+            "NaN.gb",
+        ] {
+            assert!(
+                eval_err(expr).msg().contains("float must be finite"),
+                "{expr}"
+            );
+        }
+    }
+
+    #[test]
+    fn finite_negative_floats_can_be_converted() {
+        assert!(matches!(
+            eval(r#"(-1.5).toDuration("s")"#),
+            PklValue::Duration(duration) if duration.is_negative
+        ));
+        assert!(matches!(
+            eval("(-1.5).mb"),
+            PklValue::DataSize(size) if size.is_negative
+        ));
+    }
+
+    #[test]
+    fn is_between_is_inclusive_and_its_exclusive_variant_is_not() {
+        assert_eq!(eval("5.0.isBetween(0, 5)"), PklValue::Bool(true));
+        assert_eq!(eval("5.0.isBetweenExclusive(0, 5)"), PklValue::Bool(false));
+        assert_eq!(eval("0.5.isBetweenExclusive(0, 1.5)"), PklValue::Bool(true));
+
+        // the last argument is checked as well
+        assert_eq!(
+            eval_err(r#"0.5.isBetweenExclusive(0, "1")"#).msg(),
+            "isBetweenExclusive method expects argument at index 1 to be of type Number, but found String"
+        );
+    }
+
+    #[test]
+    fn to_string_renders_floats_like_pkl() {
+        let rendered = |expr: &str| eval(&format!("({expr}).toString()"));
+
+        assert_eq!(rendered("2.0"), PklValue::String("2.0".to_owned()));
+        assert_eq!(rendered("2.50"), PklValue::String("2.5".to_owned()));
+        assert_eq!(rendered("1.0e20"), PklValue::String("1.0E20".to_owned()));
+        assert_eq!(rendered("0.0001"), PklValue::String("1.0E-4".to_owned()));
+        assert_eq!(rendered("1.0 / 0"), PklValue::String("Infinity".to_owned()));
+        assert_eq!(
+            rendered("-1.0 / 0"),
+            PklValue::String("-Infinity".to_owned())
+        );
+        assert_eq!(rendered("0.0 / 0"), PklValue::String("NaN".to_owned()));
+    }
+}
@@ -2,7 +2,7 @@ use super::{
     data_size,
     duration::{self, Duration},
 };
-use crate::{generate_method, values::Byte, PklResult, PklValue};
+use crate::{generate_method, values::Byte, PklError, PklResult, PklValue};
 use std::ops::Range;
 
 /// Based on v0.26.0
@@ -31,14 +31,21 @@ pub fn match_int_props_api(int: i64, property: &str, range: Range<usize>) -> Pkl
         }
         "ceil" => return Ok(PklValue::Int(int)),
         "floor" => return Ok(PklValue::Int(int)),
-        "isPositive" => return Ok(PklValue::Bool(int.is_positive())),
+        // zero is positive, like for Float, Duration and DataSize
+        "isPositive" => return Ok(PklValue::Bool(int >= 0)),
         "isFinite" => return Ok(PklValue::Bool(true)),
         "isNaN" => return Ok(PklValue::Bool(false)),
         "isNonZero" => return Ok(PklValue::Bool(int != 0)),
         "inv" => return Ok(PklValue::Int(!int)),
         "isEven" => return Ok(PklValue::Bool(int % 2 == 0).into()),
         "isOdd" => return Ok(PklValue::Bool(int % 2 == 1)),
-        _ => return Err((format!("Int does not possess {} property", property), range).into()),
+        _ => {
+            return Err(PklError::UnknownMember(
+                format!("Int does not possess {} property", property),
+                range,
+                None,
+            ))
+        }
     }
 }
 
@@ -57,6 +64,14 @@ pub fn match_int_methods_api(
                 range
             )
         }
+        "formatNumber" => {
+            generate_method!(
+                "formatNumber", &args;
+                0: String;
+                |separator: String| Ok(group_digits(int, &separator).into());
+                range
+            )
+        }
         "round" => {
             generate_method!(
                 "round", &args;
@@ -125,6 +140,7 @@ pub fn match_int_methods_api(
                 range
             )
         }
+        // both bounds are inclusive
         "isBetween" => {
             generate_method!(
                 "isBetween", &args;
@@ -137,6 +153,58 @@ pub fn match_int_methods_api(
                 range
             )
         }
+        // both bounds are exclusive
+        "isBetweenExclusive" => {
+            generate_method!(
+                "isBetweenExclusive", &args;
+                Numbers: 2;
+                |[start, exclusive_end]: [f64; 2]|
+                    {
+                        Ok(((int as f64) > start && (int as f64) < exclusive_end).into())
+                    }
+                ;
+                range
+            )
+        }
+        "pow" => {
+            generate_method!(
+                "pow", &args;
+                0: Int;
+                |exponent: i64|
+                    {
+                        if exponent < 0 {
+                            return Err((format!("Method 'pow' expects a non-negative exponent, here it is '{}'", exponent), range))
+                        }
+                        // these bases never overflow, whatever the exponent
+                        match int {
+                            0 if exponent > 0 => return Ok(PklValue::Int(0)),
+                            1 => return Ok(PklValue::Int(1)),
+                            -1 => return Ok(PklValue::Int(if exponent % 2 == 0 { 1 } else { -1 })),
+                            _ => {}
+                        }
+
+                        u32::try_from(exponent)
+                            .ok()
+                            .and_then(|exponent| int.checked_pow(exponent))
+                            .map(PklValue::Int)
+                            .ok_or_else(|| (format!("Int overflow when computing {}.pow({})", int, exponent), range))
+                    }
+                ;
+                range
+            )
+        }
+        "factorial" => {
+            // 20! is the largest factorial that fits in an Int
+            generate_method!(
+                "factorial", &args;
+                match int {
+                    i64::MIN..=-1 => Err((format!("Cannot compute the factorial of a negative number, here it is '{}'", int), range).into()),
+                    0..=20 => Ok(PklValue::Int((1..=int).product())),
+                    _ => Err((format!("Int overflow when computing {}.factorial()", int), range).into()),
+                };
+                range
+            )
+        }
         "toRadixString" => {
             generate_method!(
                 "toRadixString", &args;
@@ -240,14 +308,104 @@ pub fn match_int_methods_api(
             )
         }
         _ => {
-            return Err((
-                format!(
-                    "String does not possess {} method (or method not yet implemented)",
-                    fn_name
-                ),
+            return Err(PklError::UnknownMember(
+                format!("Int does not possess {} method", fn_name),
                 range,
-            )
-                .into())
+                None,
+            ))
+        }
+    }
+}
+
+/// Formats an integer with `separator` inserted every three digits,
+/// such as `-1,000,000` for `-1000000` and `","`.
+fn group_digits(int: i64, separator: &str) -> String {
+    let digits = int.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * separator.len() + 1);
+
+    if int < 0 {
+        grouped.push('-');
+    }
+    for (i, digit) in digits.chars().enumerate() {
+        if i != 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{eval, eval_err},
+        PklValue,
+    };
+
+    #[test]
+    fn zero_is_positive_for_every_numeric_type() {
+        for zero in ["0", "0.0", "0.b", "0.s"] {
+            assert_eq!(
+                eval(&format!("{zero}.isPositive")),
+                PklValue::Bool(true),
+                "{zero}"
+            );
         }
+        assert_eq!(eval("(-1).isPositive"), PklValue::Bool(false));
+    }
+
+    #[test]
+    fn format_number_groups_digits_by_three() {
+        let formatted = |expr: &str| match eval(expr) {
+            PklValue::String(s) => s,
+            other => panic!("expected a String, got {other:?}"),
+        };
+
+        assert_eq!(formatted("1000000.formatNumber(\",\")"), "1,000,000");
+        assert_eq!(formatted("(-1234567).formatNumber(\" \")"), "-1 234 567");
+        assert_eq!(formatted("999.formatNumber(\",\")"), "999");
+        assert_eq!(formatted("0.formatNumber(\",\")"), "0");
+        assert_eq!(
+            formatted("(-9223372036854775808).formatNumber(\"_\")"),
+            "-9_223_372_036_854_775_808"
+        );
+    }
+
+    #[test]
+    fn is_between_is_inclusive_and_its_exclusive_variant_is_not() {
+        assert_eq!(eval("5.isBetween(0, 5)"), PklValue::Bool(true));
+        assert_eq!(eval("0.isBetween(0, 5)"), PklValue::Bool(true));
+        assert_eq!(eval("5.isBetweenExclusive(0, 5)"), PklValue::Bool(false));
+        assert_eq!(eval("0.isBetweenExclusive(0, 5)"), PklValue::Bool(false));
+        assert_eq!(eval("3.isBetweenExclusive(0, 5)"), PklValue::Bool(true));
+        assert_eq!(eval("3.isBetweenExclusive(0, 5.5)"), PklValue::Bool(true));
+    }
+
+    #[test]
+    fn pow_and_factorial_check_for_overflows() {
+        assert_eq!(eval("2.pow(10)"), PklValue::Int(1024));
+        assert_eq!(eval("(-1).pow(2)"), PklValue::Int(1));
+        assert_eq!(eval("0.pow(0)"), PklValue::Int(1));
+        assert_eq!(eval("0.pow(4294967296)"), PklValue::Int(0));
+        assert_eq!(eval("1.pow(4294967296)"), PklValue::Int(1));
+        assert_eq!(eval("(-1).pow(4294967296)"), PklValue::Int(1));
+        assert_eq!(eval("(-1).pow(4294967297)"), PklValue::Int(-1));
+        assert_eq!(
+            eval("20.factorial()"),
+            PklValue::Int(2_432_902_008_176_640_000)
+        );
+
+        assert_eq!(
+            eval_err("21.factorial()").msg(),
+            "Int overflow when computing 21.factorial()"
+        );
+        assert_eq!(
+            eval_err("2.pow(63)").msg(),
+            "Int overflow when computing 2.pow(63)"
+        );
+        assert!(eval_err("2.pow(-1)")
+            .msg()
+            .contains("expects a non-negative exponent"));
     }
 }
@@ -1,5 +1,6 @@
-use crate::{PklResult, PklValue};
-use std::ops::Range;
+use super::set_api::distinct;
+use crate::{generate_method, PklError, PklResult, PklValue};
+use std::{cmp::Ordering, ops::Range};
 
 /// Based on v0.26.0
 pub fn match_list_props_api(
@@ -23,45 +24,39 @@ pub fn match_list_props_api(
                     .into());
             }
 
-            return Ok(list.remove(0));
+            return Ok(list.swap_remove(0));
         }
         "firstOrNull" => {
-            if list.is_empty() {
-                return Ok(PklValue::Null);
-            }
-
-            return Ok(list.remove(0));
+            return Ok(list.into_iter().next().unwrap_or(PklValue::Null));
         }
         "rest" => {
             if list.is_empty() {
                 return Err(("Cannot get the rest of an empty list!".to_owned(), range).into());
             }
 
-            return Ok(PklValue::List(list.split_at(1).1.to_vec()));
+            list.remove(0);
+            return Ok(PklValue::List(list));
         }
         "restOrNull" => {
             if list.is_empty() || list.len() == 1 {
                 return Ok(PklValue::Null);
             }
 
-            return Ok(PklValue::List(list.split_at(1).1.to_vec()));
+            list.remove(0);
+            return Ok(PklValue::List(list));
         }
         "last" => {
             if list.is_empty() {
                 return Err(("Cannot get last element of empty list!".to_owned(), range).into());
             }
 
-            return Ok(list.last().unwrap().to_owned());
+            return Ok(list.pop().unwrap());
         }
         "lastOrNull" => {
-            if list.is_empty() {
-                return Ok(PklValue::Null);
-            }
-
-            return Ok(list.remove(list.len() - 1));
+            return Ok(list.pop().unwrap_or(PklValue::Null));
         }
         "single" => {
-            if list.is_empty() || list.len() != 1 {
+            if list.len() != 1 {
                 return Err((
                     "Cannot get single element of a list with length != 1!".to_owned(),
                     range,
@@ -69,14 +64,14 @@ pub fn match_list_props_api(
                     .into());
             }
 
-            return Ok(list.remove(0));
+            return Ok(list.pop().unwrap());
         }
         "singleOrNull" => {
-            if list.is_empty() || list.len() != 1 {
+            if list.len() != 1 {
                 return Ok(PklValue::Null);
             }
 
-            return Ok(list.remove(0));
+            return Ok(list.pop().unwrap());
         }
 
         "lastIndex" => {
@@ -87,28 +82,582 @@ pub fn match_list_props_api(
             return Ok(PklValue::Int((list.len() - 1) as i64));
         }
 
-        "min" => return Err((format!("min property is not yet implemented"), range).into()),
+        "min" => {
+            return Err(PklError::unsupported(
+                "min property is not yet implemented".to_owned(),
+                range,
+            ))
+        }
         "minOrNull" => {
-            return Err((format!("minOrNull property is not yet implemented"), range).into())
+            return Err(PklError::unsupported(
+                "minOrNull property is not yet implemented".to_owned(),
+                range,
+            ))
+        }
+        "max" => {
+            return Err(PklError::unsupported(
+                "max property is not yet implemented".to_owned(),
+                range,
+            ))
         }
-        "max" => return Err((format!("max property is not yet implemented"), range).into()),
         "maxOrNull" => {
-            return Err((format!("maxOrNull property is not yet implemented"), range).into())
+            return Err(PklError::unsupported(
+                "maxOrNull property is not yet implemented".to_owned(),
+                range,
+            ))
         }
 
         "isDistinct" => {
-            return Err((format!("isDistinct property is not yet implemented"), range).into())
+            return Err(PklError::unsupported(
+                "isDistinct property is not yet implemented".to_owned(),
+                range,
+            ))
         }
         "distinct" => {
-            return Err((format!("distinct property is not yet implemented"), range).into())
+            return Err(PklError::unsupported(
+                "distinct property is not yet implemented".to_owned(),
+                range,
+            ))
         }
 
         _ => {
-            return Err((
+            return Err(PklError::UnknownMember(
                 format!("List does not possess {} property", property),
                 range,
+                None,
+            ))
+        }
+    }
+}
+
+/// Based on v0.26.0
+pub fn match_list_methods_api(
+    mut list: Vec<PklValue>,
+    fn_name: &str,
+    args: Vec<PklValue>,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match fn_name {
+        "sortBy" => {
+            generate_method!(
+                "sortBy", &args;
+                0: String;
+                |field_name: String| sort_by_field(list, &field_name, range);
+                range
             )
-                .into())
         }
+        "add" => {
+            // the element can be of any type, thus `generate_method` cannot be used
+            if args.len() != 1 {
+                return Err((
+                    "Method 'add' expects exactly 1 argument(s)".to_owned(),
+                    range,
+                )
+                    .into());
+            }
+
+            list.extend(args);
+            Ok(PklValue::List(list))
+        }
+        "plus" => {
+            generate_method!(
+                "plus", &args;
+                0: List;
+                |other: Vec<PklValue>| {
+                    list.extend(other);
+                    Ok(PklValue::List(list))
+                };
+                range
+            )
+        }
+        "withIndex" => {
+            // pairs are not supported yet, each element is paired with its index in a 2-element list
+            generate_method!(
+                "withIndex", &args;
+                Ok(PklValue::List(
+                    list.into_iter()
+                        .enumerate()
+                        .map(|(index, element)| PklValue::List(vec![PklValue::Int(index as i64), element]))
+                        .collect(),
+                ));
+                range
+            )
+        }
+        "chunked" => {
+            generate_method!(
+                "chunked", &args;
+                0: Int;
+                |size: i64| {
+                    let size = expect_positive_size("chunked", size, range)?;
+                    // the last chunk holds the remaining elements, it may be shorter
+                    Ok(PklValue::List(
+                        list.chunks(size).map(|chunk| PklValue::List(chunk.to_vec())).collect(),
+                    ))
+                };
+                range
+            )
+        }
+        "windowed" => {
+            generate_method!(
+                "windowed", &args;
+                0: Int;
+                |size: i64| {
+                    let size = expect_positive_size("windowed", size, range)?;
+                    // a list shorter than the size has no window
+                    Ok(PklValue::List(
+                        list.windows(size).map(|window| PklValue::List(window.to_vec())).collect(),
+                    ))
+                };
+                range
+            )
+        }
+        "toSet" => {
+            generate_method!(
+                "toSet", &args;
+                Ok(PklValue::Set(distinct(list)));
+                range
+            )
+        }
+        "sum" => {
+            generate_method!(
+                "sum", &args;
+                reduce_numbers(&list, "sum", 0, i64::checked_add, |a, b| a + b, range);
+                range
+            )
+        }
+        "product" => {
+            generate_method!(
+                "product", &args;
+                reduce_numbers(&list, "product", 1, i64::checked_mul, |a, b| a * b, range);
+                range
+            )
+        }
+        "average" => {
+            generate_method!(
+                "average", &args;
+                {
+                    if list.is_empty() {
+                        return Err((
+                            "Cannot compute the average of an empty list!".to_owned(),
+                            range,
+                        )
+                            .into());
+                    }
+                    expect_numbers(&list, "average", range)?;
+
+                    let sum: f64 = list.iter().filter_map(PklValue::as_number).sum();
+                    Ok(PklValue::Float(sum / list.len() as f64))
+                };
+                range
+            )
+        }
+        _ => {
+            return Err(PklError::UnknownMember(
+                format!("List does not possess {} method", fn_name),
+                range,
+                None,
+            ))
+        }
+    }
+}
+
+/// Based on v0.26.0, the methods taking a function,
+/// `f` calling it with the given arguments.
+pub fn match_list_lambda_methods_api(
+    list: Vec<PklValue>,
+    fn_name: &str,
+    f: &mut dyn FnMut(Vec<PklValue>) -> PklResult<PklValue>,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match fn_name {
+        "partition" => {
+            let mut matching = Vec::new();
+            let mut non_matching = Vec::new();
+
+            for element in list {
+                match f(vec![element.to_owned()])? {
+                    PklValue::Bool(true) => matching.push(element),
+                    PklValue::Bool(false) => non_matching.push(element),
+                    other => {
+                        return Err(PklError::type_mismatch(
+                            format!(
+                                "The predicate of 'partition' must return a Boolean, found {}",
+                                other.describe_type()
+                            ),
+                            range,
+                        ))
+                    }
+                }
+            }
+
+            // pairs are not supported yet, the halves are returned in a 2-element list
+            Ok(PklValue::List(vec![
+                PklValue::List(matching),
+                PklValue::List(non_matching),
+            ]))
+        }
+        _ => Err(PklError::UnknownMember(
+            format!("List does not possess {} method taking a function", fn_name),
+            range,
+            None,
+        )),
+    }
+}
+
+/// Checks that the size of chunks or windows is strictly positive.
+fn expect_positive_size(
+    fn_name: &str,
+    size: i64,
+    range: Range<usize>,
+) -> Result<usize, (String, Range<usize>)> {
+    match usize::try_from(size) {
+        Ok(size) if size > 0 => Ok(size),
+        _ => Err((
+            format!(
+                "Method '{}' expects a positive size, here it is '{}'",
+                fn_name, size
+            ),
+            range,
+        )),
+    }
+}
+
+/// Checks that every element of the list is a number, as expected by numeric reducers.
+fn expect_numbers(list: &[PklValue], fn_name: &str, range: Range<usize>) -> PklResult<()> {
+    match list
+        .iter()
+        .enumerate()
+        .find(|(_, element)| !element.is_number())
+    {
+        Some((index, element)) => Err(PklError::type_mismatch(
+            format!(
+                "Method '{}' expects a list of numbers, element at index {} is of type {}",
+                fn_name,
+                index,
+                element.describe_type()
+            ),
+            range,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Reduces a list of numbers starting from `initial`,
+/// the result being an `Int` if all the elements are integers and a `Float` otherwise.
+fn reduce_numbers(
+    list: &[PklValue],
+    fn_name: &str,
+    initial: i64,
+    int_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    expect_numbers(list, fn_name, range.to_owned())?;
+
+    if list
+        .iter()
+        .all(|element| matches!(element, PklValue::Int(_)))
+    {
+        return list
+            .iter()
+            .filter_map(PklValue::as_int)
+            .try_fold(initial, int_op)
+            .map(PklValue::Int)
+            .ok_or_else(|| (format!("Integer overflow in method '{}'", fn_name), range).into());
+    }
+
+    Ok(PklValue::Float(
+        list.iter()
+            .filter_map(PklValue::as_number)
+            .fold(initial as f64, float_op),
+    ))
+}
+
+/// The value of the field a list of objects is sorted by,
+/// Ints being kept apart from Floats so that they compare exactly.
+enum SortKey {
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+/// Sorts a list of objects or class instances by the value of one of their fields,
+/// the values of the field must either all be numbers or all be strings.
+fn sort_by_field(
+    list: Vec<PklValue>,
+    field_name: &str,
+    range: Range<usize>,
+) -> Result<PklValue, (String, Range<usize>)> {
+    let mut keyed_list = Vec::with_capacity(list.len());
+
+    for (index, element) in list.into_iter().enumerate() {
+        let fields = match &element {
+            PklValue::Object(fields) | PklValue::ClassInstance(_, fields) => fields,
+            _ => {
+                return Err((
+                    format!(
+                        "Cannot sort by '{}', element at index {} is of type {} and not an object",
+                        field_name,
+                        index,
+                        element.describe_type()
+                    ),
+                    range,
+                ))
+            }
+        };
+
+        let key = match fields.get(field_name) {
+            Some(PklValue::Int(i)) => SortKey::Int(*i),
+            Some(PklValue::Float(f)) => SortKey::Float(*f),
+            Some(PklValue::String(s)) => SortKey::String(s.to_owned()),
+            Some(value) => {
+                return Err((
+                    format!(
+                        "Cannot sort by '{}', field of type {} at index {} is not comparable",
+                        field_name,
+                        value.describe_type(),
+                        index
+                    ),
+                    range,
+                ))
+            }
+            None => {
+                return Err((
+                    format!(
+                        "Cannot sort by '{}', element at index {} does not possess a '{}' field",
+                        field_name, index, field_name
+                    ),
+                    range,
+                ))
+            }
+        };
+
+        keyed_list.push((key, element));
+    }
+
+    let all_numbers = keyed_list
+        .iter()
+        .all(|(key, _)| matches!(key, SortKey::Int(_) | SortKey::Float(_)));
+    let all_strings = keyed_list
+        .iter()
+        .all(|(key, _)| matches!(key, SortKey::String(_)));
+
+    if !all_numbers && !all_strings {
+        return Err((
+            format!(
+                "Cannot sort by '{}', field values are both numbers and strings",
+                field_name
+            ),
+            range,
+        ));
+    }
+
+    keyed_list.sort_by(|(a, _), (b, _)| match (a, b) {
+        (SortKey::Int(a), SortKey::Int(b)) => a.cmp(b),
+        // an Int is only converted when compared to a Float
+        (SortKey::Int(a), SortKey::Float(b)) => (*a as f64).total_cmp(b),
+        (SortKey::Float(a), SortKey::Int(b)) => a.total_cmp(&(*b as f64)),
+        (SortKey::Float(a), SortKey::Float(b)) => a.total_cmp(b),
+        (SortKey::String(a), SortKey::String(b)) => a.cmp(b),
+        _ => Ordering::Equal,
+    });
+
+    Ok(PklValue::List(
+        keyed_list.into_iter().map(|(_, element)| element).collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{eval, eval_err, parse, parse_err},
+        PklValue,
+    };
+
+    /// Parses objects `a`, `b` and `c` and evaluates `expr` after them.
+    fn with_objects(expr: &str) -> PklValue {
+        let pkl = parse(&format!(
+            "a {{\n  n = 2\n  name = \"b\"\n}}
+b {{\n  n = 3\n  name = \"a\"\n}}
+c {{\n  n = 1.5\n  name = \"c\"\n}}
+x = {expr}"
+        ));
+        pkl.get_value("x").unwrap()
+    }
+
+    fn names(value: PklValue) -> Vec<String> {
+        let PklValue::List(elements) = value else {
+            panic!("expected a List, got {value:?}");
+        };
+        elements
+            .into_iter()
+            .map(|element| match element {
+                PklValue::Object(fields) => match &fields["name"] {
+                    PklValue::String(name) => name.to_owned(),
+                    other => panic!("expected a String, got {other:?}"),
+                },
+                other => panic!("expected an object, got {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sort_by_orders_by_a_numeric_field() {
+        let sorted = with_objects("List(a, b, c).sortBy(\"n\")");
+        assert_eq!(names(sorted), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn sort_by_compares_ints_exactly() {
+        // both are the same Float once converted
+        let pkl = parse(
+            "a {\n  n = 9007199254740993\n  name = \"a\"\n}
+b {\n  n = 9007199254740992\n  name = \"b\"\n}
+x = List(a, b).sortBy(\"n\")",
+        );
+        assert_eq!(names(pkl.get_value("x").unwrap()), ["b", "a"]);
+    }
+
+    #[test]
+    fn sort_by_orders_by_a_string_field() {
+        let sorted = with_objects("List(a, b, c).sortBy(\"name\")");
+        assert_eq!(names(sorted), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn sort_by_rejects_missing_and_incomparable_fields() {
+        let error = parse_err("a { n = 1 }\nb { m = 2 }\nx = List(a, b).sortBy(\"n\")");
+        assert!(error
+            .msg()
+            .contains("element at index 1 does not possess a 'n' field"));
+
+        let error = parse_err("a { n = true }\nx = List(a).sortBy(\"n\")");
+        assert!(error
+            .msg()
+            .contains("field of type Boolean at index 0 is not comparable"));
+
+        let error = eval_err("List(1, 2).sortBy(\"n\")");
+        assert!(error.msg().contains("is of type Int and not an object"));
+    }
+
+    fn ints(values: &[i64]) -> PklValue {
+        PklValue::List(values.iter().copied().map(PklValue::Int).collect())
+    }
+
+    #[test]
+    fn add_and_plus_return_longer_lists() {
+        assert_eq!(eval("List(1, 2).add(3)"), ints(&[1, 2, 3]));
+        assert_eq!(eval("List(1).plus(List(2, 3))"), ints(&[1, 2, 3]));
+        assert_eq!(eval("List(1) == List(1).add(2)"), PklValue::Bool(false));
+    }
+
+    #[test]
+    fn plus_requires_a_list() {
+        let error = eval_err("List(1).plus(2)");
+        assert_eq!(
+            error.msg(),
+            "plus method expects argument at index 0 to be of type List, but found Int"
+        );
+    }
+
+    #[test]
+    fn accessors_agree_with_their_or_null_variants() {
+        for accessor in ["first", "last", "rest"] {
+            assert_eq!(
+                eval(&format!("List(1, 2, 3).{accessor}")),
+                eval(&format!("List(1, 2, 3).{accessor}OrNull")),
+                "{accessor}"
+            );
+            assert_eq!(eval(&format!("List().{accessor}OrNull")), PklValue::Null);
+        }
+        assert_eq!(eval("List(1, 2, 3).last"), PklValue::Int(3));
+        assert_eq!(eval("List(1, 2, 3).rest"), ints(&[2, 3]));
+
+        assert_eq!(eval("List(1).single"), PklValue::Int(1));
+        assert_eq!(eval("List(1, 2).singleOrNull"), PklValue::Null);
+        assert!(eval_err("List().last").msg().contains("empty"));
+    }
+
+    #[test]
+    fn with_index_pairs_elements_with_their_index() {
+        let string = |s: &str| PklValue::String(s.to_owned());
+        assert_eq!(
+            eval("List(\"a\", \"b\").withIndex()"),
+            PklValue::List(vec![
+                PklValue::List(vec![PklValue::Int(0), string("a")]),
+                PklValue::List(vec![PklValue::Int(1), string("b")]),
+            ])
+        );
+        assert_eq!(eval("List().withIndex()"), ints(&[]));
+    }
+
+    #[test]
+    fn numeric_reducers_check_their_elements_and_overflows() {
+        assert_eq!(eval("List(1, 2, 4).average()"), PklValue::Float(7.0 / 3.0));
+        assert_eq!(eval("List(1.5, 2).average()"), PklValue::Float(1.75));
+        assert_eq!(eval("List(1, 2, 3).product()"), PklValue::Int(6));
+        assert_eq!(eval("List(2, 1.5).product()"), PklValue::Float(3.0));
+        assert_eq!(eval("List().sum()"), PklValue::Int(0));
+        assert_eq!(eval("List().product()"), PklValue::Int(1));
+
+        assert_eq!(
+            eval_err("List().average()").msg(),
+            "Cannot compute the average of an empty list!"
+        );
+        assert_eq!(
+            eval_err("List(9223372036854775807, 2).product()").msg(),
+            "Integer overflow in method 'product'"
+        );
+        assert_eq!(
+            eval_err("List(9223372036854775807, 1).sum()").msg(),
+            "Integer overflow in method 'sum'"
+        );
+        assert_eq!(
+            eval_err(r#"List(1, "a").average()"#).msg(),
+            "Method 'average' expects a list of numbers, element at index 1 is of type String"
+        );
+    }
+
+    #[test]
+    fn chunked_and_windowed_batch_the_elements() {
+        assert_eq!(
+            eval("List(1, 2, 3, 4, 5).chunked(2)"),
+            PklValue::List(vec![ints(&[1, 2]), ints(&[3, 4]), ints(&[5])])
+        );
+        assert_eq!(
+            eval("List(1, 2, 3, 4, 5).windowed(3)"),
+            PklValue::List(vec![ints(&[1, 2, 3]), ints(&[2, 3, 4]), ints(&[3, 4, 5])])
+        );
+        assert_eq!(eval("List(1, 2).windowed(3)"), ints(&[]));
+
+        assert_eq!(
+            eval_err("List(1, 2).chunked(0)").msg(),
+            "Method 'chunked' expects a positive size, here it is '0'"
+        );
+        assert_eq!(
+            eval_err("List(1, 2).windowed(-1)").msg(),
+            "Method 'windowed' expects a positive size, here it is '-1'"
+        );
+    }
+
+    #[test]
+    fn partition_splits_on_a_predicate() {
+        let ints =
+            |values: &[i64]| PklValue::List(values.iter().copied().map(PklValue::Int).collect());
+
+        assert_eq!(
+            eval("List(1, 2, 3, 4, 5).partition((n) -> n % 2 == 0)"),
+            PklValue::List(vec![ints(&[2, 4]), ints(&[1, 3, 5])])
+        );
+        assert_eq!(
+            eval("List().partition((n) -> n > 0)"),
+            PklValue::List(vec![ints(&[]), ints(&[])])
+        );
+        let error = eval_err("List(1).partition((n) -> n)");
+        assert_eq!(
+            error.msg(),
+            "The predicate of 'partition' must return a Boolean, found Int"
+        );
+        assert_eq!(error.code(), Some("pkl_fast::type_mismatch"));
     }
 }
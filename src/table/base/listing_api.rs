@@ -0,0 +1,147 @@
+use crate::{generate_method, PklError, PklResult, PklValue};
+use indexmap::IndexMap;
+use std::ops::Range;
+
+/// Based on v0.26.0
+pub fn match_listing_props_api(
+    listing: Vec<PklValue>,
+    property: &str,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match property {
+        "length" => Ok(PklValue::Int(listing.len() as i64)),
+        "isEmpty" => Ok(PklValue::Bool(listing.is_empty())),
+        _ => Err(PklError::UnknownMember(
+            format!("Listing does not possess {} property", property),
+            range,
+            None,
+        )),
+    }
+}
+
+/// Based on v0.26.0
+pub fn match_listing_methods_api(
+    listing: Vec<PklValue>,
+    fn_name: &str,
+    args: Vec<PklValue>,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match fn_name {
+        "toList" => {
+            generate_method!(
+                "toList", &args;
+                Ok(PklValue::List(listing));
+                range
+            )
+        }
+        _ => Err(PklError::UnknownMember(
+            format!("Listing does not possess {} method", fn_name),
+            range,
+            None,
+        )),
+    }
+}
+
+/// Based on v0.26.0
+pub fn match_mapping_props_api(
+    mapping: IndexMap<String, PklValue>,
+    property: &str,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match property {
+        "length" => Ok(PklValue::Int(mapping.len() as i64)),
+        "isEmpty" => Ok(PklValue::Bool(mapping.is_empty())),
+        "keys" => Ok(PklValue::List(
+            mapping.into_keys().map(PklValue::String).collect(),
+        )),
+        _ => Err(PklError::UnknownMember(
+            format!("Mapping does not possess {} property", property),
+            range,
+            None,
+        )),
+    }
+}
+
+/// Based on v0.26.0
+pub fn match_mapping_methods_api(
+    mut mapping: IndexMap<String, PklValue>,
+    fn_name: &str,
+    args: Vec<PklValue>,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match fn_name {
+        "containsKey" => {
+            generate_method!(
+                "containsKey", &args;
+                0: String;
+                |key: String| Ok(mapping.contains_key(&key).into());
+                range
+            )
+        }
+        "getOrNull" => {
+            generate_method!(
+                "getOrNull", &args;
+                0: String;
+                |key: String| Ok(mapping.swap_remove(&key).unwrap_or(PklValue::Null));
+                range
+            )
+        }
+        _ => Err(PklError::UnknownMember(
+            format!("Mapping does not possess {} method", fn_name),
+            range,
+            None,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{parse, parse_err},
+        PklValue,
+    };
+
+    #[test]
+    fn amending_a_listing_appends_elements() {
+        let pkl = parse(
+            "base = new Listing {\n  1\n  2\n}\nderived = (base) {\n  3\n}\nn = derived.length",
+        );
+
+        assert_eq!(
+            pkl.get_value("derived"),
+            Some(PklValue::Listing(vec![
+                PklValue::Int(1),
+                PklValue::Int(2),
+                PklValue::Int(3)
+            ]))
+        );
+        assert_eq!(pkl.get_value("n"), Some(PklValue::Int(3)));
+    }
+
+    #[test]
+    fn amending_a_mapping_overrides_entries() {
+        let pkl = parse("base = new Mapping {\n  [\"a\"] = 1\n  [\"b\"] = 2\n}\nderived = (base) {\n  [\"a\"] = 3\n  [\"c\"] = 4\n}\nhasC = derived.containsKey(\"c\")");
+
+        let Some(PklValue::Mapping(entries)) = pkl.get_value("derived") else {
+            panic!("expected a Mapping");
+        };
+        assert_eq!(entries.keys().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(entries["a"], PklValue::Int(3));
+        assert_eq!(pkl.get_value("hasC"), Some(PklValue::Bool(true)));
+    }
+
+    #[test]
+    fn listings_and_mappings_reject_members_of_the_other_kind() {
+        let error = parse_err("x = new Listing {\n  a = 1\n}");
+        assert_eq!(
+            error.msg(),
+            "Cannot declare property `a` in a Listing, only elements are allowed"
+        );
+
+        let error = parse_err("x = new Mapping {\n  1\n}");
+        assert_eq!(
+            error.msg(),
+            "Cannot declare an element in a Mapping, only entries are allowed"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{generate_method, table::value::contains_structurally, PklError, PklResult, PklValue};
+use std::ops::Range;
+
+/// Based on v0.26.0
+pub fn match_set_props_api(
+    set: Vec<PklValue>,
+    property: &str,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match property {
+        "length" => Ok(PklValue::Int(set.len() as i64)),
+        "isEmpty" => Ok(PklValue::Bool(set.is_empty())),
+        _ => Err(PklError::UnknownMember(
+            format!("Set does not possess {} property", property),
+            range,
+            None,
+        )),
+    }
+}
+
+/// Based on v0.26.0
+pub fn match_set_methods_api(
+    set: Vec<PklValue>,
+    fn_name: &str,
+    args: Vec<PklValue>,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match fn_name {
+        "toList" => {
+            generate_method!(
+                "toList", &args;
+                Ok(PklValue::List(set));
+                range
+            )
+        }
+        "contains" => {
+            // the element can be of any type, thus `generate_method` cannot be used
+            match args.as_slice() {
+                [element] => Ok(contains_structurally(&set, element).into()),
+                _ => Err((
+                    "Method 'contains' expects exactly 1 argument(s)".to_owned(),
+                    range,
+                )
+                    .into()),
+            }
+        }
+        _ => Err(PklError::UnknownMember(
+            format!("Set does not possess {} method", fn_name),
+            range,
+            None,
+        )),
+    }
+}
+
+/// Removes the elements structurally equal to a previous one,
+/// keeping the first occurrence of each element in place.
+pub(crate) fn distinct(elements: Vec<PklValue>) -> Vec<PklValue> {
+    let mut set: Vec<PklValue> = Vec::with_capacity(elements.len());
+
+    for element in elements {
+        if !contains_structurally(&set, &element) {
+            set.push(element);
+        }
+    }
+
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{eval, parse},
+        PklValue,
+    };
+
+    #[test]
+    fn lists_and_sets_convert_into_each_other() {
+        assert_eq!(
+            eval("List(1, 2, 2, 3).toSet().toList() == List(1, 2, 3)"),
+            PklValue::Bool(true)
+        );
+        assert_eq!(
+            eval("List(1, 2, 2, 3).toSet().toList()"),
+            PklValue::List(vec![PklValue::Int(1), PklValue::Int(2), PklValue::Int(3)])
+        );
+    }
+
+    #[test]
+    fn sets_deduplicate_structurally_equal_elements() {
+        assert_eq!(eval("Set(1, 1.0).length"), PklValue::Int(1));
+        assert_eq!(eval("Set(List(1), List(1.0)).length"), PklValue::Int(1));
+        assert_eq!(eval("Set(1, 2).contains(2.0)"), PklValue::Bool(true));
+
+        let pkl = parse("o {\n  a = 1\n}\np {\n  a = 1.0\n}\nx = List(o, p).toSet().length");
+        assert_eq!(pkl.get_value("x"), Some(PklValue::Int(1)));
+    }
+}
@@ -1,47 +1,121 @@
+use super::{
+    data_size::{self, Byte},
+    duration::{self, Duration},
+};
 use crate::generate_method;
-use crate::{PklResult, PklValue};
+#[cfg(feature = "suggestions")]
+use crate::table::utils::spelling::suggest_closest_word;
+use crate::{PklError, PklResult, PklValue};
 use base64::prelude::*;
 use std::ops::Range;
 
+const STRING_PROPERTIES: [&str; 13] = [
+    "length",
+    "lastIndex",
+    "isEmpty",
+    "isBlank",
+    "isRegex",
+    "md5",
+    "sha1",
+    "sha256",
+    "sha256Int",
+    "base64",
+    "base64Decoded",
+    "chars",
+    "codePoints",
+];
+
+const STRING_METHODS: [&str; 51] = [
+    "getOrNull",
+    "substring",
+    "substringOrNull",
+    "repeat",
+    "contains",
+    "count",
+    "matches",
+    "startsWith",
+    "endsWith",
+    "indexOf",
+    "indexOfOrNull",
+    "lastIndexOf",
+    "lastIndexOfOrNull",
+    "take",
+    "takeWhile",
+    "takeLast",
+    "takeLastWhile",
+    "drop",
+    "dropWhile",
+    "dropLast",
+    "dropLastWhile",
+    "replaceFirst",
+    "replaceLast",
+    "replaceAll",
+    "replaceFirstMapped",
+    "replaceLastMapped",
+    "replaceAllMapped",
+    "replaceRange",
+    "toUpperCase",
+    "toLowerCase",
+    "reverse",
+    "trim",
+    "trimStart",
+    "trimEnd",
+    "padStart",
+    "padEnd",
+    "rjust",
+    "ljust",
+    "split",
+    "capitalize",
+    "decapitalize",
+    "isUpperCase",
+    "isLowerCase",
+    "toInt",
+    "toIntOrNull",
+    "toFloat",
+    "toFloatOrNull",
+    "toBoolean",
+    "toBooleanOrNull",
+    "toDuration",
+    "toDataSize",
+];
+
 /// Based on v0.26.0
 pub fn match_string_props_api(s: &str, property: &str, range: Range<usize>) -> PklResult<PklValue> {
     match property {
-        "length" => return Ok(PklValue::Int(s.len() as i64)),
-        "lastIndex" => {
-            return Ok(PklValue::Int({
-                if s.len() == 0 {
-                    -1
-                } else {
-                    (s.len() - 1) as i64
-                }
-            }))
-        }
+        // the length is a number of codepoints, not of bytes
+        "length" => return Ok(PklValue::Int(s.chars().count() as i64)),
+        "lastIndex" => return Ok(PklValue::Int(s.chars().count() as i64 - 1)),
         "isEmpty" => return Ok(PklValue::Bool(s.len() == 0)),
         "isBlank" => return Ok(PklValue::Bool(s.trim().len() == 0).into()),
         "isRegex" => {
-            return Err((
+            return Err(PklError::unsupported(
                 "isRegex String API method not yet supported".to_owned(),
                 range,
-            )
-                .into())
+            ))
+        }
+        "md5" => {
+            return Err(PklError::unsupported(
+                "md5 String API method not yet supported".to_owned(),
+                range,
+            ))
         }
-        "md5" => return Err(("md5 String API method not yet supported".to_owned(), range).into()),
         "sha1" => {
-            return Err(("sha1 String API method not yet supported".to_owned(), range).into())
+            return Err(PklError::unsupported(
+                "sha1 String API method not yet supported".to_owned(),
+                range,
+            ))
         }
         "sha256" => {
-            return Err((
+            return Err(PklError::unsupported(
                 "sha256 String API method not yet supported".to_owned(),
                 range,
-            )
-                .into())
+            ))
         }
         "sha256Int" => {
-            return Err((
+            return Err(PklError::unsupported(
                 "sha256Int String API method not yet supported".to_owned(),
                 range,
-            )
-                .into())
+            ))
         }
         "base64" => return Ok(PklValue::String(BASE64_STANDARD.encode(s))),
         "base64Decoded" => {
@@ -75,11 +149,12 @@ pub fn match_string_props_api(s: &str, property: &str, range: Range<usize>) -> P
             return Ok(PklValue::List(codepoints));
         }
         _ => {
-            return Err((
-                format!("String does not possess {} property", property),
+            return Err(unknown_member_err(
+                property,
+                "property",
+                &STRING_PROPERTIES,
                 range,
-            )
-                .into())
+            ))
         }
     }
 }
@@ -97,11 +172,13 @@ pub fn match_string_methods_api(
                 "getOrNull", &args;
                 0: Int;
                 |index: i64| {
-                    if let Some(s) = s.get(index as usize..(index+1) as usize) {
-                        return Ok(String::from(s).into())
+                    // the index is a codepoint index
+                    let length = s.chars().count();
+                    if index < 0 || index as usize >= length {
+                        return Ok(().into())
                     }
 
-                    Ok(().into())
+                    Ok(codepoint_slice(s, index as usize, index as usize + 1).to_owned().into())
                 };
                 range
             )
@@ -111,18 +188,16 @@ pub fn match_string_methods_api(
                 "substring", &args;
                 0: Int, 1: Int;
                 |(start, exclusive_end): (i64, i64)| {
-                    if start < 0 || start as usize >= s.len() {
+                    // indices are codepoint indices, both may be equal to the length, slicing to the end
+                    let length = s.chars().count();
+                    if start < 0 || start as usize > length {
                         return Err(("start index is out of bound".to_owned(), range))
                     }
-                    if exclusive_end < start || exclusive_end as usize >= s.len() {
+                    if exclusive_end < start || exclusive_end as usize > length {
                         return Err(("exclusiveEnd index is out of bound".to_owned(), range))
                     }
 
-                    if let Some(s) = s.get(start as usize..exclusive_end as usize) {
-                        return Ok(String::from(s).into())
-                    }
-
-                    Ok(().into())
+                    Ok(codepoint_slice(s, start as usize, exclusive_end as usize).to_owned().into())
                 };
                 range
             )
@@ -132,15 +207,12 @@ pub fn match_string_methods_api(
                 "substringOrNull", &args;
                 0: Int, 1: Int;
                 |(start, exclusive_end): (i64, i64)| {
-                    if start < 0 || start as usize >= s.len() || exclusive_end < start || exclusive_end as usize >= s.len() {
+                    let length = s.chars().count();
+                    if start < 0 || start as usize > length || exclusive_end < start || exclusive_end as usize > length {
                         return Ok(().into())
                     }
 
-                    if let Some(s) = s.get(start as usize..exclusive_end as usize) {
-                        return Ok(String::from(s).into())
-                    }
-
-                    Ok(().into())
+                    Ok(codepoint_slice(s, start as usize, exclusive_end as usize).to_owned().into())
                 };
                 range
             )
@@ -165,6 +237,23 @@ pub fn match_string_methods_api(
                 range
             )
         }
+        "count" => {
+            // the overlapping flag is optional, thus `generate_method` cannot be used
+            let (pattern, overlapping) = match args.as_slice() {
+                [PklValue::String(pattern)] => (pattern, false),
+                [PklValue::String(pattern), PklValue::Bool(overlapping)] => (pattern, *overlapping),
+                _ => {
+                    return Err((
+                        "Method 'count' expects a String and an optional Boolean argument"
+                            .to_owned(),
+                        range,
+                    )
+                        .into())
+                }
+            };
+
+            Ok(count_occurrences(s, pattern, overlapping, range)?)
+        }
         "matches" => {
             generate_method!(
                 "matches", &args;
@@ -201,7 +290,7 @@ pub fn match_string_methods_api(
                 0: String;
                 |pattern: String| {
                     let result = s.find(&pattern).ok_or((format!("Cannot use indexOf to index pattern '{pattern}', it is not present in the string"), range))?;
-                    Ok((result as i64).into())
+                    Ok(codepoint_index(s, result).into())
                 };
                 range
             )
@@ -211,7 +300,7 @@ pub fn match_string_methods_api(
                 "indexOfOrNull", &args;
                 0: String;
                 |pattern: String| {
-                    Ok(s.find(&pattern).map(|x| x as i64).map(PklValue::Int).unwrap_or(PklValue::Null))
+                    Ok(s.find(&pattern).map(|x| codepoint_index(s, x)).map(PklValue::Int).unwrap_or(PklValue::Null))
                 };
                 range
             )
@@ -222,7 +311,7 @@ pub fn match_string_methods_api(
                 0: String;
                 |pattern: String| {
                     let result = s.rfind(&pattern).ok_or((format!("Cannot use lastIndexOf to index pattern '{pattern}', it is not present in the string"), range))?;
-                    Ok((result as i64).into())
+                    Ok(codepoint_index(s, result).into())
                 };
                 range
             )
@@ -232,7 +321,7 @@ pub fn match_string_methods_api(
                 "lastIndexOfOrNull", &args;
                 0: String;
                 |pattern: String| {
-                    Ok(s.rfind(&pattern).map(|x| x as i64).map(PklValue::Int).unwrap_or(PklValue::Null))
+                    Ok(s.rfind(&pattern).map(|x| codepoint_index(s, x)).map(PklValue::Int).unwrap_or(PklValue::Null))
                 };
                 range
             )
@@ -243,7 +332,8 @@ pub fn match_string_methods_api(
                 0: Int;
                 |n: i64| {
                     if n.is_negative() {return Err(("Cannot use take method with a negative index".to_owned(), range))}
-                    Ok(s[..=(n as usize).min(s.len())].to_owned().into())
+                    // counts codepoints, the whole string is returned when `n` exceeds its length
+                    Ok(s.chars().take(n as usize).collect::<String>().into())
                 };
                 range
             )
@@ -264,47 +354,28 @@ pub fn match_string_methods_api(
                 0: Int;
                 |n: i64| {
                     if n.is_negative() {return Err(("Cannot use takeLast method with a negative index".to_owned(), range))}
-                    if n as usize >= s.len() {return Ok(String::from(s).into())}
-                    Ok(s[s.len() - n as usize..].to_owned().into())
-                };
-                range
-            )
-        }
-        "takeLastWhile" => {
-            generate_method!(
-                "takeLastWhile", &args;
-                0: String;
-                |_pattern: String| {
-                    // Argument function not yet supported
-                    return Err(("Function arguments are not yet supported!".to_owned(), range));
-                    // Ok(s[s.len() - s.trim_end_matches(&pattern).len()..].to_owned().into())
+                    let length = s.chars().count();
+                    Ok(s.chars().skip(length.saturating_sub(n as usize)).collect::<String>().into())
                 };
                 range
             )
         }
+        // these methods take a function argument
+        "takeLastWhile" | "dropWhile" | "dropLastWhile" | "replaceFirstMapped"
+        | "replaceLastMapped" | "replaceAllMapped" => Err(PklError::unsupported(
+            format!("Function arguments are not yet supported, `{fn_name}` String method cannot be used"),
+            range,
+        )),
         "drop" => {
             generate_method!(
                 "drop", &args;
                 0: Int;
                 |n: i64| {
                     if n.is_negative() {return Err(("Cannot use drop method with a negative index".to_owned(), range))}
-                    if n as usize >= s.len() {return Ok(String::new().into())}
-                    Ok(s[n as usize..].to_owned().into())
-                };
-                range
-            )
-        }
-        "dropWhile" => {
-            generate_method!(
-                "dropWhile", &args;
-                0: Int;
-                |_n: i64| {
-                    // Argument function not yet supported
-                    return Err(("Function arguments are not yet supported!".to_owned(), range));
-
-                    // if n.is_negative() {return Err(("Cannot use dropWhile method with a negative index".to_owned(), range))}
-                    // if n as usize >= s.len() {return Ok(String::new().into())}
-                    // Ok(s[n as usize..].to_owned().into())
+                    // counts codepoints, nothing is left when `n` exceeds the length
+                    let length = s.chars().count();
+                    let n = (n as usize).min(length);
+                    Ok(codepoint_slice(s, n, length).to_owned().into())
                 };
                 range
             )
@@ -315,23 +386,9 @@ pub fn match_string_methods_api(
                 0: Int;
                 |n: i64| {
                     if n.is_negative() {return Err(("Cannot use dropLast method with a negative index".to_owned(), range))}
-                    if n as usize >= s.len() {return Ok(String::new().into())}
-                    Ok(s[..s.len() - n as usize].to_owned().into())
-                };
-                range
-            )
-        }
-        "dropLastWhile" => {
-            generate_method!(
-                "dropLastWhile", &args;
-                0: Int;
-                |_n: i64| {
-                    // Argument function not yet supported
-                    return Err(("Function arguments are not yet supported!".to_owned(), range));
-
-                    // if n.is_negative() {return Err(("Cannot use dropWhile method with a negative index".to_owned(), range))}
-                    // if n as usize >= s.len() {return Ok(String::new().into())}
-                    // Ok(s[n as usize..].to_owned().into())
+                    let length = s.chars().count();
+                    let n = (n as usize).min(length);
+                    Ok(codepoint_slice(s, 0, length - n).to_owned().into())
                 };
                 range
             )
@@ -371,54 +428,30 @@ pub fn match_string_methods_api(
                 range
             )
         }
-        "replaceFirstMapped" => {
-            generate_method!(
-                "replaceFirstMapped", &args;
-                0: String;
-                |_pattern: String| {
-                    // Argument function not yet supported
-                    return Err(("Function arguments are not yet supported!".to_owned(), range));
-                };
-                range
-            )
-        }
-        "replaceLastMapped" => {
-            generate_method!(
-                "replaceLastMapped", &args;
-                0: String;
-                |_pattern: String| {
-                    // Argument function not yet supported
-                    return Err(("Function arguments are not yet supported!".to_owned(), range));
-                };
-                range
-            )
-        }
-        "replaceAllMapped" => {
-            generate_method!(
-                "replaceAllMapped", &args;
-                0: String;
-                |_pattern: String| {
-                    // Argument function not yet supported
-                     Err(("Function arguments are not yet supported!".to_owned(), range))
-                };
-                range
-            )
-        }
         "replaceRange" => {
             generate_method!(
                 "replaceRange", &args;
                 0: Int, 1: Int, 2:String;
                 |(start, exclusive_end, replacement): (i64,i64, String)| {
-                    if start.is_negative() {return Err(("Cannot use replaceRange method with a negative index (start)".to_owned(), range))}
-                    if exclusive_end.is_negative() {return Err(("Cannot use replaceRange method with a negative index (exclusiveEnd)".to_owned(), range))}
+                    // indices are codepoint indices, not byte indices
+                    let length = s.chars().count() as i64;
 
-                    if start as usize >= s.len() || exclusive_end as  usize > s.len() || start > exclusive_end {
-                        return Ok(String::from(s).into()); // Invalid range, return the original string
+                    if start < 0 || start > length {
+                        return Err((format!("start index {} is out of bounds, expected a value between 0 and {}", start, length), range))
                     }
-                    let mut result = String::new();
-                    result.push_str(&s[0..start as usize]);
-                    result.push_str(&replacement);
-                    result.push_str(&s[exclusive_end as usize..]);
+                    if exclusive_end < 0 || exclusive_end > length {
+                        return Err((format!("exclusiveEnd index {} is out of bounds, expected a value between 0 and {}", exclusive_end, length), range))
+                    }
+                    if exclusive_end < start {
+                        return Err((format!("exclusiveEnd index {} is lower than start index {}", exclusive_end, start), range))
+                    }
+
+                    let result: String = s
+                        .chars()
+                        .take(start as usize)
+                        .chain(replacement.chars())
+                        .chain(s.chars().skip(exclusive_end as usize))
+                        .collect();
 
                     Ok(result.into())
                 };
@@ -446,58 +479,80 @@ pub fn match_string_methods_api(
                 range
             )
         }
-        "trim" => {
+        "trim" if args.is_empty() => {
             generate_method!(
                 "trim", &args;
                 Ok(s.trim().to_owned().into());
                 range
             )
         }
-        "trimStart" => {
+        "trim" => {
+            generate_method!(
+                "trim", &args;
+                0: String;
+                |chars: String| {
+                    if chars.is_empty() {
+                        return Ok(s.trim().to_owned().into());
+                    }
+                    Ok(s.trim_matches(|c| chars.contains(c)).to_owned().into())
+                };
+                range
+            )
+        }
+        "trimStart" if args.is_empty() => {
             generate_method!(
                 "trimStart", &args;
                 Ok(s.trim_start().to_owned().into());
                 range
             )
         }
-        "trimEnd" => {
+        "trimStart" => {
+            generate_method!(
+                "trimStart", &args;
+                0: String;
+                |chars: String| {
+                    if chars.is_empty() {
+                        return Ok(s.trim_start().to_owned().into());
+                    }
+                    Ok(s.trim_start_matches(|c| chars.contains(c)).to_owned().into())
+                };
+                range
+            )
+        }
+        "trimEnd" if args.is_empty() => {
             generate_method!(
                 "trimEnd", &args;
                 Ok(s.trim_end().to_owned().into());
                 range
             )
         }
-        "padStart" => {
+        "trimEnd" => {
             generate_method!(
-                "padStart", &args;
-                0: Int, 1: String;
-                |(width, character): (i64, String)| {
-                    if character.len() != 1 {return Err(("padStart expects a Char (String(length = 1)), found String".to_owned(), range))}
-                    if s.len() as i64 >= width {return Ok(String::from(s).into())}
-                    let mut string = String::with_capacity(width as usize);
-                    while (string.len() as i64) + (s.len() as i64) < width {
-                        string.push_str(&character);
+                "trimEnd", &args;
+                0: String;
+                |chars: String| {
+                    if chars.is_empty() {
+                        return Ok(s.trim_end().to_owned().into());
                     }
-                    string.push_str(s);
-                    Ok(string.into())
+                    Ok(s.trim_end_matches(|c| chars.contains(c)).to_owned().into())
                 };
                 range
             )
         }
-        "padEnd" => {
+        // `rjust` and `ljust` are aliases of `padStart` and `padEnd`
+        "padStart" | "rjust" => {
             generate_method!(
-                "padEnd", &args;
+                fn_name, &args;
                 0: Int, 1: String;
-                |(width, character): (i64, String)| {
-                    if character.len() != 1 {return Err(("padEnd expects a Char (String(length = 1)), found String".to_owned(), range))}
-                    if s.len() as i64 >= width {return Ok(String::from(s).into())}
-                    let mut string = String::with_capacity(width as usize);
-                    string.push_str(s);
-                    while (string.len() as i64) < width {
-                        string.push_str(&character);
-                    }
-                    Ok(string.into())
-                };
+                |(width, character): (i64, String)| pad(s, fn_name, width, &character, true, range);
+                range
+            )
+        }
+        "padEnd" | "ljust" => {
+            generate_method!(
+                fn_name, &args;
+                0: Int, 1: String;
+                |(width, character): (i64, String)| pad(s, fn_name, width, &character, false, range);
                 range
             )
         }
@@ -516,6 +571,8 @@ pub fn match_string_methods_api(
             generate_method!(
                 "capitalize", &args;
                 {
+                    // the full uppercase mapping is used, a first character such as `ß`
+                    // becoming several ones, `"ßa".capitalize()` being `"SSa"`
                     let mut chars = s.chars();
                     let new_s = match chars.next() {
                         None => String::new(),
@@ -530,6 +587,7 @@ pub fn match_string_methods_api(
             generate_method!(
                 "decapitalize", &args;
                 {
+                    // the full lowercase mapping is used, as for `capitalize`
                     let mut chars = s.chars();
                     let new_s = match chars.next() {
                         None => String::new(),
@@ -540,6 +598,21 @@ pub fn match_string_methods_api(
                 range
             )
         }
+        "isUpperCase" => {
+            // characters without a case, such as digits, are ignored
+            generate_method!(
+                "isUpperCase", &args;
+                Ok(s.chars().all(|c| !c.is_lowercase()).into());
+                range
+            )
+        }
+        "isLowerCase" => {
+            generate_method!(
+                "isLowerCase", &args;
+                Ok(s.chars().all(|c| !c.is_uppercase()).into());
+                range
+            )
+        }
         "toInt" => {
             generate_method!(
                 "toInt", &args;
@@ -552,6 +625,38 @@ pub fn match_string_methods_api(
                 range
             )
         }
+        "toDuration" => {
+            generate_method!(
+                "toDuration", &args;
+                {
+                    let (value, unit) = split_value_and_unit(s)
+                        .ok_or_else(|| (invalid_unit_value_msg(s, "Duration", "duration"), range.to_owned()))?;
+
+                    match (value, duration::Unit::from_str(unit)) {
+                        (PklValue::Int(i), Some(unit)) => Ok(Duration::from_int_and_unit(i, unit).into()),
+                        (PklValue::Float(f), Some(unit)) => Ok(Duration::from_float_and_unit(f, unit).into()),
+                        _ => Err((format!("Cannot convert '{}' to Duration, durationUnit '{}' is not valid", s, unit), range).into()),
+                    }
+                };
+                range
+            )
+        }
+        "toDataSize" => {
+            generate_method!(
+                "toDataSize", &args;
+                {
+                    let (value, unit) = split_value_and_unit(s)
+                        .ok_or_else(|| (invalid_unit_value_msg(s, "DataSize", "data size"), range.to_owned()))?;
+
+                    match (value, data_size::Unit::from_str(unit)) {
+                        (PklValue::Int(i), Some(unit)) => Ok(Byte::from_int_and_unit(i, unit).into()),
+                        (PklValue::Float(f), Some(unit)) => Ok(Byte::from_float_and_unit(f, unit).into()),
+                        _ => Err((format!("Cannot convert '{}' to DataSize, datasizeUnit '{}' is not valid", s, unit), range).into()),
+                    }
+                };
+                range
+            )
+        }
         "toIntOrNull" => {
             generate_method!(
                 "toIntOrNull", &args;
@@ -615,14 +720,352 @@ pub fn match_string_methods_api(
             )
         }
         _ => {
-            return Err((
-                format!(
-                    "String does not possess {} method (or method not yet implemented)",
-                    fn_name
-                ),
+            return Err(unknown_member_err(
+                fn_name,
+                "method",
+                &STRING_METHODS,
                 range,
-            )
-                .into())
+            ))
         }
     }
 }
+
+/// Splits a string such as `30s` or `1.5mib` into its value and its unit,
+/// the value is an Int unless it has a fractional part.
+fn split_value_and_unit(s: &str) -> Option<(PklValue, &str)> {
+    let s = s.trim();
+    let unit_start = s.find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '.' | '_')))?;
+    let (value, unit) = s.split_at(unit_start);
+    let value: String = value.chars().filter(|&c| c != '_').collect();
+
+    if let Ok(i) = value.parse::<i64>() {
+        return Some((PklValue::Int(i), unit.trim_start()));
+    }
+
+    match value.parse::<f64>() {
+        Ok(f) if f.is_finite() => Some((PklValue::Float(f), unit.trim_start())),
+        _ => None,
+    }
+}
+
+fn invalid_unit_value_msg(s: &str, type_name: &str, unit_kind: &str) -> String {
+    format!(
+        "Cannot convert '{}' to {}, expected a number followed by a {} unit",
+        s, type_name, unit_kind
+    )
+}
+
+/// Builds the error for a property or a method the String API does not possess,
+/// suggesting the closest existing name if any with the `suggestions` feature.
+#[cfg_attr(not(feature = "suggestions"), allow(unused_variables))]
+fn unknown_member_err(
+    name: &str,
+    kind: &str,
+    known_names: &[&str],
+    range: Range<usize>,
+) -> PklError {
+    let msg = format!("String does not possess {} {}", name, kind);
+    #[cfg(feature = "suggestions")]
+    let msg = match suggest_closest_word(name, known_names, 2) {
+        Some(closest) => format!("{}, did you mean '{}'?", msg, closest),
+        None => msg,
+    };
+
+    PklError::UnknownMember(msg, range, None)
+}
+
+/// Pads `s` with `character` until it is `width` characters long,
+/// at its start or at its end. Widths are measured in codepoints.
+fn pad(
+    s: &str,
+    fn_name: &str,
+    width: i64,
+    character: &str,
+    at_start: bool,
+    range: Range<usize>,
+) -> Result<PklValue, (String, Range<usize>)> {
+    let mut chars = character.chars();
+    let (Some(character), None) = (chars.next(), chars.next()) else {
+        return Err((
+            format!("{fn_name} expects a Char (String(length = 1)), found String"),
+            range,
+        ));
+    };
+
+    let length = s.chars().count();
+    let missing = usize::try_from(width).unwrap_or(0).saturating_sub(length);
+    let padding = character.to_string().repeat(missing);
+
+    if at_start {
+        Ok((padding + s).into())
+    } else {
+        Ok((s.to_owned() + &padding).into())
+    }
+}
+
+/// Slices `s` between two codepoint indices, which must not exceed its number of codepoints.
+fn codepoint_slice(s: &str, start: usize, exclusive_end: usize) -> &str {
+    let byte_index = |index: usize| s.char_indices().nth(index).map_or(s.len(), |(i, _)| i);
+
+    &s[byte_index(start)..byte_index(exclusive_end)]
+}
+
+/// Returns the codepoint index of the byte index `byte_index` of `s`.
+fn codepoint_index(s: &str, byte_index: usize) -> i64 {
+    s[..byte_index].chars().count() as i64
+}
+
+/// Counts the occurrences of `pattern` in `s`, an occurrence starting
+/// inside the previous one only being counted when `overlapping` is set.
+fn count_occurrences(
+    s: &str,
+    pattern: &str,
+    overlapping: bool,
+    range: Range<usize>,
+) -> Result<PklValue, (String, Range<usize>)> {
+    let Some(first_char) = pattern.chars().next() else {
+        return Err((
+            "Cannot count the occurrences of an empty String".to_owned(),
+            range,
+        ));
+    };
+
+    if !overlapping {
+        return Ok(PklValue::Int(s.matches(pattern).count() as i64));
+    }
+
+    let mut count = 0;
+    let mut start = 0;
+    while let Some(index) = s[start..].find(pattern) {
+        count += 1;
+        // the next occurrence may start right after the first char of this one
+        start += index + first_char.len_utf8();
+    }
+
+    Ok(PklValue::Int(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{eval, eval_err};
+    use crate::PklValue;
+
+    #[test]
+    fn unimplemented_members_are_told_apart_from_unknown_ones() {
+        let unimplemented = eval_err("\"x\".md5");
+        let unknown = eval_err("\"x\".bogus");
+
+        assert_eq!(unimplemented.code(), Some("pkl_fast::unsupported"));
+        assert_eq!(unknown.code(), Some("pkl_fast::unknown_member"));
+        assert_eq!(
+            eval_err("List(1).min").code(),
+            Some("pkl_fast::unsupported")
+        );
+    }
+
+    #[cfg(feature = "suggestions")]
+    #[test]
+    fn unknown_members_suggest_the_closest_name() {
+        let error = eval_err("\"x\".lenght");
+        assert!(error.msg().contains("length"), "{}", error.msg());
+    }
+
+    #[cfg(not(feature = "suggestions"))]
+    #[test]
+    fn unknown_members_are_reported_without_a_suggestion() {
+        let error = eval_err("\"x\".lenght");
+        assert_eq!(error.msg(), "String does not possess lenght property");
+    }
+
+    fn string(s: &str) -> PklValue {
+        PklValue::String(s.to_owned())
+    }
+
+    #[test]
+    fn replace_range_uses_codepoint_indices() {
+        assert_eq!(
+            eval("\"héllo\".replaceRange(1, 4, \"ipp\")"),
+            string("hippo")
+        );
+        assert_eq!(
+            eval("\"日本語\".replaceRange(3, 3, \"!\")"),
+            string("日本語!")
+        );
+    }
+
+    #[test]
+    fn indexing_methods_use_codepoint_indices() {
+        assert_eq!(eval("\"héllo\".getOrNull(1)"), string("é"));
+        assert_eq!(eval("\"héllo\".getOrNull(4)"), string("o"));
+        assert_eq!(eval("\"héllo\".getOrNull(5)"), PklValue::Null);
+        assert_eq!(eval("\"héllo\".getOrNull(-1)"), PklValue::Null);
+
+        assert_eq!(eval("\"日本語の本\".indexOf(\"本\")"), PklValue::Int(1));
+        assert_eq!(eval("\"日本語の本\".lastIndexOf(\"本\")"), PklValue::Int(4));
+        assert_eq!(
+            eval("\"日本語の本\".indexOfOrNull(\"の\")"),
+            PklValue::Int(3)
+        );
+        assert_eq!(
+            eval("\"日本語の本\".lastIndexOfOrNull(\"語\")"),
+            PklValue::Int(2)
+        );
+    }
+
+    #[test]
+    fn drop_methods_count_codepoints() {
+        assert_eq!(eval("\"héllo\".drop(2)"), string("llo"));
+        assert_eq!(eval("\"héllo\".drop(5)"), string(""));
+        assert_eq!(eval("\"héllo\".drop(9)"), string(""));
+        assert_eq!(eval("\"héllo\".dropLast(3)"), string("hé"));
+        assert_eq!(eval("\"héllo\".dropLast(9)"), string(""));
+    }
+
+    #[test]
+    fn replace_range_rejects_invalid_ranges() {
+        let error = eval_err("\"héllo\".replaceRange(1, 6, \"\")");
+        assert_eq!(
+            error.msg(),
+            "exclusiveEnd index 6 is out of bounds, expected a value between 0 and 5"
+        );
+
+        let error = eval_err("\"héllo\".replaceRange(3, 1, \"\")");
+        assert_eq!(
+            error.msg(),
+            "exclusiveEnd index 1 is lower than start index 3"
+        );
+    }
+
+    #[test]
+    fn take_and_take_last_count_codepoints() {
+        assert_eq!(eval("\"abc\".take(10)"), string("abc"));
+        assert_eq!(eval("\"abc\".takeLast(10)"), string("abc"));
+        assert_eq!(eval("\"héllo\".take(2)"), string("hé"));
+        assert_eq!(eval("\"日本語\".takeLast(2)"), string("本語"));
+        assert_eq!(eval("\"abc\".take(0)"), string(""));
+    }
+
+    #[test]
+    fn take_and_take_last_reject_negative_counts() {
+        let error = eval_err("\"abc\".take(-1)");
+        assert_eq!(error.msg(), "Cannot use take method with a negative index");
+
+        let error = eval_err("\"abc\".takeLast(-1)");
+        assert_eq!(
+            error.msg(),
+            "Cannot use takeLast method with a negative index"
+        );
+    }
+
+    #[test]
+    fn strings_are_converted_to_durations_and_data_sizes() {
+        assert_eq!(eval("\"30s\".toDuration() == 30.s"), PklValue::Bool(true));
+        assert_eq!(eval("\"1.5h\".toDuration() == 1.5.h"), PklValue::Bool(true));
+        assert_eq!(
+            eval("\"256mib\".toDataSize() == 256.mib"),
+            PklValue::Bool(true)
+        );
+        assert_eq!(
+            eval("\"1_000 b\".toDataSize() == 1000.b"),
+            PklValue::Bool(true)
+        );
+    }
+
+    #[test]
+    fn strings_with_an_invalid_unit_or_value_are_not_converted() {
+        let error = eval_err("\"30parsecs\".toDuration()");
+        assert_eq!(
+            error.msg(),
+            "Cannot convert '30parsecs' to Duration, durationUnit 'parsecs' is not valid"
+        );
+
+        let error = eval_err("\"mib\".toDataSize()");
+        assert_eq!(
+            error.msg(),
+            "Cannot convert 'mib' to DataSize, expected a number followed by a data size unit"
+        );
+    }
+
+    #[test]
+    fn trim_accepts_a_set_of_characters() {
+        assert_eq!(eval("\"xxabcxx\".trim(\"x\")"), string("abc"));
+        assert_eq!(eval("\"xyabcyx\".trimStart(\"xy\")"), string("abcyx"));
+        assert_eq!(eval("\"xyabcyx\".trimEnd(\"xy\")"), string("xyabc"));
+        assert_eq!(eval("\"  abc  \".trim(\"\")"), string("abc"));
+        assert_eq!(eval("\"  abc  \".trim()"), string("abc"));
+    }
+
+    #[test]
+    fn capitalize_uses_the_full_case_mappings() {
+        assert_eq!(eval("\"ßa\".capitalize()"), string("SSa"));
+        assert_eq!(eval("\"éa\".capitalize()"), string("Éa"));
+        assert_eq!(eval("\"ÉA\".decapitalize()"), string("éA"));
+        assert_eq!(eval("\"\".capitalize()"), string(""));
+    }
+
+    #[test]
+    fn case_predicates_ignore_characters_without_a_case() {
+        assert_eq!(eval("\"ABC 1\".isUpperCase()"), PklValue::Bool(true));
+        assert_eq!(eval("\"ÀÉ\".isUpperCase()"), PklValue::Bool(true));
+        assert_eq!(eval("\"aBc\".isUpperCase()"), PklValue::Bool(false));
+        assert_eq!(eval("\"aBc\".isLowerCase()"), PklValue::Bool(false));
+        assert_eq!(eval("\"straße\".isLowerCase()"), PklValue::Bool(true));
+    }
+
+    #[test]
+    fn padding_is_measured_in_codepoints() {
+        assert_eq!(eval(r#""ab".padStart(5, "*")"#), string("***ab"));
+        assert_eq!(eval(r#""ab".padEnd(5, "*")"#), string("ab***"));
+        assert_eq!(eval(r#""ab".padStart(4, "→")"#), string("→→ab"));
+        assert_eq!(eval(r#""ab".padEnd(4, "→")"#), string("ab→→"));
+        assert_eq!(eval(r#""hé".padStart(3, "→")"#), string("→hé"));
+        assert_eq!(eval(r#""héllo".padEnd(3, "-")"#), string("héllo"));
+
+        assert_eq!(eval(r#""ab".rjust(3, " ")"#), string(" ab"));
+        assert_eq!(eval(r#""ab".ljust(3, " ")"#), string("ab "));
+
+        assert_eq!(
+            eval_err(r#""ab".padStart(4, "→→")"#).msg(),
+            "padStart expects a Char (String(length = 1)), found String"
+        );
+    }
+
+    #[test]
+    fn substring_slices_codepoints_up_to_the_end() {
+        assert_eq!(eval(r#""hello".substring(0, 5)"#), string("hello"));
+        assert_eq!(eval(r#""hello".substring(1, 5)"#), string("ello"));
+        assert_eq!(eval(r#""hello".substring(5, 5)"#), string(""));
+        assert_eq!(
+            eval_err(r#""hello".substring(0, 6)"#).msg(),
+            "exclusiveEnd index is out of bound"
+        );
+
+        assert_eq!(eval(r#""héllo".substring(0, 5)"#), string("héllo"));
+        assert_eq!(eval(r#""héllo".substring(1, 3)"#), string("él"));
+        assert_eq!(
+            eval_err(r#""héllo".substring(0, 6)"#).msg(),
+            "exclusiveEnd index is out of bound"
+        );
+
+        assert_eq!(eval(r#""héllo".substringOrNull(0, 2)"#), string("hé"));
+        assert_eq!(eval(r#""héllo".substringOrNull(2, 5)"#), string("llo"));
+        assert_eq!(eval(r#""héllo".substringOrNull(0, 6)"#), PklValue::Null);
+        assert_eq!(eval(r#""héllo".substringOrNull(-1, 2)"#), PklValue::Null);
+    }
+
+    #[test]
+    fn length_counts_codepoints() {
+        assert_eq!(eval(r#""héllo".length"#), PklValue::Int(5));
+        assert_eq!(eval(r#""héllo".lastIndex"#), PklValue::Int(4));
+        assert_eq!(eval(r#""".lastIndex"#), PklValue::Int(-1));
+    }
+
+    #[test]
+    fn count_optionally_includes_overlapping_occurrences() {
+        assert_eq!(eval("\"aaaa\".count(\"aa\")"), PklValue::Int(2));
+        assert_eq!(eval("\"aaaa\".count(\"aa\", true)"), PklValue::Int(3));
+        assert_eq!(eval("\"abcabc\".count(\"bc\", false)"), PklValue::Int(2));
+        assert_eq!(eval("\"abc\".count(\"x\", true)"), PklValue::Int(0));
+        assert!(eval_err("\"abc\".count(\"\")").msg().contains("empty"));
+    }
+}
@@ -3,18 +3,26 @@ use crate::parser::{
     statement::class::{ClassDeclaration, ClassField},
     Identifier,
 };
+use crate::{PklError, PklResult};
 use hashbrown::HashMap;
 
 pub type ClassSchema = HashMap<String, PklType>;
 
 pub fn generate_class_schema(
     ClassDeclaration { name, fields, .. }: ClassDeclaration<'_>,
-) -> (Identifier<'_>, ClassSchema) {
+) -> PklResult<(Identifier<'_>, ClassSchema)> {
     let mut types = HashMap::new();
 
     for (ClassField { name, kind, .. }, _type) in fields {
-        types.insert(name.to_owned(), _type.into());
+        if let Some(span) = _type.requirement_span() {
+            return Err(unsupported_requirement_err(span));
+        }
+        types.insert(name.into_owned(), _type.into());
     }
 
-    (name, types)
+    Ok((name, types))
+}
+
+pub fn unsupported_requirement_err(span: logos::Span) -> PklError {
+    PklError::unsupported("Type constraints are not yet supported", span)
 }
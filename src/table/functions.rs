@@ -0,0 +1,44 @@
+use super::value::PklValue;
+use crate::PklResult;
+use hashbrown::HashMap;
+use std::{fmt, sync::Arc};
+
+/// A function implemented in Rust and called from Pkl, see `Pkl::register_fn`.
+pub type NativeFn = Arc<dyn Fn(&[PklValue]) -> PklResult<PklValue> + Send + Sync>;
+
+/// The functions registered by the embedder, by name,
+/// consulted when a called function is not a builtin one.
+#[derive(Clone, Default)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, NativeFn>,
+}
+
+impl FunctionRegistry {
+    /// Registers a function, replacing any function previously registered under the same name.
+    pub fn register(&mut self, name: impl Into<String>, f: NativeFn) {
+        self.functions.insert(name.into(), f);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NativeFn> {
+        self.functions.get(name)
+    }
+}
+
+impl fmt::Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.functions.keys()).finish()
+    }
+}
+
+/// Two registries are equal when they hold the very same functions under the same names.
+impl PartialEq for FunctionRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.functions.len() == other.functions.len()
+            && self.functions.iter().all(|(name, f)| {
+                other
+                    .functions
+                    .get(name)
+                    .is_some_and(|other_f| Arc::ptr_eq(f, other_f))
+            })
+    }
+}
@@ -1,41 +1,79 @@
-use super::{PklMember, PklTable};
-use crate::PklResult;
+use super::{FunctionRegistry, PklMember, PklTable, TableSettings};
 use crate::{lexer::IsValidPkl, Pkl};
+use crate::{PklError, PklResult};
 use hashbrown::HashMap;
 use logos::Span;
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 pub mod official;
 pub mod web;
 
-#[derive(Debug, Clone, Default)]
-pub struct Importer;
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Importer {
+    // sources registered by name, imported with the `mem:` scheme
+    virtual_modules: HashMap<String, String>,
+    // the modules being read by imports, amends and extends,
+    // as (resolved id, uri as written) pairs, used to detect cycles
+    visiting: Vec<(String, String)>,
+    // the directory relative file paths are resolved against,
+    // the current directory when not set
+    base_dir: Option<PathBuf>,
+}
 
 impl Importer {
+    /// Registers a virtual module, its source is only parsed when imported as `mem:{name}`.
+    pub fn register_module(&mut self, name: impl Into<String>, source: impl Into<String>) {
+        self.virtual_modules.insert(name.into(), source.into());
+    }
+
+    /// Sets the directory relative file paths are resolved against.
+    ///
+    /// The files read from it resolve their own imports relative to their directory.
+    pub fn set_base_dir(&mut self, base_dir: impl Into<PathBuf>) {
+        self.base_dir = Some(base_dir.into());
+    }
+
+    /// Returns the name a module is imported under, the last segment of its uri
+    /// without the `.pkl` extension, wrapped in backticks when it is not a valid identifier.
     pub fn construct_name_from_uri(uri: &str) -> String {
-        let prefix_removed = uri
-            .strip_prefix("http:|https:|pkl:|package:")
-            .unwrap_or(uri);
-        let suffix_removed = prefix_removed
-            .strip_suffix(".pkl")
-            .unwrap_or(prefix_removed);
-
-        let mut name = String::from(suffix_removed.split('/').last().unwrap());
-
-        if !name.is_valid_pkl_id() {
-            name = name + "`";
-            name.push('`');
+        let name = Self::module_id_from_uri(uri);
+
+        if name.is_valid_pkl_id() {
+            name.to_owned()
+        } else {
+            format!("`{}`", name)
         }
+    }
+
+    /// Returns the name of a module without any backticks, that is the name
+    /// it is stored under as backticks are stripped from identifiers when lexing.
+    pub fn module_id_from_uri(uri: &str) -> &str {
+        let suffix_removed = uri.strip_suffix(".pkl").unwrap_or(uri);
 
-        name
+        suffix_removed
+            .rsplit(['/', ':'])
+            .next()
+            .unwrap_or(suffix_removed)
     }
 
-    pub fn import(&mut self, module_uri: &str, span: Span) -> PklResult<PklTable> {
+    pub fn import(
+        &mut self,
+        module_uri: &str,
+        span: Span,
+        settings: TableSettings,
+        functions: &FunctionRegistry,
+    ) -> PklResult<PklTable> {
         let mut imported_table = match module_uri {
             uri if uri.starts_with("package://") => web::import_pkg(uri, span)?,
             uri if uri.starts_with("pkl:") => official::import_pkg(uri, span)?,
             uri if uri.starts_with("https://") => web::import_http(uri, span)?,
-            file_path => self.read_file_as_table(file_path, span)?,
+            uri if uri.starts_with("mem:") => {
+                self.read_virtual_module_as_table(uri, span, settings, functions)?
+            }
+            file_path => self.read_file_as_table(file_path, span, settings, functions)?,
         };
 
         imported_table.members.retain(|_, v| !v.is_local());
@@ -46,12 +84,21 @@ impl Importer {
     /// Generates the amended table
     /// - removes the parsed local items
     /// - set all items as amended
-    pub fn amends(&mut self, module_uri: &str, span: Span) -> PklResult<PklTable> {
+    pub fn amends(
+        &mut self,
+        module_uri: &str,
+        span: Span,
+        settings: TableSettings,
+        functions: &FunctionRegistry,
+    ) -> PklResult<PklTable> {
         let mut amended_table = match module_uri {
             uri if uri.starts_with("package://") => web::amends_pkg(uri, span)?,
             uri if uri.starts_with("pkl:") => official::amends_pkg(uri, span)?,
             uri if uri.starts_with("https://") => web::amends_http(uri, span)?,
-            file_path => self.read_file_as_table(file_path, span)?,
+            uri if uri.starts_with("mem:") => {
+                self.read_virtual_module_as_table(uri, span, settings, functions)?
+            }
+            file_path => self.read_file_as_table(file_path, span, settings, functions)?,
         };
 
         amended_table.members.retain(|_, v| {
@@ -65,12 +112,21 @@ impl Importer {
     /// Generates the extended table
     /// - removes the parsed local items
     /// - set all items as extended
-    pub fn extends(&mut self, module_uri: &str, span: Span) -> PklResult<PklTable> {
+    pub fn extends(
+        &mut self,
+        module_uri: &str,
+        span: Span,
+        settings: TableSettings,
+        functions: &FunctionRegistry,
+    ) -> PklResult<PklTable> {
         let mut extended_table = match module_uri {
             uri if uri.starts_with("package://") => web::extends_pkg(uri, span)?,
             uri if uri.starts_with("pkl:") => official::extends_pkg(uri, span)?,
             uri if uri.starts_with("https://") => web::extends_http(uri, span)?,
-            file_path => self.read_file_as_table(file_path, span)?,
+            uri if uri.starts_with("mem:") => {
+                self.read_virtual_module_as_table(uri, span, settings, functions)?
+            }
+            file_path => self.read_file_as_table(file_path, span, settings, functions)?,
         };
 
         extended_table.members.retain(|_, v| {
@@ -81,13 +137,92 @@ impl Importer {
         Ok(extended_table)
     }
 
-    fn read_file_as_table(&mut self, path_as_str: &str, span: Span) -> PklResult<PklTable> {
-        // check for circular imports, amends and extends expr
+    fn read_file_as_table(
+        &mut self,
+        path_as_str: &str,
+        span: Span,
+        settings: TableSettings,
+        functions: &FunctionRegistry,
+    ) -> PklResult<PklTable> {
+        let path = match &self.base_dir {
+            Some(base_dir) => base_dir.join(path_as_str),
+            None => PathBuf::from(path_as_str),
+        };
+
+        let content = self.file_content(&path, span.to_owned())?;
+        let id = fs::canonicalize(&path)
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|_| path_as_str.to_owned());
+
+        self.visit(id, path_as_str, span.to_owned())?;
+        let mut importer = self.to_owned();
+        if self.base_dir.is_some() {
+            importer.base_dir = path.parent().map(Path::to_path_buf);
+        }
+        let table = importer
+            .parse_as_table(&content, settings, functions)
+            .map_err(|e| e.imported_from(path_as_str.to_owned(), content, span));
+        self.visiting.pop();
+
+        table
+    }
+
+    fn read_virtual_module_as_table(
+        &mut self,
+        uri: &str,
+        span: Span,
+        settings: TableSettings,
+        functions: &FunctionRegistry,
+    ) -> PklResult<PklTable> {
+        let name = &uri["mem:".len()..];
+        let content = match self.virtual_modules.get(name) {
+            Some(content) => content.to_owned(),
+            None => {
+                return Err((format!("Virtual module `{}` is not registered", name), span).into())
+            }
+        };
+
+        self.visit(uri.to_owned(), uri, span.to_owned())?;
+        let table = self
+            .parse_as_table(&content, settings, functions)
+            .map_err(|e| e.imported_from(uri.to_owned(), content, span));
+        self.visiting.pop();
+
+        table
+    }
 
-        let content = self.file_content(&path_as_str, span.to_owned())?;
+    /// Marks a module as being read, the same guard is shared by imports, amends and extends
+    /// so that a module referencing itself, even transitively, is reported instead of looping forever.
+    fn visit(&mut self, id: String, uri: &str, span: Span) -> PklResult<()> {
+        if let Some(start) = self.visiting.iter().position(|(visited, _)| *visited == id) {
+            let cycle = self.visiting[start..]
+                .iter()
+                .map(|(_, uri)| uri.as_str())
+                .chain(std::iter::once(uri))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            return Err((format!("Cyclic module reference: {}", cycle), span).into());
+        }
+
+        self.visiting.push((id, uri.to_owned()));
+        Ok(())
+    }
+
+    /// Parses a module with the settings and the registered functions of the importing one,
+    /// the virtual modules remaining available to its own imports.
+    fn parse_as_table(
+        &self,
+        content: &str,
+        settings: TableSettings,
+        functions: &FunctionRegistry,
+    ) -> PklResult<PklTable> {
         let mut pkl = Pkl::new();
+        pkl.importer = self.to_owned();
+        pkl.settings = settings;
+        pkl.functions = functions.to_owned();
 
-        pkl.parse(&content)?;
+        pkl.parse(content)?;
         let table = pkl.table;
 
         Ok(table)
@@ -96,8 +231,137 @@ impl Importer {
     fn file_content(&self, file_path: impl AsRef<Path>, span: Span) -> PklResult<String> {
         let path = file_path.as_ref();
         let file_content = fs::read_to_string(path)
-            .map_err(|e| (format!("Error reading {}: {}", path.display(), e), span))?;
+            .map_err(|e| PklError::unreadable(path.display(), e, Some(span)))?;
 
         Ok(file_content)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Importer;
+    use crate::{test_utils::parse_err, Pkl, PklValue};
+
+    #[test]
+    fn names_that_are_not_identifiers_are_wrapped_in_backticks_once() {
+        assert_eq!(
+            Importer::construct_name_from_uri("dir/my-module.pkl"),
+            "`my-module`"
+        );
+        assert_eq!(Importer::construct_name_from_uri("pkl:math"), "math");
+        assert_eq!(
+            Importer::module_id_from_uri("dir/my-module.pkl"),
+            "my-module"
+        );
+    }
+
+    #[test]
+    fn modules_with_invalid_identifier_names_are_accessed_with_backticks() {
+        let mut pkl = Pkl::new();
+        pkl.register_module("my-module", "x = 1");
+
+        let result = pkl.parse("import \"mem:my-module\"\ny = `my-module`.x");
+        assert!(result.is_ok(), "{}", result.err().unwrap().msg());
+        assert_eq!(pkl.get_value("y"), Some(PklValue::Int(1)));
+    }
+
+    #[test]
+    fn unreadable_imported_files_are_reported_on_the_import() {
+        let source = "import \"missing_module.pkl\"";
+        let error = parse_err(source);
+
+        assert!(error
+            .msg()
+            .starts_with("Error reading missing_module.pkl: "));
+        assert_eq!(error.span(), Some(0..source.len()));
+    }
+
+    #[test]
+    fn registered_virtual_modules_are_imported_and_amended() {
+        let mut pkl = Pkl::new();
+        pkl.register_module("base", "x = 1\ny = 2");
+
+        assert!(pkl
+            .parse("import \"mem:base\"\nz = base.x + base.y")
+            .is_ok());
+        assert_eq!(pkl.get_value("z"), Some(PklValue::Int(3)));
+
+        let mut amending = Pkl::new();
+        amending.register_module("base", "x = 1\ny = 2");
+        assert!(amending.parse("amends \"mem:base\"\ny = 3").is_ok());
+        assert_eq!(amending.get_value("x"), Some(PklValue::Int(1)));
+        assert_eq!(amending.get_value("y"), Some(PklValue::Int(3)));
+    }
+
+    #[test]
+    fn imported_modules_share_the_functions_and_settings_of_the_importer() {
+        let mut pkl = Pkl::new();
+        pkl.register_fn("double", |args| match args {
+            [PklValue::Int(x)] => Ok(PklValue::Int(x * 2)),
+            _ => Ok(PklValue::Null),
+        });
+        pkl.register_module("doubled", "x = double(21)");
+
+        let result = pkl.parse("import \"mem:doubled\"\ny = doubled.x");
+        assert!(result.is_ok(), "{}", result.err().unwrap().msg());
+        assert_eq!(pkl.get_value("y"), Some(PklValue::Int(42)));
+
+        let mut lenient = Pkl::new();
+        lenient.set_lenient_instances(true);
+        lenient.register_module(
+            "servers",
+            "class Server {\n  host: String\n}\ns = new Server {}",
+        );
+        let result = lenient.parse("import \"mem:servers\"");
+        assert!(result.is_ok(), "{}", result.err().unwrap().msg());
+
+        let mut strict = Pkl::new();
+        strict.set_deny_duplicate_imports(true);
+        strict.register_module("base", "x = 1");
+        strict.register_module("twice", "import \"mem:base\"\nimport \"mem:base\"");
+        let error = strict.parse("import \"mem:twice\"").err().unwrap();
+        assert_eq!(error.msg(), "Module `mem:base` is already imported");
+    }
+
+    #[test]
+    fn unregistered_virtual_modules_are_reported() {
+        let error = parse_err("import \"mem:missing\"");
+        assert_eq!(error.msg(), "Virtual module `missing` is not registered");
+    }
+
+    #[test]
+    fn modules_amending_each_other_are_reported_as_a_cycle() {
+        let mut pkl = Pkl::new();
+        pkl.register_module("a", "amends \"mem:b\"\nx = 1");
+        pkl.register_module("b", "extends \"mem:a\"\ny = 2");
+
+        let error = pkl.parse("amends \"mem:a\"").err().unwrap();
+        assert_eq!(
+            error.msg(),
+            "Cyclic module reference: mem:a -> mem:b -> mem:a"
+        );
+        assert_eq!(error.import_chain(), ["mem:b", "mem:a"]);
+    }
+
+    #[test]
+    fn imported_classes_are_instantiated_through_the_import_name() {
+        let mut pkl = Pkl::new();
+        pkl.register_module("shapes", "class Circle {\n  radius: Float\n}\nunit = 1.0");
+
+        let source = "import \"mem:shapes\" as s\nc = new s.Circle { radius = s.unit }";
+        if let Err(e) = pkl.parse(source) {
+            panic!("{}", e.msg());
+        }
+
+        assert_eq!(
+            pkl.get_class_instance("c").ok(),
+            Some((
+                "s.Circle".to_owned(),
+                [("radius".to_owned(), PklValue::Float(1.0))]
+                    .into_iter()
+                    .collect()
+            ))
+        );
+        assert!(pkl.get_schema("s.Circle").is_some());
+    }
+}
@@ -1,5 +1,5 @@
-use crate::PklResult;
 use crate::PklTable;
+use crate::{PklError, PklResult};
 use logos::Span;
 
 mod math;
@@ -30,24 +30,21 @@ pub fn import_pkg(pkg_uri: &str, span: Span) -> PklResult<PklTable> {
         _ => return Err((format!("Unknow Pkl Package '{pkg_uri}'"), span).into()),
     };
 
-    return Err((
-        "Pkl official packages imports not yet supported!".to_owned(),
+    return Err(PklError::unsupported(
+        "Pkl official packages imports not yet supported!",
         span,
-    )
-        .into());
+    ));
 }
 
 pub fn amends_pkg(pkg_uri: &str, span: Span) -> PklResult<PklTable> {
-    return Err((
-        "Pkl official packages amending not yet supported!".to_owned(),
+    return Err(PklError::unsupported(
+        "Pkl official packages amending not yet supported!",
         span,
-    )
-        .into());
+    ));
 }
 pub fn extends_pkg(pkg_uri: &str, span: Span) -> PklResult<PklTable> {
-    return Err((
-        "Pkl official packages extending not yet supported!".to_owned(),
+    return Err(PklError::unsupported(
+        "Pkl official packages extending not yet supported!",
         span,
-    )
-        .into());
+    ));
 }
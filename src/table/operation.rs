@@ -0,0 +1,241 @@
+use crate::{parser::expr::operation::Operator, PklError, PklResult, PklValue};
+use std::ops::Range;
+
+/// Applies a binary operator on two already evaluated operands.
+///
+/// Logical operators and the null coalescing operator are not handled
+/// here as they need to short-circuit, see `PklTable::evaluate_operation`.
+pub fn match_operation(
+    left: PklValue,
+    op: Operator,
+    right: PklValue,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    match op {
+        // equality is defined between any two values, see `PklValue::structurally_eq`
+        Operator::Equal => Ok(left.structurally_eq(&right).into()),
+        Operator::NotEqual => Ok((!left.structurally_eq(&right)).into()),
+        Operator::LessThan | Operator::LessOrEqual | Operator::MoreThan | Operator::MoreOrEqual => {
+            compare(left, op, right, range)
+        }
+        Operator::Add => match (left, right) {
+            (PklValue::Int(a), PklValue::Int(b)) => a
+                .checked_add(b)
+                .map(PklValue::Int)
+                .ok_or_else(|| overflow_err(op, range)),
+            (PklValue::String(a), PklValue::String(b)) => Ok(PklValue::String(a + &b)),
+            (PklValue::List(mut a), PklValue::List(b)) => {
+                a.extend(b);
+                Ok(PklValue::List(a))
+            }
+            (left, right) => float_operation(left, op, right, range, |a, b| a + b),
+        },
+        Operator::Sub => match (left, right) {
+            (PklValue::Int(a), PklValue::Int(b)) => a
+                .checked_sub(b)
+                .map(PklValue::Int)
+                .ok_or_else(|| overflow_err(op, range)),
+            (left, right) => float_operation(left, op, right, range, |a, b| a - b),
+        },
+        Operator::Mul => match (left, right) {
+            (PklValue::Int(a), PklValue::Int(b)) => a
+                .checked_mul(b)
+                .map(PklValue::Int)
+                .ok_or_else(|| overflow_err(op, range)),
+            (left, right) => float_operation(left, op, right, range, |a, b| a * b),
+        },
+        Operator::Div => float_operation(left, op, right, range, |a, b| a / b),
+        Operator::IntDiv => match (left, right) {
+            (PklValue::Int(_), PklValue::Int(0)) => {
+                Err(("Integer division by zero".to_owned(), range).into())
+            }
+            (PklValue::Int(a), PklValue::Int(b)) => a
+                .checked_div(b)
+                .map(PklValue::Int)
+                .ok_or_else(|| overflow_err(op, range)),
+            (left, right) => {
+                let result = float_operation(left, op, right, range.to_owned(), |a, b| a / b)?;
+                match result {
+                    PklValue::Float(f) if f.is_finite() => Ok(PklValue::Int(f.trunc() as i64)),
+                    _ => Err(("Integer division by zero".to_owned(), range).into()),
+                }
+            }
+        },
+        Operator::Modulo => match (left, right) {
+            (PklValue::Int(_), PklValue::Int(0)) => {
+                Err(("Integer division by zero".to_owned(), range).into())
+            }
+            (PklValue::Int(a), PklValue::Int(b)) => a
+                .checked_rem(b)
+                .map(PklValue::Int)
+                .ok_or_else(|| overflow_err(op, range)),
+            (left, right) => float_operation(left, op, right, range, |a, b| a % b),
+        },
+        Operator::Pow => match (left, right) {
+            (PklValue::Int(a), PklValue::Int(b)) if b >= 0 => u32::try_from(b)
+                .ok()
+                .and_then(|b| a.checked_pow(b))
+                .map(PklValue::Int)
+                .ok_or_else(|| overflow_err(op, range)),
+            (left, right) => float_operation(left, op, right, range, f64::powf),
+        },
+        Operator::And | Operator::Or | Operator::NullCoalescing => {
+            unreachable!("short-circuiting operators are evaluated by the table")
+        }
+    }
+}
+
+/// Applies the unary `-` on an already evaluated operand,
+/// only numbers, durations and data sizes can be negated.
+pub fn negate(value: PklValue, range: Range<usize>) -> PklResult<PklValue> {
+    match value {
+        PklValue::Duration(duration) => Ok(PklValue::Duration(duration.negated())),
+        PklValue::DataSize(size) => Ok(PklValue::DataSize(size.negated())),
+        PklValue::Int(i) => i
+            .checked_neg()
+            .map(PklValue::Int)
+            .ok_or_else(|| overflow_err(Operator::Sub, range)),
+        PklValue::Float(f) => Ok(PklValue::Float(-f)),
+        value => Err(PklError::type_mismatch(
+            format!(
+                "Operator `-` cannot be applied to an operand of type {}",
+                value.describe_type()
+            ),
+            range,
+        )),
+    }
+}
+
+/// Unlike equality, ordering is only defined between numbers, between strings,
+/// compared lexicographically by codepoint, between durations and between data sizes,
+/// other operands being an error.
+fn compare(
+    left: PklValue,
+    op: Operator,
+    right: PklValue,
+    range: Range<usize>,
+) -> PklResult<PklValue> {
+    let ordering = match (&left, &right) {
+        (PklValue::Int(a), PklValue::Int(b)) => a.partial_cmp(b),
+        // the byte order of UTF-8 strings is their codepoint order
+        (PklValue::String(a), PklValue::String(b)) => a.partial_cmp(b),
+        (PklValue::Duration(a), PklValue::Duration(b)) => a.partial_cmp(b),
+        (PklValue::DataSize(a), PklValue::DataSize(b)) => a.partial_cmp(b),
+        (a, b) if a.is_number() && b.is_number() => {
+            a.as_number().unwrap().partial_cmp(&b.as_number().unwrap())
+        }
+        _ => return Err(operands_err(&left, op, &right, range)),
+    };
+
+    let result = match ordering {
+        // NaN is never ordered
+        None => false,
+        Some(ordering) => match op {
+            Operator::LessThan => ordering.is_lt(),
+            Operator::LessOrEqual => ordering.is_le(),
+            Operator::MoreThan => ordering.is_gt(),
+            Operator::MoreOrEqual => ordering.is_ge(),
+            _ => unreachable!(),
+        },
+    };
+
+    Ok(result.into())
+}
+
+fn float_operation(
+    left: PklValue,
+    op: Operator,
+    right: PklValue,
+    range: Range<usize>,
+    action: impl Fn(f64, f64) -> f64,
+) -> PklResult<PklValue> {
+    match (left.as_number(), right.as_number()) {
+        (Some(a), Some(b)) => Ok(PklValue::Float(action(a, b))),
+        _ => Err(operands_err(&left, op, &right, range)),
+    }
+}
+
+fn operands_err(left: &PklValue, op: Operator, right: &PklValue, range: Range<usize>) -> PklError {
+    PklError::type_mismatch(
+        format!(
+            "Operator `{op}` cannot be applied to operands of type {} and {}",
+            left.describe_type(),
+            right.describe_type()
+        ),
+        range,
+    )
+}
+
+fn overflow_err(op: Operator, range: Range<usize>) -> PklError {
+    (format!("Int overflow when applying operator `{op}`"), range).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{error_slice, eval, eval_err},
+        PklValue,
+    };
+
+    #[test]
+    fn non_boolean_operands_are_reported_on_their_own_span() {
+        let error = eval_err("1 && true");
+        assert_eq!(error_slice("1 && true", &error), "1");
+        assert!(error.msg().contains("Int"), "{}", error.msg());
+
+        let error = eval_err(r#"false || "x""#);
+        assert_eq!(error_slice(r#"false || "x""#, &error), r#""x""#);
+        assert!(error.msg().contains("String"), "{}", error.msg());
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        assert_eq!(eval("false && 1"), PklValue::Bool(false));
+        assert_eq!(eval(r#"true || "x""#), PklValue::Bool(true));
+        assert_eq!(eval("true && false"), PklValue::Bool(false));
+        assert_eq!(eval("false || true"), PklValue::Bool(true));
+    }
+
+    #[test]
+    fn unary_minus_negates_numbers() {
+        assert_eq!(eval("-(1 + 2)"), PklValue::Int(-3));
+        assert_eq!(eval("-(1.5)"), PklValue::Float(-1.5));
+        assert_eq!(eval("-List(1, 2).length"), PklValue::Int(-2));
+        assert_eq!(eval("-2.ms == (0 - 2).ms"), PklValue::Bool(true));
+        assert_eq!(eval("-9223372036854775808"), PklValue::Int(i64::MIN));
+        assert!(eval_err("-(-9223372036854775808)")
+            .msg()
+            .contains("overflow"));
+        assert!(eval_err(r#"-"a""#).msg().contains("String"));
+    }
+
+    #[test]
+    fn equality_is_total_while_ordering_is_type_restricted() {
+        assert_eq!(eval("1.s == 1.mb"), PklValue::Bool(false));
+        assert_eq!(eval("1.s != 1.mb"), PklValue::Bool(true));
+        assert_eq!(eval("1 == \"1\""), PklValue::Bool(false));
+
+        assert_eq!(
+            eval_err("1.s < 1.mb").msg(),
+            "Operator `<` cannot be applied to operands of type Duration and DataSize"
+        );
+        assert_eq!(
+            eval_err("1.s >= 1.mb").msg(),
+            "Operator `>=` cannot be applied to operands of type Duration and DataSize"
+        );
+    }
+
+    #[test]
+    fn strings_are_ordered_lexicographically() {
+        assert_eq!(eval(r#""abc" < "abd""#), PklValue::Bool(true));
+        assert_eq!(eval(r#""b" > "abc""#), PklValue::Bool(true));
+        assert_eq!(eval(r#""abc" <= "abc""#), PklValue::Bool(true));
+        assert_eq!(eval(r#""Z" >= "a""#), PklValue::Bool(false));
+        assert_eq!(eval(r#""é" > "z""#), PklValue::Bool(true));
+
+        assert_eq!(
+            eval_err(r#""a" < 1"#).msg(),
+            "Operator `<` cannot be applied to operands of type String and Int"
+        );
+    }
+}
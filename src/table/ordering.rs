@@ -0,0 +1,307 @@
+use crate::{
+    parser::{
+        expr::{
+            class::ClassInstance, fn_call::FuncCall, lambda::Lambda, member_expr::ExprMember,
+            operation::Operation, string::StringFragment, PklExpr,
+        },
+        statement::{property::Property, when::When, PklStatement},
+        value::AstPklValue,
+        ExprHash, Identifier, ObjectKey,
+    },
+    PklResult,
+};
+use hashbrown::HashMap;
+use std::borrow::Cow;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    Pending,
+    InProgress,
+    Done,
+}
+
+/// Reorders the top-level statements so that the properties, and the `when` statements, referencing
+/// a property or a class defined later in the file are evaluated after it,
+/// the referenced statement being moved right before the first one needing it.
+///
+/// Statements without forward references keep their order,
+/// properties referencing each other in a cycle, such as `a = b` and `b = a`, are an error.
+pub fn order_forward_references(statements: Vec<PklStatement>) -> PklResult<Vec<PklStatement>> {
+    let mut properties = HashMap::new();
+    let mut classes = HashMap::new();
+    for (index, statement) in statements.iter().enumerate() {
+        match statement {
+            PklStatement::Class(declaration) => {
+                classes.entry(declaration.name.0.clone()).or_insert(index);
+            }
+            statement => {
+                if let Some(Property { name, .. }) = top_level_property(statement) {
+                    properties.entry(name.0.clone()).or_insert(index);
+                }
+            }
+        }
+    }
+
+    // the statements each statement depends on, by index
+    let dependencies = statements
+        .iter()
+        .enumerate()
+        .map(|(index, statement)| {
+            let mut references = References::default();
+            references.collect_statement(statement);
+
+            let properties = references
+                .properties
+                .iter()
+                .filter_map(|name| properties.get(name));
+            let classes = references
+                .classes
+                .iter()
+                .filter_map(|name| classes.get(name));
+
+            // a property referencing its own name refers to the amended value
+            properties
+                .chain(classes)
+                .copied()
+                .filter(|dependency| *dependency != index)
+                .collect()
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    let mut visits = vec![Visit::Pending; statements.len()];
+    let mut order = Vec::with_capacity(statements.len());
+    let mut path = Vec::new();
+    for index in 0..statements.len() {
+        visit(
+            index,
+            &statements,
+            &dependencies,
+            &mut visits,
+            &mut path,
+            &mut order,
+        )?;
+    }
+
+    let mut statements = statements.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(order
+        .into_iter()
+        .filter_map(|index| statements[index].take())
+        .collect())
+}
+
+/// Pushes the dependencies of the statement at `index`, and then the statement itself, to `order`.
+fn visit(
+    index: usize,
+    statements: &[PklStatement],
+    dependencies: &[Vec<usize>],
+    visits: &mut [Visit],
+    path: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+) -> PklResult<()> {
+    match visits[index] {
+        Visit::Done => return Ok(()),
+        Visit::InProgress => {
+            let cycle_start = path.iter().position(|i| *i == index).unwrap_or(0);
+            let names = path[cycle_start..]
+                .iter()
+                .chain([&index])
+                .filter_map(|i| top_level_property(&statements[*i]))
+                .map(|property| property.name.value())
+                .collect::<Vec<_>>();
+            let Identifier(_, span) = &top_level_property(&statements[index])
+                .expect("only properties have dependencies")
+                .name;
+
+            return Err((
+                format!(
+                    "Cyclic reference between properties: {}",
+                    names.join(" -> ")
+                ),
+                span.to_owned(),
+            )
+                .into());
+        }
+        Visit::Pending => (),
+    }
+
+    visits[index] = Visit::InProgress;
+    path.push(index);
+    for dependency in &dependencies[index] {
+        visit(*dependency, statements, dependencies, visits, path, order)?;
+    }
+    path.pop();
+
+    visits[index] = Visit::Done;
+    order.push(index);
+    Ok(())
+}
+
+/// Returns the property a top-level statement declares, modifiers included.
+fn top_level_property<'a, 'b>(statement: &'b PklStatement<'a>) -> Option<&'b Property<'a>> {
+    match statement {
+        PklStatement::Property(property) => Some(property),
+        PklStatement::Local(statement, _)
+        | PklStatement::Const(statement, _)
+        | PklStatement::Fixed(statement, _) => match statement.as_ref() {
+            PklStatement::Property(property) => Some(property),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The names of the properties and of the classes an expression refers to.
+#[derive(Default)]
+struct References<'a> {
+    properties: Vec<Cow<'a, str>>,
+    classes: Vec<Cow<'a, str>>,
+}
+
+impl<'a> References<'a> {
+    /// Collects the references of the value of a property,
+    /// or of the condition and of both bodies of a `when` statement.
+    fn collect_statement(&mut self, statement: &PklStatement<'a>) {
+        match statement {
+            PklStatement::Property(Property {
+                value: Some(value), ..
+            }) => self.collect(value),
+            PklStatement::Local(statement, _)
+            | PklStatement::Const(statement, _)
+            | PklStatement::Fixed(statement, _) => self.collect_statement(statement),
+            PklStatement::When(When {
+                condition,
+                body,
+                else_body,
+                ..
+            }) => {
+                self.collect(condition);
+                body.iter()
+                    .chain(else_body.iter().flatten())
+                    .for_each(|statement| self.collect_statement(statement));
+            }
+            _ => {}
+        }
+    }
+
+    fn collect(&mut self, expr: &PklExpr<'a>) {
+        match expr {
+            PklExpr::Identifier(Identifier(name, _)) => self.properties.push(name.clone()),
+            PklExpr::Value(value) => self.collect_value(value),
+            PklExpr::MemberExpression(base, member, _) => {
+                self.collect(base);
+                if let ExprMember::FuncCall(FuncCall(_, args, _)) = member {
+                    args.iter().for_each(|arg| self.collect(arg));
+                }
+            }
+            PklExpr::FuncCall(FuncCall(_, args, _)) => {
+                args.iter().for_each(|arg| self.collect(arg))
+            }
+            PklExpr::Operation(Operation(left, _, right, _)) => {
+                self.collect(left);
+                self.collect(right);
+            }
+            PklExpr::Parenthesized(expr, _)
+            | PklExpr::Spread(expr, _)
+            | PklExpr::Not(expr, _)
+            | PklExpr::Negate(expr, _)
+            | PklExpr::NonNull(expr, _) => self.collect(expr),
+            PklExpr::Lambda(Lambda(parameters, body, _)) => {
+                let mut body_references = References::default();
+                body_references.collect(body);
+
+                body_references.properties.retain(|name| {
+                    !parameters
+                        .iter()
+                        .any(|Identifier(parameter, _)| parameter == name)
+                });
+                self.properties.extend(body_references.properties);
+                self.classes.extend(body_references.classes);
+            }
+        }
+    }
+
+    fn collect_value(&mut self, value: &AstPklValue<'a>) {
+        match value {
+            AstPklValue::List(elements, _) => elements.iter().for_each(|e| self.collect(e)),
+            AstPklValue::MultiLineString(fragments, _) => {
+                for fragment in fragments {
+                    if let StringFragment::Interpolation(expr) = fragment {
+                        self.collect(expr);
+                    }
+                }
+            }
+            AstPklValue::Object(body) => self.collect_body(body),
+            AstPklValue::ClassInstance(ClassInstance(class_name, body, _)) => {
+                if let Some(Identifier(name, _)) = class_name {
+                    self.classes.push(name.clone());
+                }
+                self.collect_body(body);
+            }
+            AstPklValue::AmendingObject(name, body, _) => {
+                self.properties.push(name.clone());
+                self.collect_body(body);
+            }
+            AstPklValue::AmendedObject(value, body, _) => {
+                self.collect_value(value);
+                self.collect_body(body);
+            }
+            _ => (),
+        }
+    }
+
+    /// Collects the references of the fields, elements and entries of an object body,
+    /// except the ones to its own fields.
+    fn collect_body(&mut self, (members, _, elements, _): &ExprHash<'a>) {
+        let mut body_references = References::default();
+        for (key, value) in members {
+            if let ObjectKey::Entry(key) = key {
+                body_references.collect(key);
+            }
+            body_references.collect(value);
+        }
+        elements
+            .iter()
+            .for_each(|expr| body_references.collect(expr));
+
+        body_references.properties.retain(|name| {
+            !members
+                .iter()
+                .any(|(key, _)| matches!(key, ObjectKey::Field(field) if field == name))
+        });
+        self.properties.extend(body_references.properties);
+        self.classes.extend(body_references.classes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{parse, parse_err},
+        PklValue,
+    };
+
+    #[test]
+    fn properties_are_evaluated_after_the_later_members_they_reference() {
+        let pkl = parse("a = b + 1\nb = c\nc = 1");
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(2)));
+
+        let source = "a = b\nb = a";
+        let error = parse_err(source);
+        assert_eq!(
+            error.msg(),
+            "Cyclic reference between properties: a -> b -> a"
+        );
+    }
+
+    #[test]
+    fn when_statements_are_evaluated_after_the_later_members_they_reference() {
+        let pkl = parse("when (true) {\n  a = b\n}\nb = 1");
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(1)));
+
+        let pkl = parse("when (b == 1) {\n  a = 2\n}\nb = 1");
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(2)));
+
+        let pkl = parse("when (false) {\n  a = 0\n} else {\n  a = b\n}\nb = 5");
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(5)));
+    }
+}
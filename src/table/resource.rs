@@ -0,0 +1,344 @@
+use super::value::PklValue;
+use crate::PklResult;
+use base64::prelude::*;
+use indexmap::IndexMap;
+use logos::Span;
+use std::{env, fs};
+
+/// The formats a resource can be read as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResourceFormat {
+    Yaml,
+    Toml,
+}
+
+#[cfg(any(feature = "yaml", feature = "toml"))]
+impl ResourceFormat {
+    fn name(&self) -> &'static str {
+        match self {
+            ResourceFormat::Yaml => "YAML",
+            ResourceFormat::Toml => "TOML",
+        }
+    }
+}
+
+/// Reads the resource located at `uri` and converts it into a `PklValue`.
+///
+/// A `file:` or `env:` resource is read as is, see [`read_text_resource`].
+/// Otherwise the format is either given by an explicit `yaml:` or `toml:` scheme,
+/// or guessed from the extension of the file.
+pub fn read_resource(uri: &str, span: Span) -> PklResult<PklValue> {
+    if uri.starts_with("file:") || uri.starts_with("env:") {
+        return read_text_resource(uri, span);
+    }
+
+    let (format, path) = resource_format(uri, span.to_owned())?;
+
+    let content = fs::read_to_string(path).map_err(|e| {
+        (
+            format!("Error reading resource `{}`: {}", uri, e),
+            span.to_owned(),
+        )
+    })?;
+
+    match format {
+        ResourceFormat::Yaml => parse_yaml(&content, uri, span),
+        ResourceFormat::Toml => parse_toml(&content, uri, span),
+    }
+}
+
+/// Reads a `file:` resource, the content of a file,
+/// or an `env:` resource, the value of an environment variable,
+/// into a `Resource` object holding its `uri`, its `text` and its `base64` encoded text.
+fn read_text_resource(uri: &str, span: Span) -> PklResult<PklValue> {
+    let text = if let Some(path) = uri.strip_prefix("file:") {
+        // both `file:path` and `file:///absolute/path` are accepted
+        let path = path.strip_prefix("//").unwrap_or(path);
+        fs::read_to_string(path).map_err(|e| {
+            (
+                format!("Error reading resource `{}`: {}", uri, e),
+                span.to_owned(),
+            )
+        })?
+    } else {
+        let name = &uri["env:".len()..];
+        env::var(name).map_err(|e| {
+            (
+                format!("Error reading resource `{}`: {}", uri, e),
+                span.to_owned(),
+            )
+        })?
+    };
+
+    let mut fields = IndexMap::with_capacity(3);
+    fields.insert("uri".to_owned(), PklValue::String(uri.to_owned()));
+    fields.insert(
+        "base64".to_owned(),
+        PklValue::String(BASE64_STANDARD.encode(&text)),
+    );
+    fields.insert("text".to_owned(), PklValue::String(text));
+
+    Ok(PklValue::Object(fields))
+}
+
+fn resource_format(uri: &str, span: Span) -> PklResult<(ResourceFormat, &str)> {
+    if let Some(path) = uri.strip_prefix("yaml:") {
+        return Ok((ResourceFormat::Yaml, path));
+    }
+    if let Some(path) = uri.strip_prefix("toml:") {
+        return Ok((ResourceFormat::Toml, path));
+    }
+
+    let format = match uri.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("yaml") | Some("yml") => ResourceFormat::Yaml,
+        Some("toml") => ResourceFormat::Toml,
+        _ => {
+            return Err((
+                format!(
+                    "Cannot read resource `{}`, expected a .yaml, .yml or .toml file or a `yaml:`, `toml:`, `file:` or `env:` scheme",
+                    uri
+                ),
+                span,
+            )
+                .into())
+        }
+    };
+
+    Ok((format, uri))
+}
+
+#[cfg(any(feature = "yaml", feature = "toml"))]
+fn parse_error(
+    format: ResourceFormat,
+    uri: &str,
+    error: impl ToString,
+    span: Span,
+) -> crate::PklError {
+    (
+        format!(
+            "Failed to parse {} resource `{}`: {}",
+            format.name(),
+            uri,
+            error.to_string()
+        ),
+        span,
+    )
+        .into()
+}
+
+#[cfg(not(feature = "yaml"))]
+fn parse_yaml(_content: &str, _uri: &str, span: Span) -> PklResult<PklValue> {
+    Err((
+        "Reading YAML resources requires the `yaml` feature".to_owned(),
+        span,
+    )
+        .into())
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml(content: &str, uri: &str, span: Span) -> PklResult<PklValue> {
+    let value: serde_yaml::Value = serde_yaml::from_str(content)
+        .map_err(|e| parse_error(ResourceFormat::Yaml, uri, e, span.to_owned()))?;
+
+    yaml_to_pkl(value, uri, &span)
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_to_pkl(value: serde_yaml::Value, uri: &str, span: &Span) -> PklResult<PklValue> {
+    use serde_yaml::Value;
+
+    let value = match value {
+        Value::Null => PklValue::Null,
+        Value::Bool(b) => PklValue::Bool(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => PklValue::Int(i),
+            // u64 numbers too big for an i64 end up as floats
+            None => PklValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(s) => PklValue::String(s),
+        Value::Sequence(elements) => PklValue::List(
+            elements
+                .into_iter()
+                .map(|element| yaml_to_pkl(element, uri, span))
+                .collect::<PklResult<_>>()?,
+        ),
+        Value::Mapping(mapping) => {
+            let mut fields = indexmap::IndexMap::with_capacity(mapping.len());
+
+            for (key, value) in mapping {
+                let key = match key {
+                    Value::String(s) => s,
+                    Value::Bool(b) => b.to_string(),
+                    Value::Number(n) => n.to_string(),
+                    _ => {
+                        return Err(parse_error(
+                            ResourceFormat::Yaml,
+                            uri,
+                            "mapping keys are expected to be scalars",
+                            span.to_owned(),
+                        ))
+                    }
+                };
+
+                fields.insert(key, yaml_to_pkl(value, uri, span)?);
+            }
+
+            PklValue::Object(fields)
+        }
+        Value::Tagged(tagged) => yaml_to_pkl(tagged.value, uri, span)?,
+    };
+
+    Ok(value)
+}
+
+#[cfg(not(feature = "toml"))]
+fn parse_toml(_content: &str, _uri: &str, span: Span) -> PklResult<PklValue> {
+    Err((
+        "Reading TOML resources requires the `toml` feature".to_owned(),
+        span,
+    )
+        .into())
+}
+
+#[cfg(feature = "toml")]
+fn parse_toml(content: &str, uri: &str, span: Span) -> PklResult<PklValue> {
+    let table: toml::Table = content
+        .parse()
+        .map_err(|e| parse_error(ResourceFormat::Toml, uri, e, span))?;
+
+    Ok(toml_to_pkl(toml::Value::Table(table)))
+}
+
+#[cfg(feature = "toml")]
+fn toml_to_pkl(value: toml::Value) -> PklValue {
+    use toml::Value;
+
+    match value {
+        Value::String(s) => PklValue::String(s),
+        Value::Integer(i) => PklValue::Int(i),
+        Value::Float(f) => PklValue::Float(f),
+        Value::Boolean(b) => PklValue::Bool(b),
+        Value::Datetime(datetime) => PklValue::String(datetime.to_string()),
+        Value::Array(elements) => PklValue::List(elements.into_iter().map(toml_to_pkl).collect()),
+        Value::Table(table) => PklValue::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_pkl(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_resource;
+    use crate::{
+        test_utils::{eval, eval_err},
+        PklValue,
+    };
+    use std::{env, fs, path::PathBuf};
+
+    /// Writes `content` to a file of the temporary directory, returning its path.
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("pkl_fast_{}_{name}", std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn resources_of_unknown_formats_are_rejected() {
+        let error = read_resource("config.json", 0..1).err().unwrap();
+        assert!(error
+            .msg()
+            .starts_with("Cannot read resource `config.json`"));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_resources_are_read_into_objects() {
+        let path = temp_file("read.yaml", "name: pkl\nports:\n  - 80\n  - 443\n");
+        let value = read_resource(path.to_str().unwrap(), 0..1).ok();
+
+        let PklValue::Object(fields) = value.unwrap() else {
+            panic!("expected an object");
+        };
+        assert_eq!(fields["name"], PklValue::String("pkl".to_owned()));
+        assert_eq!(
+            fields["ports"],
+            PklValue::List(vec![PklValue::Int(80), PklValue::Int(443)])
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_parse_errors_name_the_resource() {
+        let path = temp_file("invalid.yaml", "a: [1");
+        let uri = format!("yaml:{}", path.display());
+
+        let error = read_resource(&uri, 3..7).err().unwrap();
+        assert!(error
+            .msg()
+            .starts_with(&format!("Failed to parse YAML resource `{uri}`")));
+        assert_eq!(error.span(), Some(3..7));
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    #[test]
+    fn yaml_resources_require_the_yaml_feature() {
+        let path = temp_file("feature.yaml", "a: 1");
+        let error = read_resource(path.to_str().unwrap(), 0..1).err().unwrap();
+        assert_eq!(
+            error.msg(),
+            "Reading YAML resources requires the `yaml` feature"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_resources_are_read_into_objects() {
+        let path = temp_file("read.toml", "[server]\nport = 80\n");
+        let value = read_resource(path.to_str().unwrap(), 0..1).ok();
+
+        let PklValue::Object(fields) = value.unwrap() else {
+            panic!("expected an object");
+        };
+        let PklValue::Object(server) = &fields["server"] else {
+            panic!("expected an object");
+        };
+        assert_eq!(server["port"], PklValue::Int(80));
+    }
+
+    #[test]
+    fn file_resources_expose_their_text_and_base64() {
+        let path = temp_file("read.txt", "hello");
+        let uri = format!("file:{}", path.display());
+
+        let resource = match read_resource(&uri, 0..0) {
+            Ok(PklValue::Object(fields)) => fields,
+            _ => panic!("{uri} should be read into an object"),
+        };
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(resource.get("uri"), Some(&PklValue::String(uri)));
+        assert_eq!(
+            resource.get("text"),
+            Some(&PklValue::String("hello".to_owned()))
+        );
+        assert_eq!(
+            resource.get("base64"),
+            Some(&PklValue::String("aGVsbG8=".to_owned()))
+        );
+    }
+
+    #[test]
+    fn env_resources_expose_the_variable() {
+        env::set_var("PKL_FAST_RESOURCE_TEST", "hi");
+        assert_eq!(
+            eval("read(\"env:PKL_FAST_RESOURCE_TEST\").text"),
+            PklValue::String("hi".to_owned())
+        );
+        assert!(eval_err("read(\"env:PKL_FAST_RESOURCE_MISSING\")")
+            .msg()
+            .starts_with("Error reading resource `env:PKL_FAST_RESOURCE_MISSING`"));
+    }
+}
@@ -0,0 +1,120 @@
+use super::{
+    class::ClassSchema, functions::NativeFn, PklMember, PklTable, PklValue, PklWarning,
+    TableSettings,
+};
+use hashbrown::HashMap;
+
+/// A scope in which expressions are evaluated, layering bindings,
+/// such as the parameters of a lambda, over a parent scope or a table.
+///
+/// A binding shadows the members of the same name, the lookups of other names
+/// falling through to the parent, so that nested scopes never clone the table.
+#[derive(Debug)]
+pub struct ScopedTable<'a> {
+    table: &'a PklTable,
+    parent: Option<&'a ScopedTable<'a>>,
+    bindings: HashMap<String, PklMember>,
+}
+
+impl<'a> ScopedTable<'a> {
+    /// Creates the outermost scope of a table, without any binding.
+    pub fn new(table: &'a PklTable) -> Self {
+        Self {
+            table,
+            parent: None,
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Creates a scope nested in this one, without any binding of its own.
+    pub fn child(&self) -> ScopedTable<'_> {
+        ScopedTable {
+            table: self.table,
+            parent: Some(self),
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Binds a value in this scope, shadowing any member of the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the value is bound to.
+    /// * `value` - The value to bind.
+    pub fn bind(&mut self, name: impl Into<String>, value: PklValue) {
+        self.bindings.insert(name.into(), PklMember::value(value));
+    }
+
+    /// Looks a member up in this scope, then in the enclosing ones and finally in the table.
+    pub fn get(&self, name: impl AsRef<str>) -> Option<&PklMember> {
+        let name = name.as_ref();
+
+        match self.bindings.get(name) {
+            Some(member) => Some(member),
+            None => match self.parent {
+                Some(parent) => parent.get(name),
+                None => self.table.get(name),
+            },
+        }
+    }
+
+    pub fn get_schema(&self, name: impl AsRef<str>) -> Option<ClassSchema> {
+        self.get(name)
+            .and_then(|member| member.to_owned().extract_schema())
+    }
+
+    pub fn get_value(&self, name: impl AsRef<str>) -> Option<PklValue> {
+        self.get(name)
+            .and_then(|member| member.to_owned().extract_value())
+    }
+
+    /// Returns the settings of the table the scope belongs to.
+    pub fn settings(&self) -> &TableSettings {
+        &self.table.settings
+    }
+
+    /// Looks a function registered from Rust up by name.
+    pub fn function(&self, name: &str) -> Option<&NativeFn> {
+        self.table.functions.get(name)
+    }
+
+    /// Emits a warning on the table, which is only borrowed while evaluating,
+    /// the warning being reported once the property being evaluated is handled.
+    pub fn defer_warning(&self, warning: PklWarning) {
+        self.table.deferred_warnings.borrow_mut().push(warning);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScopedTable;
+    use crate::{test_utils::parse, PklValue};
+
+    #[test]
+    fn bindings_shadow_the_members_of_enclosing_scopes() {
+        let pkl = parse("a = 1\nb = 2");
+        let mut scope = ScopedTable::new(&pkl.table);
+        scope.bind("a", PklValue::Int(10));
+
+        let mut child = scope.child();
+        child.bind("b", PklValue::Int(20));
+
+        assert_eq!(child.get_value("a"), Some(PklValue::Int(10)));
+        assert_eq!(child.get_value("b"), Some(PklValue::Int(20)));
+        assert_eq!(scope.get_value("b"), Some(PklValue::Int(2)));
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(1)));
+    }
+
+    #[test]
+    fn lookups_fall_through_to_the_table() {
+        let pkl = parse("class Point {\n  x: Int\n}\na = 1");
+        let scope = ScopedTable::new(&pkl.table);
+        let mut child = scope.child();
+        child.bind("c", PklValue::Int(3));
+
+        assert_eq!(child.get_value("a"), Some(PklValue::Int(1)));
+        assert!(child.get_schema("Point").is_some());
+        assert!(child.get_value("missing").is_none());
+        assert!(scope.get_value("c").is_none());
+    }
+}
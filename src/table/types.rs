@@ -1,4 +1,5 @@
 use crate::{parser::types::AstPklType, PklValue};
+use indexmap::IndexMap;
 // use hashbrown::HashMap;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -21,9 +22,13 @@ pub enum PklType {
 }
 
 impl PklType {
+    /// `Any` and `unknown` are top types, every value is an instance of them.
+    ///
+    /// `Nothing` on the other side is the bottom type, no value is an instance of it,
+    /// thus it is never matched by any of the `can_be_*` methods.
     pub fn can_be_any(&self) -> bool {
         match self {
-            PklType::Basic(x) if x == "Any" => true,
+            PklType::Basic(x) if x == "Any" || x == "unknown" => true,
             PklType::Union(a, b) => a.can_be_any() || b.can_be_any(),
             PklType::Nullable(a) if a.can_be_any() => true,
             _ => false,
@@ -102,6 +107,61 @@ impl PklType {
             _ => false,
         }
     }
+    pub fn can_be_set(&self, elements: &[PklValue]) -> bool {
+        match self {
+            PklType::Basic(x) if x == "Set" => true,
+            PklType::Union(a, b) => a.can_be_set(elements) || b.can_be_set(elements),
+            PklType::Nullable(x) if x.can_be_set(elements) => true,
+            PklType::WithAttributes {
+                name: x,
+                attributes,
+            } if x == "Set" => match attributes.as_slice() {
+                [element_type] => elements.iter().all(|e| e.is_instance_of(element_type)),
+                _ => false,
+            },
+            PklType::WithRequirement { base_type, .. } => base_type.can_be_set(elements),
+            x if x.can_be_collection() => true,
+            _ => false,
+        }
+    }
+    pub fn can_be_listing(&self, elements: &[PklValue]) -> bool {
+        match self {
+            PklType::Basic(x) if x == "Listing" => true,
+            PklType::Union(a, b) => a.can_be_listing(elements) || b.can_be_listing(elements),
+            PklType::Nullable(x) if x.can_be_listing(elements) => true,
+            PklType::WithAttributes {
+                name: x,
+                attributes,
+            } if x == "Listing" => match attributes.as_slice() {
+                [element_type] => elements.iter().all(|e| e.is_instance_of(element_type)),
+                _ => false,
+            },
+            PklType::WithRequirement { base_type, .. } => base_type.can_be_listing(elements),
+            x if x.can_be_object() => true,
+            _ => false,
+        }
+    }
+    pub fn can_be_mapping(&self, entries: &IndexMap<String, PklValue>) -> bool {
+        match self {
+            PklType::Basic(x) if x == "Mapping" => true,
+            PklType::Union(a, b) => a.can_be_mapping(entries) || b.can_be_mapping(entries),
+            PklType::Nullable(x) if x.can_be_mapping(entries) => true,
+            PklType::WithAttributes {
+                name: x,
+                attributes,
+            } if x == "Mapping" => match attributes.as_slice() {
+                // keys are always strings for the moment
+                [key_type, value_type] => entries.iter().all(|(key, value)| {
+                    PklValue::String(key.to_owned()).is_instance_of(key_type)
+                        && value.is_instance_of(value_type)
+                }),
+                _ => false,
+            },
+            PklType::WithRequirement { base_type, .. } => base_type.can_be_mapping(entries),
+            x if x.can_be_object() => true,
+            _ => false,
+        }
+    }
     pub fn can_be_object(&self) -> bool {
         match self {
             PklType::Basic(x) if x == "Object" => true,
@@ -255,3 +315,23 @@ impl fmt::Display for PklType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{parse, parse_err},
+        PklValue,
+    };
+
+    #[test]
+    fn unknown_accepts_any_value_and_nothing_none() {
+        let pkl = parse("a: unknown = 5\nb: unknown = \"x\"\nc: Null = null");
+        assert_eq!(pkl.get_value("a"), Some(PklValue::Int(5)));
+        assert_eq!(pkl.get_value("c"), Some(PklValue::Null));
+
+        let error = parse_err("x: Nothing = 5");
+        assert_eq!(error.code(), Some("pkl_fast::type_mismatch"));
+        let error = parse_err("x: Null = 5");
+        assert_eq!(error.code(), Some("pkl_fast::type_mismatch"));
+    }
+}
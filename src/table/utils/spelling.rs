@@ -1,3 +1,4 @@
+#[cfg(feature = "suggestions")]
 fn levenshtein_distance(a: &str, b: &str) -> usize {
     let mut costs = vec![0; b.len() + 1];
 
@@ -26,6 +27,7 @@ fn levenshtein_distance(a: &str, b: &str) -> usize {
     costs[b.len()]
 }
 
+#[cfg(feature = "suggestions")]
 fn closest_word<'a>(word: &str, word_list: &[&'a str]) -> (&'a str, usize) {
     let mut min_distance = usize::MAX;
     let mut closest = word_list[0];
@@ -41,6 +43,7 @@ fn closest_word<'a>(word: &str, word_list: &[&'a str]) -> (&'a str, usize) {
     (closest, min_distance)
 }
 
+#[cfg(feature = "suggestions")]
 pub fn check_closest_word<'a>(
     word: &'a str,
     word_list: &[&'a str],
@@ -62,3 +65,19 @@ pub fn check_closest_word<'a>(
         None
     }
 }
+
+/// Returns the word of `word_list` closest to `word`,
+/// if it is at most `threshold` edits away from it.
+#[cfg(feature = "suggestions")]
+pub fn suggest_closest_word<'a>(
+    word: &str,
+    word_list: &[&'a str],
+    threshold: usize,
+) -> Option<&'a str> {
+    if word_list.is_empty() {
+        return None;
+    }
+
+    let (closest, distance) = closest_word(word, word_list);
+    (distance <= threshold).then_some(closest)
+}
@@ -1,8 +1,13 @@
 use std::default;
+use std::mem;
 
 use super::{base::duration::Duration, types::PklType};
 use crate::values::Byte;
-use hashbrown::HashMap;
+use crate::{PklError, PklResult};
+use indexmap::IndexMap;
+
+pub mod json;
+pub(crate) mod render;
 
 /// Represents a value in the PKL format.
 ///
@@ -16,8 +21,10 @@ use hashbrown::HashMap;
 /// * `Int` - Represents an integer, which can be decimal, octal, hex, or binary.
 /// * `String` - Represents a single-line string.
 /// * `MultiLineString` - Represents a multiline string.
-/// * `Object` - Represents a nested object (Dynamic Object), which is a hashmap of key-value pairs.
+/// * `Object` - Represents a nested object (Dynamic Object), which is an ordered map of key-value pairs.
 /// * `ClassInstance` - Represents an instance of a class (Typed Object), which includes the class name and its properties.
+/// * `List` and `Set` - Represent immutable collections of elements, a set holding distinct elements.
+/// * `Listing` and `Mapping` - Represent the amendable collections of elements and of entries.
 #[derive(Debug, PartialEq, Clone, Default)]
 pub enum PklValue {
     #[default]
@@ -41,17 +48,35 @@ pub enum PklValue {
     /// A List
     List(Vec<PklValue>),
 
-    /// A nested object represented as a hashmap of key-value pairs.
+    /// A collection of distinct elements, kept in insertion order.
+    ///
+    /// It represents a [Set](https://pkl-lang.org/main/current/language-reference/index.html#sets)
+    /// in the documentation.
+    Set(Vec<PklValue>),
+
+    /// A typed collection of elements that can be amended, elements being appended.
+    ///
+    /// It represents a [Listing](https://pkl-lang.org/main/current/language-reference/index.html#listings)
+    /// in the documentation.
+    Listing(Vec<PklValue>),
+
+    /// A typed collection of entries that can be amended, entries being overridden or appended.
+    ///
+    /// It represents a [Mapping](https://pkl-lang.org/main/current/language-reference/index.html#mappings)
+    /// in the documentation.
+    Mapping(IndexMap<String, PklValue>),
+
+    /// A nested object represented as a map of key-value pairs, in declaration order.
     ///
     /// It represents a [Dynamic object](https://pkl-lang.org/main/current/language-reference/index.html#typed-objects)
     /// in the documentation.
-    Object(HashMap<String, PklValue>),
+    Object(IndexMap<String, PklValue>),
 
     /// An instance of a class, including the class name it is refering to and its properties.
     ///
     /// It represents a [Typed object](https://pkl-lang.org/main/current/language-reference/index.html#typed-objects)
     /// in the documentation.
-    ClassInstance(String, HashMap<String, PklValue>),
+    ClassInstance(String, IndexMap<String, PklValue>),
 
     /// A duration
     Duration(Duration),
@@ -72,6 +97,9 @@ impl PklValue {
             (PklValue::Int(i), t) if t.can_be_int(*i) => true,
             (PklValue::String(s), t) if t.can_be_str(s) => true,
             (PklValue::List(elements), t) if t.can_be_list(elements) => true,
+            (PklValue::Set(elements), t) if t.can_be_set(elements) => true,
+            (PklValue::Listing(elements), t) if t.can_be_listing(elements) => true,
+            (PklValue::Mapping(entries), t) if t.can_be_mapping(entries) => true,
             (PklValue::Object(_), t) if t.can_be_object() => true,
             (PklValue::Duration(_), t) if t.can_be_duration() => true,
             (PklValue::DataSize(_), t) if t.can_be_datasize() => true,
@@ -88,6 +116,9 @@ impl PklValue {
             PklValue::Int(_) => "Int",
             PklValue::String(_) => "String",
             PklValue::List(_) => "List",
+            PklValue::Set(_) => "Set",
+            PklValue::Listing(_) => "Listing",
+            PklValue::Mapping(_) => "Mapping",
             PklValue::Object(_) => "Dynamic",
             PklValue::ClassInstance(class_name, _) => &class_name,
             PklValue::Duration(_) => "Duration",
@@ -95,6 +126,240 @@ impl PklValue {
         }
     }
 
+    /// Indexes the value, an `Int` indexing a list or a listing
+    /// and a `String` indexing the fields of an object or a class instance, or the entries of a mapping.
+    ///
+    /// Returns `None` when the index is out of bounds, the field does not exist,
+    /// or the key cannot index the value.
+    pub fn index(&self, key: &PklValue) -> Option<&PklValue> {
+        match (self, key) {
+            (PklValue::List(elements), PklValue::Int(i))
+            | (PklValue::Listing(elements), PklValue::Int(i)) => {
+                usize::try_from(*i).ok().and_then(|i| elements.get(i))
+            }
+            (PklValue::Object(fields), PklValue::String(name))
+            | (PklValue::ClassInstance(_, fields), PklValue::String(name))
+            | (PklValue::Mapping(fields), PklValue::String(name)) => fields.get(name),
+            _ => None,
+        }
+    }
+
+    /// Visits the value and then all of its nested values depth-first,
+    /// that is the elements of lists, sets and listings, the fields of objects and class instances
+    /// and the values of mappings, in order.
+    pub fn walk<F: FnMut(&PklValue)>(&self, f: &mut F) {
+        f(self);
+
+        match self {
+            PklValue::List(elements) | PklValue::Set(elements) | PklValue::Listing(elements) => {
+                for element in elements {
+                    element.walk(f);
+                }
+            }
+            PklValue::Object(fields)
+            | PklValue::ClassInstance(_, fields)
+            | PklValue::Mapping(fields) => {
+                for field in fields.values() {
+                    field.walk(f);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Approximates the memory used by the value, in bytes,
+    /// that is its own size plus the heap allocated by its strings, elements and fields.
+    ///
+    /// Allocator and hash table overheads are not accounted for.
+    pub fn approx_size_bytes(&self) -> usize {
+        let heap_size = match self {
+            PklValue::String(s) => s.capacity(),
+            PklValue::List(elements) | PklValue::Set(elements) | PklValue::Listing(elements) => {
+                let unused = elements.capacity() - elements.len();
+                unused * mem::size_of::<PklValue>()
+                    + elements
+                        .iter()
+                        .map(PklValue::approx_size_bytes)
+                        .sum::<usize>()
+            }
+            PklValue::Object(fields) | PklValue::Mapping(fields) => fields_size_bytes(fields),
+            PklValue::ClassInstance(class_name, fields) => {
+                class_name.capacity() + fields_size_bytes(fields)
+            }
+            // the value a duration or a data size was written with is boxed
+            PklValue::Duration(_) | PklValue::DataSize(_) => mem::size_of::<PklValue>(),
+            _ => 0,
+        };
+
+        mem::size_of::<PklValue>() + heap_size
+    }
+
+    /// Compares two values the way Pkl's `==` does, at any depth.
+    ///
+    /// An `Int` equals the `Float` of the same value, sets are equal when they hold
+    /// the same elements whatever their order, and so are the fields of objects,
+    /// values of different types such as a `Duration` and a `DataSize` being simply unequal.
+    pub fn structurally_eq(&self, other: &PklValue) -> bool {
+        match (self, other) {
+            (PklValue::Int(a), PklValue::Float(b)) | (PklValue::Float(b), PklValue::Int(a)) => {
+                *a as f64 == *b
+            }
+            (PklValue::Set(a), PklValue::Set(b)) => {
+                a.len() == b.len() && a.iter().all(|element| contains_structurally(b, element))
+            }
+            (PklValue::List(a), PklValue::List(b))
+            | (PklValue::Listing(a), PklValue::Listing(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.structurally_eq(b))
+            }
+            (PklValue::Mapping(a), PklValue::Mapping(b))
+            | (PklValue::Object(a), PklValue::Object(b)) => fields_structurally_eq(a, b),
+            (PklValue::ClassInstance(a_name, a), PklValue::ClassInstance(b_name, b)) => {
+                a_name == b_name && fields_structurally_eq(a, b)
+            }
+            (left, right) => left == right,
+        }
+    }
+
+    /// Describes the type of the value the way it would be written in Pkl,
+    /// including the type of the elements of collections, such as `Mapping<String, Int>`.
+    ///
+    /// Collections containing elements of different types are described as `List<Mixed>`.
+    pub fn describe_type(&self) -> String {
+        self.infer_type().to_string()
+    }
+
+    /// Returns the type shared by all the elements of a list,
+    /// or `None` when the value is not a list, the list is empty
+    /// or its elements are of different types.
+    pub fn list_element_type(&self) -> Option<PklType> {
+        let PklValue::List(elements) = self else {
+            return None;
+        };
+
+        let mut element_types = elements.iter().map(PklValue::infer_type);
+        let first = element_types.next()?;
+
+        element_types.all(|t| t == first).then_some(first)
+    }
+
+    /// Infers the type of the value on a best-effort basis, such as `Int`,
+    /// `List<Int>` for a list whose elements share a type, or the class name for an instance.
+    ///
+    /// The keys of a `Mapping` are always strings, it is thus inferred as `Mapping<String, V>`.
+    pub fn infer_type(&self) -> PklType {
+        let attributes = match self {
+            PklValue::List(elements) | PklValue::Set(elements) | PklValue::Listing(elements) => {
+                vec![common_type(elements.iter())]
+            }
+            PklValue::Mapping(entries) => vec![
+                PklType::Basic("String".to_owned()),
+                common_type(entries.values()),
+            ],
+            _ => return PklType::Basic(self.get_type().to_owned()),
+        };
+
+        PklType::WithAttributes {
+            name: self.get_type().to_owned(),
+            attributes,
+        }
+    }
+
+    /// Recursively merges `other` into the value.
+    ///
+    /// Objects, and class instances, are merged field by field,
+    /// otherwise `other` wins, lists included.
+    pub fn deep_merge(self, other: PklValue) -> PklValue {
+        match (self, other) {
+            (PklValue::Object(mut fields), PklValue::Object(other_fields)) => {
+                merge_fields(&mut fields, other_fields);
+                PklValue::Object(fields)
+            }
+            (
+                PklValue::ClassInstance(_, mut fields),
+                PklValue::ClassInstance(name, other_fields),
+            ) => {
+                merge_fields(&mut fields, other_fields);
+                PklValue::ClassInstance(name, fields)
+            }
+            (_, other) => other,
+        }
+    }
+
+    /// Recursively converts the floats with a whole value, such as `2.0`, into ints.
+    ///
+    /// Floats too big to fit in an int are left untouched.
+    pub fn canonicalize_numbers(self) -> PklValue {
+        self.map_numbers(&|value| match value {
+            PklValue::Float(f)
+                if f.fract() == 0.0 && f >= i64::MIN as f64 && f < i64::MAX as f64 =>
+            {
+                PklValue::Int(f as i64)
+            }
+            value => value,
+        })
+    }
+
+    /// Recursively converts the ints into floats,
+    /// the reverse of [`PklValue::canonicalize_numbers`].
+    pub fn ints_to_floats(self) -> PklValue {
+        self.map_numbers(&|value| match value {
+            PklValue::Int(i) => PklValue::Float(i as f64),
+            value => value,
+        })
+    }
+
+    /// Applies `action` on every number, going through lists, objects and class instances.
+    fn map_numbers(self, action: &impl Fn(PklValue) -> PklValue) -> PklValue {
+        match self {
+            PklValue::Int(_) | PklValue::Float(_) => action(self),
+            PklValue::List(elements) => PklValue::List(
+                elements
+                    .into_iter()
+                    .map(|element| element.map_numbers(action))
+                    .collect(),
+            ),
+            PklValue::Set(elements) => PklValue::Set(
+                elements
+                    .into_iter()
+                    .map(|element| element.map_numbers(action))
+                    .collect(),
+            ),
+            PklValue::Listing(elements) => PklValue::Listing(
+                elements
+                    .into_iter()
+                    .map(|element| element.map_numbers(action))
+                    .collect(),
+            ),
+            PklValue::Mapping(entries) => PklValue::Mapping(map_fields_numbers(entries, action)),
+            PklValue::Object(fields) => PklValue::Object(map_fields_numbers(fields, action)),
+            PklValue::ClassInstance(name, fields) => {
+                PklValue::ClassInstance(name, map_fields_numbers(fields, action))
+            }
+            value => value,
+        }
+    }
+
+    /// Recursively converts the class instances into dynamic objects with the same fields,
+    /// used when the classes they refer to are not available, such as in a bundled module.
+    pub(crate) fn erase_classes(self) -> PklValue {
+        match self {
+            PklValue::ClassInstance(_, fields) | PklValue::Object(fields) => {
+                PklValue::Object(erase_fields_classes(fields))
+            }
+            PklValue::Mapping(entries) => PklValue::Mapping(erase_fields_classes(entries)),
+            PklValue::List(elements) => {
+                PklValue::List(elements.into_iter().map(PklValue::erase_classes).collect())
+            }
+            PklValue::Set(elements) => {
+                PklValue::Set(elements.into_iter().map(PklValue::erase_classes).collect())
+            }
+            PklValue::Listing(elements) => {
+                PklValue::Listing(elements.into_iter().map(PklValue::erase_classes).collect())
+            }
+            value => value,
+        }
+    }
+
     pub fn is_string(&self) -> bool {
         matches!(self, PklValue::String(_))
     }
@@ -135,6 +400,20 @@ impl PklValue {
         }
     }
 
+    /// Returns the string as a `&str`, or `None` if the value is not a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PklValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the string as a `&str`, failing with an error
+    /// naming the actual type of the value when it is not a `String`.
+    pub fn as_str_or_err(&self) -> PklResult<&str> {
+        self.as_str().ok_or_else(|| mismatch_err(self, "a String"))
+    }
+
     pub fn as_bool(&self) -> Option<bool> {
         if let PklValue::Bool(b) = self {
             Some(*b)
@@ -163,6 +442,21 @@ impl PklValue {
         }
     }
 
+    /// Returns the int as an `i32`, or `None` if the value is not an int or is out of range.
+    pub fn as_i32(&self) -> Option<i32> {
+        self.as_int().and_then(|i| i32::try_from(i).ok())
+    }
+
+    /// Returns the int as a `u32`, or `None` if the value is not an int or is out of range.
+    pub fn as_u32(&self) -> Option<u32> {
+        self.as_int().and_then(|i| u32::try_from(i).ok())
+    }
+
+    /// Returns the int as a `usize`, or `None` if the value is not an int or is out of range.
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_int().and_then(|i| usize::try_from(i).ok())
+    }
+
     pub fn as_list(&self) -> Option<&Vec<PklValue>> {
         if let PklValue::List(ref l) = self {
             Some(l)
@@ -171,7 +465,7 @@ impl PklValue {
         }
     }
 
-    pub fn as_object(&self) -> Option<&HashMap<String, PklValue>> {
+    pub fn as_object(&self) -> Option<&IndexMap<String, PklValue>> {
         if let PklValue::Object(ref o) = self {
             Some(o)
         } else {
@@ -194,6 +488,205 @@ impl PklValue {
             None
         }
     }
+
+    /// Converts the value into a boolean, failing with an error
+    /// naming the actual type of the value when it is not a `Boolean`.
+    pub fn try_into_bool(self) -> PklResult<bool> {
+        match self {
+            PklValue::Bool(b) => Ok(b),
+            value => Err(mismatch_err(&value, "a Boolean")),
+        }
+    }
+
+    /// Converts the value into an integer, failing with an error
+    /// naming the actual type of the value when it is not an `Int`.
+    pub fn try_into_int(self) -> PklResult<i64> {
+        match self {
+            PklValue::Int(i) => Ok(i),
+            value => Err(mismatch_err(&value, "an Int")),
+        }
+    }
+
+    /// Converts the value into a float, failing with an error
+    /// naming the actual type of the value when it is not a `Float`.
+    pub fn try_into_float(self) -> PklResult<f64> {
+        match self {
+            PklValue::Float(f) => Ok(f),
+            value => Err(mismatch_err(&value, "a Float")),
+        }
+    }
+
+    /// Converts the value into a string, failing with an error
+    /// naming the actual type of the value when it is not a `String`.
+    pub fn try_into_string(self) -> PklResult<String> {
+        match self {
+            PklValue::String(s) => Ok(s),
+            value => Err(mismatch_err(&value, "a String")),
+        }
+    }
+
+    /// Converts the value into its elements, failing with an error
+    /// naming the actual type of the value when it is not a `List`.
+    pub fn try_into_list(self) -> PklResult<Vec<PklValue>> {
+        match self {
+            PklValue::List(elements) => Ok(elements),
+            value => Err(mismatch_err(&value, "a List")),
+        }
+    }
+
+    /// Converts the value into its fields, failing with an error
+    /// naming the actual type of the value when it is not a `Dynamic` object.
+    pub fn try_into_object(self) -> PklResult<IndexMap<String, PklValue>> {
+        match self {
+            PklValue::Object(fields) => Ok(fields),
+            value => Err(mismatch_err(&value, "a Dynamic object")),
+        }
+    }
+
+    /// Converts the value into its class name and fields, failing with an error
+    /// naming the actual type of the value when it is not a class instance.
+    pub fn try_into_class_instance(self) -> PklResult<(String, IndexMap<String, PklValue>)> {
+        match self {
+            PklValue::ClassInstance(name, fields) => Ok((name, fields)),
+            value => Err(mismatch_err(&value, "a class instance")),
+        }
+    }
+
+    /// Converts the value into a duration, failing with an error
+    /// naming the actual type of the value when it is not a `Duration`.
+    pub fn try_into_duration(self) -> PklResult<Duration> {
+        match self {
+            PklValue::Duration(d) => Ok(d),
+            value => Err(mismatch_err(&value, "a Duration")),
+        }
+    }
+
+    /// Converts the value into a data size, failing with an error
+    /// naming the actual type of the value when it is not a `DataSize`.
+    pub fn try_into_datasize(self) -> PklResult<Byte> {
+        match self {
+            PklValue::DataSize(d) => Ok(d),
+            value => Err(mismatch_err(&value, "a DataSize")),
+        }
+    }
+
+    /// Coerces the value to a boolean, accepting a `Boolean`
+    /// or a string that is either `"true"` or `"false"`.
+    ///
+    /// Unlike [`PklValue::as_bool`], any other value is an error.
+    pub fn coerce_bool(&self) -> PklResult<bool> {
+        match self {
+            PklValue::Bool(b) => Ok(*b),
+            PklValue::String(s) => s.parse().map_err(|_| coercion_err(self, "Boolean")),
+            _ => Err(coercion_err(self, "Boolean")),
+        }
+    }
+
+    /// Coerces the value to an integer, accepting an `Int`
+    /// or a string holding a decimal integer, such as `"42"`.
+    ///
+    /// Unlike [`PklValue::as_int`], any other value is an error.
+    pub fn coerce_int(&self) -> PklResult<i64> {
+        match self {
+            PklValue::Int(i) => Ok(*i),
+            PklValue::String(s) => s.parse().map_err(|_| coercion_err(self, "Int")),
+            _ => Err(coercion_err(self, "Int")),
+        }
+    }
+
+    /// Coerces the value to a float, accepting a `Float`, an `Int`
+    /// or a string holding a number, such as `"4.2"`.
+    ///
+    /// Unlike [`PklValue::as_float`], any other value is an error.
+    pub fn coerce_float(&self) -> PklResult<f64> {
+        match self {
+            PklValue::Float(f) => Ok(*f),
+            PklValue::Int(i) => Ok(*i as f64),
+            PklValue::String(s) => s.parse().map_err(|_| coercion_err(self, "Float")),
+            _ => Err(coercion_err(self, "Float")),
+        }
+    }
+}
+
+/// Returns whether one of `values` is structurally equal to `value`, see [`PklValue::structurally_eq`].
+pub(crate) fn contains_structurally(values: &[PklValue], value: &PklValue) -> bool {
+    values.iter().any(|element| element.structurally_eq(value))
+}
+
+/// Returns whether two maps hold structurally equal values under the same keys, whatever their order.
+fn fields_structurally_eq(a: &IndexMap<String, PklValue>, b: &IndexMap<String, PklValue>) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .all(|(key, value)| b.get(key).is_some_and(|other| value.structurally_eq(other)))
+}
+
+/// Returns the type shared by values, `Nothing` when there are none
+/// and `Mixed` when they are of different types.
+fn common_type<'a>(mut values: impl Iterator<Item = &'a PklValue>) -> PklType {
+    let Some(first) = values.next().map(PklValue::infer_type) else {
+        return PklType::Basic("Nothing".to_owned());
+    };
+
+    if values.all(|value| value.infer_type() == first) {
+        first
+    } else {
+        PklType::Basic("Mixed".to_owned())
+    }
+}
+
+fn coercion_err(value: &PklValue, target: &str) -> PklError {
+    let found = match value {
+        PklValue::String(s) => format!("String \"{s}\""),
+        value => value.describe_type(),
+    };
+
+    PklError::WithoutContext(format!("Cannot coerce {found} to {target}"), None)
+}
+
+fn mismatch_err(value: &PklValue, expected: &str) -> PklError {
+    PklError::WithoutContext(
+        format!(
+            "Expected {expected}, found a value of type {}",
+            value.get_type()
+        ),
+        None,
+    )
+}
+
+fn map_fields_numbers(
+    fields: IndexMap<String, PklValue>,
+    action: &impl Fn(PklValue) -> PklValue,
+) -> IndexMap<String, PklValue> {
+    fields
+        .into_iter()
+        .map(|(name, value)| (name, value.map_numbers(action)))
+        .collect()
+}
+
+fn erase_fields_classes(fields: IndexMap<String, PklValue>) -> IndexMap<String, PklValue> {
+    fields
+        .into_iter()
+        .map(|(name, value)| (name, value.erase_classes()))
+        .collect()
+}
+
+fn merge_fields(fields: &mut IndexMap<String, PklValue>, other_fields: IndexMap<String, PklValue>) {
+    for (name, other_value) in other_fields {
+        match fields.get_mut(&name) {
+            Some(value) => *value = std::mem::take(value).deep_merge(other_value),
+            None => {
+                fields.insert(name, other_value);
+            }
+        }
+    }
+}
+
+/// Approximates the memory used by the names and values of fields, in bytes.
+fn fields_size_bytes(fields: &IndexMap<String, PklValue>) -> usize {
+    fields
+        .iter()
+        .map(|(name, value)| mem::size_of::<String>() + name.capacity() + value.approx_size_bytes())
+        .sum()
 }
 
 impl From<bool> for PklValue {
@@ -226,14 +719,14 @@ impl From<Vec<PklValue>> for PklValue {
     }
 }
 
-impl From<HashMap<String, PklValue>> for PklValue {
-    fn from(value: HashMap<String, PklValue>) -> Self {
+impl From<IndexMap<String, PklValue>> for PklValue {
+    fn from(value: IndexMap<String, PklValue>) -> Self {
         PklValue::Object(value)
     }
 }
 
-impl From<(String, HashMap<String, PklValue>)> for PklValue {
-    fn from(value: (String, HashMap<String, PklValue>)) -> Self {
+impl From<(String, IndexMap<String, PklValue>)> for PklValue {
+    fn from(value: (String, IndexMap<String, PklValue>)) -> Self {
         PklValue::ClassInstance(value.0, value.1)
     }
 }
@@ -255,3 +748,271 @@ impl From<()> for PklValue {
         PklValue::Null
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{eval, parse, parse_err},
+        PklValue,
+    };
+
+    #[test]
+    fn describe_type_renders_element_types() {
+        assert_eq!(eval("List(1, 2)").describe_type(), "List<Int>");
+        assert_eq!(eval(r#"List(1, "a")"#).describe_type(), "List<Mixed>");
+        assert_eq!(eval("List()").describe_type(), "List<Nothing>");
+        assert_eq!(
+            eval("List(List(1), List(2))").describe_type(),
+            "List<List<Int>>"
+        );
+        assert_eq!(eval("Set(1.5)").describe_type(), "Set<Float>");
+
+        let listing = PklValue::Listing(vec![PklValue::Bool(true)]);
+        assert_eq!(listing.describe_type(), "Listing<Boolean>");
+        let mapping = PklValue::Mapping([("a".to_owned(), PklValue::Int(1))].into());
+        assert_eq!(mapping.describe_type(), "Mapping<String, Int>");
+        let instance = PklValue::ClassInstance("Person".to_owned(), Default::default());
+        assert_eq!(instance.describe_type(), "Person");
+    }
+
+    #[test]
+    fn type_mismatches_describe_the_found_type() {
+        let error = parse_err("x: String = List(1, 2)");
+        assert!(error.msg().contains("List<Int>"), "{}", error.msg());
+    }
+
+    #[test]
+    fn deep_merge_combines_nested_objects() {
+        let object = |fields: &[(&str, PklValue)]| {
+            PklValue::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_owned()))
+                    .collect(),
+            )
+        };
+
+        let base = object(&[
+            (
+                "a",
+                object(&[("x", PklValue::Int(1)), ("y", PklValue::Int(2))]),
+            ),
+            (
+                "l",
+                PklValue::List(vec![PklValue::Int(1), PklValue::Int(2)]),
+            ),
+        ]);
+        let other = object(&[
+            ("a", object(&[("y", PklValue::Int(3))])),
+            ("l", PklValue::List(vec![PklValue::Int(3)])),
+        ]);
+
+        assert_eq!(
+            base.deep_merge(other),
+            object(&[
+                (
+                    "a",
+                    object(&[("x", PklValue::Int(1)), ("y", PklValue::Int(3))])
+                ),
+                ("l", PklValue::List(vec![PklValue::Int(3)])),
+            ])
+        );
+    }
+
+    #[test]
+    fn numbers_are_canonicalized_recursively() {
+        let nested = |a: PklValue, b: PklValue, c: PklValue| {
+            PklValue::Object(
+                [
+                    ("a".to_owned(), a),
+                    ("list".to_owned(), PklValue::List(vec![b, c])),
+                ]
+                .into_iter()
+                .collect(),
+            )
+        };
+        let mixed = nested(PklValue::Int(2), PklValue::Float(2.0), PklValue::Float(2.5));
+
+        assert_eq!(
+            mixed.to_owned().canonicalize_numbers(),
+            nested(PklValue::Int(2), PklValue::Int(2), PklValue::Float(2.5))
+        );
+        assert_eq!(
+            mixed.ints_to_floats(),
+            nested(
+                PklValue::Float(2.0),
+                PklValue::Float(2.0),
+                PklValue::Float(2.5)
+            )
+        );
+        assert_eq!(
+            PklValue::Float(1e19).canonicalize_numbers(),
+            PklValue::Float(1e19)
+        );
+    }
+
+    #[test]
+    fn ints_are_downcast_when_in_range() {
+        assert_eq!(PklValue::Int(42).as_u32(), Some(42));
+        assert_eq!(PklValue::Int(42).as_usize(), Some(42));
+        assert_eq!(PklValue::Int(-42).as_i32(), Some(-42));
+
+        assert_eq!(PklValue::Int(-1).as_u32(), None);
+        assert_eq!(PklValue::Int(-1).as_usize(), None);
+        assert_eq!(PklValue::Int(i64::from(i32::MAX) + 1).as_i32(), None);
+        assert_eq!(PklValue::Int(1 << 32).as_u32(), None);
+        assert_eq!(PklValue::Float(1.0).as_i32(), None);
+    }
+
+    #[test]
+    fn list_element_type_is_only_given_for_homogeneous_lists() {
+        let element_type = |expr: &str| eval(expr).list_element_type().map(|t| t.to_string());
+
+        assert_eq!(element_type("List(1, 2)").as_deref(), Some("Int"));
+        assert_eq!(
+            element_type("List(List(1), List(2))").as_deref(),
+            Some("List<Int>")
+        );
+        assert_eq!(element_type("List(1, \"a\")"), None);
+        assert_eq!(element_type("List()"), None);
+        assert_eq!(element_type("Set(1, 2)"), None);
+    }
+
+    #[test]
+    fn lists_are_indexed_by_int_and_objects_by_string() {
+        let list = eval("List(\"a\", \"b\")");
+        let string = |s: &str| PklValue::String(s.to_owned());
+
+        assert_eq!(list.index(&PklValue::Int(1)), Some(&string("b")));
+        assert_eq!(list.index(&PklValue::Int(2)), None);
+        assert_eq!(list.index(&PklValue::Int(-1)), None);
+        assert_eq!(list.index(&string("a")), None);
+
+        let object = PklValue::Object([("name".to_owned(), string("x"))].into_iter().collect());
+        assert_eq!(object.index(&string("name")), Some(&string("x")));
+        assert_eq!(object.index(&string("missing")), None);
+        assert_eq!(object.index(&PklValue::Int(0)), None);
+    }
+
+    #[test]
+    fn walk_visits_nested_values_depth_first() {
+        let pkl = parse("x {\n  a = List(1, List(2, 3))\n  b {\n    c = \"s\"\n  }\n}");
+
+        let mut visited = Vec::new();
+        pkl.get_value("x").unwrap().walk(&mut |value| {
+            visited.push(match value {
+                PklValue::Object(_) => "object".to_owned(),
+                PklValue::List(_) => "list".to_owned(),
+                PklValue::Int(i) => i.to_string(),
+                PklValue::String(s) => s.to_owned(),
+                other => panic!("unexpected value {other:?}"),
+            })
+        });
+        assert_eq!(
+            visited,
+            ["object", "list", "1", "list", "2", "3", "object", "s"]
+        );
+    }
+
+    #[test]
+    fn approx_size_bytes_grows_with_the_content_of_a_value() {
+        let value_size = std::mem::size_of::<PklValue>();
+        assert_eq!(PklValue::Int(1).approx_size_bytes(), value_size);
+
+        let small = PklValue::List((0..10).map(PklValue::Int).collect());
+        let large = PklValue::List((0..1000).map(PklValue::Int).collect());
+        assert_eq!(small.approx_size_bytes(), 11 * value_size);
+        assert_eq!(large.approx_size_bytes(), 1001 * value_size);
+
+        let string = PklValue::String("x".repeat(100));
+        assert!(string.approx_size_bytes() >= value_size + 100);
+
+        let nested = PklValue::List(vec![string.clone(), string]);
+        assert!(nested.approx_size_bytes() >= 3 * value_size + 200);
+    }
+
+    #[test]
+    fn coercions_accept_native_values_and_parseable_strings() {
+        let string = |s: &str| PklValue::String(s.to_owned());
+
+        assert_eq!(string("42").coerce_int().ok(), Some(42));
+        assert_eq!(PklValue::Int(7).coerce_int().ok(), Some(7));
+        assert_eq!(string("true").coerce_bool().ok(), Some(true));
+        assert_eq!(string("4.2").coerce_float().ok(), Some(4.2));
+        assert_eq!(PklValue::Int(2).coerce_float().ok(), Some(2.0));
+
+        assert_eq!(
+            string("forty-two").coerce_int().err().unwrap().msg(),
+            "Cannot coerce String \"forty-two\" to Int"
+        );
+        assert_eq!(
+            PklValue::Float(1.5).coerce_int().err().unwrap().msg(),
+            "Cannot coerce Float to Int"
+        );
+        assert_eq!(
+            string("yes").coerce_bool().err().unwrap().msg(),
+            "Cannot coerce String \"yes\" to Boolean"
+        );
+    }
+
+    #[test]
+    fn structural_equality_applies_at_any_depth() {
+        assert_eq!(
+            eval("List(Set(1, 2)) == List(Set(2, 1))"),
+            PklValue::Bool(true)
+        );
+        assert_eq!(eval("List(1, 2) == List(2, 1)"), PklValue::Bool(false));
+        assert_eq!(
+            eval("List(List(1)) == List(List(1.0))"),
+            PklValue::Bool(true)
+        );
+
+        let pkl = parse(
+            "o {\n  a = 1\n  b = Set(1, 2)\n}\np {\n  b = Set(2, 1)\n  a = 1.0\n}\nx = o == p",
+        );
+        assert_eq!(pkl.get_value("x"), Some(PklValue::Bool(true)));
+    }
+
+    #[test]
+    fn try_into_conversions_name_the_actual_type() {
+        assert_eq!(PklValue::Int(1).try_into_int().ok(), Some(1));
+        assert_eq!(
+            PklValue::List(vec![PklValue::Bool(true)])
+                .try_into_list()
+                .ok(),
+            Some(vec![PklValue::Bool(true)])
+        );
+
+        let error = PklValue::Int(1).try_into_string().err().unwrap();
+        assert_eq!(error.msg(), "Expected a String, found a value of type Int");
+
+        let error = PklValue::String("x".to_owned())
+            .try_into_object()
+            .err()
+            .unwrap();
+        assert!(
+            error.msg().ends_with("found a value of type String"),
+            "{}",
+            error.msg()
+        );
+
+        let error = PklValue::Null.try_into_list().err().unwrap();
+        assert!(
+            error.msg().ends_with("found a value of type Null"),
+            "{}",
+            error.msg()
+        );
+    }
+
+    #[test]
+    fn as_str_borrows_the_inner_string() {
+        let value = PklValue::String("pkl".to_owned());
+        let inner = value.as_string().unwrap();
+        assert!(std::ptr::eq(value.as_str().unwrap(), inner.as_str()));
+        assert_eq!(value.as_str_or_err().ok(), Some("pkl"));
+
+        assert_eq!(PklValue::Int(1).as_str(), None);
+        let error = PklValue::Int(1).as_str_or_err().err().unwrap();
+        assert_eq!(error.msg(), "Expected a String, found a value of type Int");
+    }
+}
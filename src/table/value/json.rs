@@ -0,0 +1,155 @@
+use super::PklValue;
+use crate::{PklError, PklResult};
+use std::fmt::Write;
+
+/// How floats JSON cannot represent, that is `NaN` and `Infinity`, are serialized.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NonFiniteFloatPolicy {
+    /// Serialization fails.
+    #[default]
+    Error,
+    /// The float is serialized as `null`.
+    Null,
+    /// The float is serialized as a string, such as `"NaN"` or `"-Infinity"`.
+    String,
+}
+
+impl PklValue {
+    /// Serializes the value to a JSON string.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - How `NaN` and `Infinity` floats are serialized.
+    ///
+    /// # Returns
+    ///
+    /// A `PklResult` containing the JSON string or an error message if the value cannot be represented in JSON.
+    pub fn to_json(&self, policy: NonFiniteFloatPolicy) -> PklResult<String> {
+        let mut output = String::new();
+        write_json(&mut output, self, policy)?;
+        Ok(output)
+    }
+}
+
+fn write_json(
+    output: &mut String,
+    value: &PklValue,
+    policy: NonFiniteFloatPolicy,
+) -> PklResult<()> {
+    match value {
+        PklValue::Null => output.push_str("null"),
+        PklValue::Bool(b) => output.push_str(if *b { "true" } else { "false" }),
+        PklValue::Int(i) => output.push_str(&i.to_string()),
+        PklValue::Float(f) if f.is_finite() => output.push_str(&format!("{:?}", f)),
+        PklValue::Float(f) => match policy {
+            NonFiniteFloatPolicy::Error => {
+                return Err(PklError::WithoutContext(
+                    format!("Cannot serialize {} to JSON", pkl_float_name(*f)),
+                    None,
+                ))
+            }
+            NonFiniteFloatPolicy::Null => output.push_str("null"),
+            NonFiniteFloatPolicy::String => write_json_string(output, pkl_float_name(*f)),
+        },
+        PklValue::String(s) => write_json_string(output, s),
+        PklValue::List(elements) | PklValue::Set(elements) | PklValue::Listing(elements) => {
+            output.push('[');
+            for (i, element) in elements.iter().enumerate() {
+                if i != 0 {
+                    output.push(',');
+                }
+                write_json(output, element, policy)?;
+            }
+            output.push(']');
+        }
+        PklValue::Object(fields)
+        | PklValue::ClassInstance(_, fields)
+        | PklValue::Mapping(fields) => write_json_object(output, fields.iter(), policy)?,
+        PklValue::Duration(_) | PklValue::DataSize(_) => {
+            return Err(PklError::WithoutContext(
+                format!(
+                    "Cannot serialize a value of type {} to JSON",
+                    value.get_type()
+                ),
+                None,
+            ))
+        }
+    };
+
+    Ok(())
+}
+
+/// Writes the given fields as a JSON object, in the order they are iterated.
+pub(crate) fn write_json_object<'a>(
+    output: &mut String,
+    fields: impl Iterator<Item = (&'a String, &'a PklValue)>,
+    policy: NonFiniteFloatPolicy,
+) -> PklResult<()> {
+    output.push('{');
+    for (i, (name, field)) in fields.enumerate() {
+        if i != 0 {
+            output.push(',');
+        }
+        write_json_string(output, name);
+        output.push(':');
+        write_json(output, field, policy)?;
+    }
+    output.push('}');
+
+    Ok(())
+}
+
+fn pkl_float_name(float: f64) -> &'static str {
+    if float.is_nan() {
+        "NaN"
+    } else if float.is_sign_negative() {
+        "-Infinity"
+    } else {
+        "Infinity"
+    }
+}
+
+fn write_json_string(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NonFiniteFloatPolicy;
+    use crate::test_utils::parse;
+
+    #[test]
+    fn nan_is_rejected_by_default() {
+        let pkl = parse("x = NaN");
+        let error = pkl.to_json(NonFiniteFloatPolicy::default()).unwrap_err();
+        assert_eq!(error.msg(), "Cannot serialize NaN to JSON");
+    }
+
+    #[test]
+    fn nan_can_be_serialized_as_null() {
+        let pkl = parse("x = NaN");
+        let json = pkl.to_json(NonFiniteFloatPolicy::Null);
+        assert_eq!(json.ok().as_deref(), Some(r#"{"x":null}"#));
+    }
+
+    #[test]
+    fn non_finite_floats_can_be_serialized_as_strings() {
+        let pkl = parse("x = NaN\ny = -Infinity");
+        let json = pkl.to_json(NonFiniteFloatPolicy::String);
+        assert_eq!(json.ok().as_deref(), Some(r#"{"x":"NaN","y":"-Infinity"}"#));
+    }
+}
@@ -0,0 +1,198 @@
+use super::PklValue;
+use indexmap::IndexMap;
+use std::fmt::Write;
+
+const INDENT: &str = "  ";
+
+/// Writes the given fields as Pkl properties, one per line, in the order they are iterated.
+///
+/// Objects and class instances are written as amended blocks, such as `name { ... }`.
+pub(crate) fn write_properties<'a>(
+    output: &mut String,
+    fields: impl Iterator<Item = (&'a String, &'a PklValue)>,
+    depth: usize,
+) {
+    for (name, value) in fields {
+        push_indent(output, depth);
+        write_identifier(output, name);
+
+        match value {
+            PklValue::Object(fields) | PklValue::ClassInstance(_, fields) => {
+                output.push(' ');
+                write_block(output, fields, depth);
+            }
+            PklValue::Listing(elements) => {
+                output.push(' ');
+                write_elements_block(output, elements, depth);
+            }
+            PklValue::Mapping(entries) => {
+                output.push(' ');
+                write_entries_block(output, entries, depth);
+            }
+            _ => {
+                output.push_str(" = ");
+                write_value(output, value, depth);
+            }
+        }
+        output.push('\n');
+    }
+}
+
+/// Writes a value interpolated in a string, strings being written as is
+/// and other values the way they would be written in Pkl.
+pub(crate) fn write_interpolation(output: &mut String, value: &PklValue) {
+    match value {
+        PklValue::String(s) => output.push_str(s),
+        value => write_value(output, value, 0),
+    }
+}
+
+fn write_value(output: &mut String, value: &PklValue, depth: usize) {
+    match value {
+        PklValue::Null => output.push_str("null"),
+        PklValue::Bool(b) => output.push_str(if *b { "true" } else { "false" }),
+        PklValue::Int(i) => output.push_str(&i.to_string()),
+        PklValue::Float(f) => write_float(output, *f),
+        PklValue::String(s) => write_string(output, s),
+        PklValue::List(elements) | PklValue::Set(elements) => {
+            let _ = write!(output, "{}(", value.get_type());
+            for (i, element) in elements.iter().enumerate() {
+                if i != 0 {
+                    output.push_str(", ");
+                }
+                write_value(output, element, depth);
+            }
+            output.push(')');
+        }
+        PklValue::Object(fields) => {
+            output.push_str("new ");
+            write_block(output, fields, depth);
+        }
+        PklValue::Listing(elements) => {
+            output.push_str("new Listing ");
+            write_elements_block(output, elements, depth);
+        }
+        PklValue::Mapping(entries) => {
+            output.push_str("new Mapping ");
+            write_entries_block(output, entries, depth);
+        }
+        PklValue::ClassInstance(name, fields) => {
+            let _ = write!(output, "new {} ", name);
+            write_block(output, fields, depth);
+        }
+        PklValue::Duration(duration) => {
+            write_value(output, duration.value(), depth);
+            let _ = write!(output, ".{}", duration.unit);
+        }
+        PklValue::DataSize(byte) => {
+            write_value(output, byte.value(), depth);
+            let _ = write!(output, ".{}", byte.unit);
+        }
+    }
+}
+
+fn write_block(output: &mut String, fields: &IndexMap<String, PklValue>, depth: usize) {
+    if fields.is_empty() {
+        output.push_str("{}");
+        return;
+    }
+
+    output.push_str("{\n");
+    write_properties(output, fields.iter(), depth + 1);
+    push_indent(output, depth);
+    output.push('}');
+}
+
+fn write_elements_block(output: &mut String, elements: &[PklValue], depth: usize) {
+    if elements.is_empty() {
+        output.push_str("{}");
+        return;
+    }
+
+    output.push_str("{\n");
+    for element in elements {
+        push_indent(output, depth + 1);
+        write_value(output, element, depth + 1);
+        output.push('\n');
+    }
+    push_indent(output, depth);
+    output.push('}');
+}
+
+fn write_entries_block(output: &mut String, entries: &IndexMap<String, PklValue>, depth: usize) {
+    if entries.is_empty() {
+        output.push_str("{}");
+        return;
+    }
+
+    output.push_str("{\n");
+    for (key, value) in entries {
+        push_indent(output, depth + 1);
+        output.push('[');
+        write_string(output, key);
+        output.push_str("] = ");
+        write_value(output, value, depth + 1);
+        output.push('\n');
+    }
+    push_indent(output, depth);
+    output.push('}');
+}
+
+/// Writes a float the way Pkl does, always with a decimal point and without
+/// trailing zeros, using the scientific notation, such as `1.0E20`,
+/// for magnitudes below `1.0E-3` or from `1.0E7` on.
+pub(crate) fn write_float(output: &mut String, float: f64) {
+    if float.is_nan() {
+        output.push_str("NaN");
+    } else if float.is_infinite() {
+        output.push_str(if float.is_sign_negative() {
+            "-Infinity"
+        } else {
+            "Infinity"
+        });
+    } else if float == 0.0 || (1e-3..1e7).contains(&float.abs()) {
+        let _ = write!(output, "{:?}", float);
+    } else {
+        let scientific = format!("{:e}", float);
+        let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+
+        output.push_str(mantissa);
+        if !mantissa.contains('.') {
+            output.push_str(".0");
+        }
+        output.push('E');
+        output.push_str(exponent);
+    }
+}
+
+/// Writes a name as is when it is a valid identifier, otherwise wrapped in backticks.
+fn write_identifier(output: &mut String, name: &str) {
+    use crate::lexer::IsValidPkl;
+
+    if name.is_valid_pkl_id() {
+        output.push_str(name);
+    } else {
+        let _ = write!(output, "`{}`", name);
+    }
+}
+
+fn write_string(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+fn push_indent(output: &mut String, depth: usize) {
+    for _ in 0..depth {
+        output.push_str(INDENT);
+    }
+}
@@ -0,0 +1,45 @@
+//! Helpers shared by the unit tests of the crate,
+//! errors being reported by their message as `PklError` is not `Debug`.
+
+use crate::{Pkl, PklError, PklValue};
+
+/// Parses a source into a new `Pkl` instance, panicking on error.
+pub(crate) fn parse(source: &str) -> Pkl {
+    let mut pkl = Pkl::new();
+    if let Err(e) = pkl.parse(source) {
+        panic!("parsing {source:?} failed: {}", e.msg());
+    }
+    pkl
+}
+
+/// Parses a source expected to fail, returning the error.
+pub(crate) fn parse_err(source: &str) -> PklError {
+    match Pkl::new().parse(source) {
+        Ok(()) => panic!("parsing {source:?} should have failed"),
+        Err(e) => e,
+    }
+}
+
+/// Evaluates an expression without any member in scope, panicking on error.
+pub(crate) fn eval(expr: &str) -> PklValue {
+    match Pkl::new().evaluate_in_scope(expr, &[]) {
+        Ok(value) => value,
+        Err(e) => panic!("evaluating {expr:?} failed: {}", e.msg()),
+    }
+}
+
+/// Evaluates an expression expected to fail, returning the error.
+pub(crate) fn eval_err(expr: &str) -> PklError {
+    match Pkl::new().evaluate_in_scope(expr, &[]) {
+        Ok(value) => panic!("evaluating {expr:?} should have failed, got {value:?}"),
+        Err(e) => e,
+    }
+}
+
+/// Returns the part of `source` the error points at.
+pub(crate) fn error_slice<'a>(source: &'a str, error: &PklError) -> &'a str {
+    error
+        .span()
+        .and_then(|span| source.get(span))
+        .unwrap_or_default()
+}
@@ -28,20 +28,20 @@ macro_rules! generate_method {
         $(
             if stringify!($arg_type) == "Number" {
                 if args[$arg_index].get_type() != "Float" && args[$arg_index].get_type() != "Int" {
-                    return Err((
+                    return Err(crate::PklError::type_mismatch(
                         format!(
                             "{} method expects argument at index {} to be of type Number, but found {}",
                             name, $arg_index, args[$arg_index].get_type()
                         ),
-                        $range).into());
+                        $range));
                 }
             } else if args[$arg_index].get_type() != stringify!($arg_type) {
-                return Err((
+                return Err(crate::PklError::type_mismatch(
                     format!(
                         "{} method expects argument at index {} to be of type {}, but found {}",
                         name, $arg_index, stringify!($arg_type), args[$arg_index].get_type()
                     ),
-                    $range).into());
+                    $range));
             }
         )+
 
@@ -49,12 +49,12 @@ macro_rules! generate_method {
             $(
                 match &args[$arg_index] {
                     PklValue::$arg_type(value) => value.to_owned(),
-                    _ => return Err((
+                    _ => return Err(crate::PklError::type_mismatch(
                         format!(
                             "{} method expects argument at index {} to be of type {}, but found {}",
                             name, $arg_index, stringify!($arg_type), args[$arg_index].get_type()
                         ),
-                        $range).into()),
+                        $range)),
                 }
             ),+
         );
@@ -97,25 +97,25 @@ macro_rules! generate_method {
 
         let mut args_tuple: [f64; $args_number] = [0.0; $args_number];
 
-        for arg_number in 0..=number_of_args {
+        for arg_number in 0..number_of_args {
             if args[arg_number].get_type() != "Float" && args[arg_number].get_type() != "Int" {
-                return Err((
+                return Err(crate::PklError::type_mismatch(
                     format!(
                         "{} method expects argument at index {} to be of type Number, but found {}",
                         name, arg_number, args[arg_number].get_type()
                     ),
-                    $range).into());
+                    $range));
             }
 
             args_tuple[arg_number] = match &args[arg_number] {
                 PklValue::Float(value) => *value,
                 PklValue::Int(value) => *value as f64,
-                _ => return Err((
+                _ => return Err(crate::PklError::type_mismatch(
                     format!(
                         "{} method expects argument at index {} to be of type Number, but found {}",
                         name, arg_number, args[arg_number].get_type()
                     ),
-                    $range).into()),
+                    $range)),
             };
         }
         $action(args_tuple)
@@ -0,0 +1,55 @@
+//! Runs the `new-pkl` binary on small sources written to the temporary directory.
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+/// Writes `content` to a file of the temporary directory, returning its path.
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("pkl_fast_cli_{}_{name}", std::process::id()));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn valid_sources_are_printed() {
+    let path = temp_file("valid.pkl", "b = 2\na = 1 + 1\n");
+    let output = Command::new(env!("CARGO_BIN_EXE_new-pkl"))
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "a = 2\nb = 2\n");
+}
+
+#[test]
+fn malformed_sources_are_reported_with_their_location() {
+    let path = temp_file("malformed.pkl", "a = 1\nb = \"x\" + 1\n");
+    let output = Command::new(env!("CARGO_BIN_EXE_new-pkl"))
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Operator `+` cannot be applied to operands of type String and Int"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains(&format!("{}:2:", path.display())),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn a_missing_argument_prints_the_usage() {
+    let output = Command::new(env!("CARGO_BIN_EXE_new-pkl"))
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Usage: new-pkl <file.pkl>\n"
+    );
+}